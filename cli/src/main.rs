@@ -10,8 +10,10 @@ use std::{
 use anyhow::Context as _;
 use clap::{Parser, Subcommand};
 use divvun_fst::speller::HfstSpeller;
-use divvun_fst::transducer::TransducerLoader;
 use divvun_fst::transducer::hfst::HfstTransducer;
+use divvun_fst::transducer::lookup::{LookupConfig, lookup_with_config};
+use divvun_fst::transducer::thfst::MmapThfstTransducer;
+use divvun_fst::transducer::{Transducer, TransducerLoader};
 use divvun_fst::types::Weight;
 use divvun_fst::vfs::Fs;
 use serde::Serialize;
@@ -21,8 +23,13 @@ use divvun_fst::{
         SpellerArchive, boxf::BoxSpellerArchive, boxf::ThfstBoxSpellerArchive,
         error::SpellerArchiveError, zip::ZipSpellerArchive,
     },
+    check::{CheckConfig, Diagnostic, check_text},
+    generator::{GeneratorConfig, Paradigm, ParadigmFormat, TagPattern},
     speller::{Speller, SpellerConfig, suggestion::Suggestion},
-    tokenizer::Tokenize,
+    tokenizer::{
+        markup::{Markup, prose_segments},
+        rules::Tokenizer,
+    },
 };
 
 trait OutputWriter {
@@ -312,9 +319,21 @@ enum Command {
     /// Get suggestions for provided input
     Suggest(SuggestArgs),
 
-    /// Print input in word-separated tokenized form
+    /// Print input in word-separated tokenized form, with the type of each token
     Tokenize(TokenizeArgs),
 
+    /// Check text files for spelling, repeated words and capitalisation
+    Check(CheckArgs),
+
+    /// Print the lemmas of words
+    Lemmatize(LemmatizeArgs),
+
+    /// Print the paradigms of lemmas, generated by a generator FST
+    Generate(GenerateArgs),
+
+    /// Print what a transducer writes for each input, as hfst-lookup does
+    Lookup(LookupArgs),
+
     /// Run accuracy tests against a word list
     #[cfg(feature = "accuracy")]
     Accuracy(accuracy::AccuracyArgs),
@@ -362,6 +381,11 @@ struct SuggestArgs {
     #[arg(long = "no-recase")]
     disable_recase: bool,
 
+    /// Ranks suggestions keeping the grammatical form of the input first
+    /// (needs a lexicon that analyses)
+    #[arg(long = "preserve-morphology")]
+    preserve_morphology: bool,
+
     /// Uses supplied config file
     #[arg(short = 'c', long)]
     config: Option<PathBuf>,
@@ -380,15 +404,118 @@ struct SuggestArgs {
 
 #[derive(Debug, Parser)]
 struct TokenizeArgs {
-    /// Show words only
+    /// Show words only, leaving out numbers, URLs, punctuation and the like
     #[arg(short = 'w', long = "words")]
     is_words_only: bool,
 
+    /// Markup of the input (plain, html, xml or markdown); only prose is tokenized
+    #[arg(short = 'm', long)]
+    markup: Option<Markup>,
+
+    /// BHFST or ZHFST archive whose language's tokenizer rules to follow
+    #[arg(short = 'a', long = "archive")]
+    archive_path: Option<PathBuf>,
+
     /// Text to be tokenized
     inputs: Vec<String>,
 }
 
+#[derive(Debug, Parser)]
+struct CheckArgs {
+    /// BHFST or ZHFST archive to be used
+    #[arg(short = 'a', long = "archive")]
+    archive_path: PathBuf,
+
+    /// Markup of the input (plain, html, xml or markdown); guessed from each
+    /// file's extension if not given
+    #[arg(short = 'm', long)]
+    markup: Option<Markup>,
+
+    /// Output in JSON format
+    #[arg(long)]
+    json: bool,
+
+    /// Files to be checked; standard input if none
+    inputs: Vec<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct LemmatizeArgs {
+    /// BHFST or ZHFST archive to be used
+    #[arg(short = 'a', long = "archive")]
+    archive_path: PathBuf,
+
+    /// Do not lemmatize the case variants of the words
+    #[arg(long = "no-recase")]
+    disable_recase: bool,
+
+    /// Output in JSON format
+    #[arg(long)]
+    json: bool,
+
+    /// Words to be lemmatized; one per line from standard input if none
+    inputs: Vec<String>,
+}
+
+#[derive(Debug, Parser)]
+struct GenerateArgs {
+    /// BHFST or ZHFST archive whose lexicon is a generator
+    #[arg(short = 'a', long = "archive")]
+    archive_path: Option<PathBuf>,
+
+    /// Generator transducer to use (if archive not provided), with lemma and
+    /// tags on its input side, such as giellaLT's generator-gt-norm.hfstol
+    #[arg(short = 'g', long = "generator")]
+    generator_path: Option<PathBuf>,
+
+    /// Output format (table, tsv or json)
+    #[arg(short = 'f', long, default_value = "table")]
+    format: ParadigmFormat,
+
+    /// Only generate forms whose tags match this pattern, where `*` matches
+    /// any run of characters ("+N+Sg*")
+    #[arg(short = 't', long)]
+    tags: Option<String>,
+
+    /// Maximum number of forms per lemma; every form if not given, or 0
+    #[arg(short = 'n', long)]
+    max_results: Option<usize>,
+
+    /// Lemmas to be generated; one per line from standard input if none
+    inputs: Vec<String>,
+}
+
+#[derive(Debug, Parser)]
+struct LookupArgs {
+    /// Transducer to look the inputs up in: an HFST file, or a THFST
+    /// directory
+    #[arg(short = 't', long = "transducer")]
+    transducer_path: PathBuf,
+
+    /// Maximum number of outputs per input; 0 for no limit
+    #[arg(short = 'n', long)]
+    max_results: Option<usize>,
+
+    /// Maximum weight of an output
+    #[arg(short = 'w', long)]
+    max_weight: Option<f32>,
+
+    /// Output in JSON format
+    #[arg(long)]
+    json: bool,
+
+    /// Strings to be looked up; one per line from standard input if none
+    inputs: Vec<String>,
+}
+
 fn tokenize(args: TokenizeArgs) -> anyhow::Result<()> {
+    let tokenizer = match &args.archive_path {
+        Some(path) => load_archive(path)
+            .with_context(|| format!("failed to load archive '{}'", path.display()))?
+            .tokenizer(),
+        None => Tokenizer::default(),
+    };
+
     let inputs: String = if args.inputs.is_empty() {
         eprintln!("Reading from stdin...");
         let mut buffer = String::new();
@@ -400,19 +527,319 @@ fn tokenize(args: TokenizeArgs) -> anyhow::Result<()> {
         args.inputs.into_iter().collect::<Vec<_>>().join(" ")
     };
 
-    if args.is_words_only {
-        for (index, token) in inputs.word_indices() {
-            println!("{:>4}: \"{}\"", index, token);
+    let markup = args.markup.unwrap_or_default();
+    for segment in prose_segments(&inputs, markup) {
+        let offset = segment.span.start;
+        for token in tokenizer.token_indices(&inputs[segment.span]) {
+            if args.is_words_only && !token.kind.is_word() {
+                continue;
+            }
+            println!(
+                "{:>4}: {:<12} \"{}\"",
+                offset + token.index,
+                token.kind,
+                token.text
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct FileDiagnostics<'a> {
+    path: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+fn check(args: CheckArgs) -> anyhow::Result<()> {
+    let archive = load_archive(&args.archive_path)
+        .with_context(|| format!("failed to load archive '{}'", args.archive_path.display()))?;
+    let speller = archive.speller();
+    let language = archive
+        .metadata()
+        .map_or("", |metadata| metadata.info().locale());
+
+    let inputs: Vec<(String, String, Markup)> = if args.inputs.is_empty() {
+        eprintln!("Reading from stdin...");
+        let mut buffer = String::new();
+        io::stdin()
+            .read_to_string(&mut buffer)
+            .context("failed to read from stdin")?;
+        vec![("-".into(), buffer, args.markup.unwrap_or_default())]
+    } else {
+        args.inputs
+            .iter()
+            .map(|path| {
+                let text = std::fs::read_to_string(path)
+                    .with_context(|| format!("failed to read '{}'", path.display()))?;
+                let markup = args.markup.unwrap_or_else(|| Markup::from_path(path));
+                Ok((path.display().to_string(), text, markup))
+            })
+            .collect::<anyhow::Result<_>>()?
+    };
+
+    let mut results = vec![];
+    for (path, text, markup) in &inputs {
+        let config = CheckConfig {
+            markup: *markup,
+            language: language.into(),
+            ..CheckConfig::default()
+        };
+        let diagnostics = check_text(speller.clone(), text, &config);
+        if args.json {
+            results.push(FileDiagnostics { path, diagnostics });
+            continue;
+        }
+        for d in diagnostics {
+            let before = &text[..d.span.start];
+            let line = before.matches('\n').count() + 1;
+            let column = before
+                .rsplit('\n')
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .count()
+                + 1;
+            let kind = serde_json::to_value(d.kind)?;
+            println!(
+                "{}:{}:{}: {}: \"{}\" -> [{}]",
+                path,
+                line,
+                column,
+                kind.as_str().unwrap_or_default(),
+                d.text,
+                d.suggestions
+                    .iter()
+                    .map(|s| format!("\"{}\"", s))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Lemmas {
+    word: String,
+    lemmas: Vec<Lemma>,
+}
+
+#[derive(Serialize)]
+struct Lemma {
+    lemma: String,
+    weight: Weight,
+}
+
+fn lemmatize(args: LemmatizeArgs) -> anyhow::Result<()> {
+    let archive = load_archive(&args.archive_path)
+        .with_context(|| format!("failed to load archive '{}'", args.archive_path.display()))?;
+    let speller = archive.speller();
+    let config = SpellerConfig {
+        recase: !args.disable_recase,
+        ..SpellerConfig::default()
+    };
+
+    let words: Vec<String> = if args.inputs.is_empty() {
+        eprintln!("Reading from stdin...");
+        let mut buffer = String::new();
+        io::stdin()
+            .read_to_string(&mut buffer)
+            .context("failed to read from stdin")?;
+        buffer
+            .lines()
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty())
+            .collect()
+    } else {
+        args.inputs
+    };
+
+    let mut results = vec![];
+    for word in words {
+        let lemmas = speller.clone().lemmatize_with_config(&word, &config);
+        if args.json {
+            results.push(Lemmas {
+                word,
+                lemmas: lemmas
+                    .into_iter()
+                    .map(|(lemma, weight)| Lemma {
+                        lemma: lemma.to_string(),
+                        weight,
+                    })
+                    .collect(),
+            });
+            continue;
+        }
+        if lemmas.is_empty() {
+            println!("{}\t?", word);
+        }
+        for (lemma, weight) in lemmas {
+            println!("{}\t{}\t{}", word, lemma, weight);
+        }
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    }
+
+    Ok(())
+}
+
+fn generate(args: GenerateArgs) -> anyhow::Result<()> {
+    let mut config = GeneratorConfig {
+        tags: args.tags.as_deref().map(TagPattern::new),
+        max_results: None,
+        ..GeneratorConfig::default()
+    };
+    if let Some(n) = args.max_results {
+        config.max_results = (n > 0).then_some(n);
+    }
+
+    let generate: Box<dyn Fn(&str) -> Paradigm> = if let Some(path) = &args.archive_path {
+        let speller = load_archive(path)
+            .with_context(|| format!("failed to load archive '{}'", path.display()))?
+            .speller();
+        Box::new(move |lemma| {
+            Paradigm::from_results(lemma, &speller.clone().generate_with_config(lemma, &config))
+        })
+    } else if let Some(path) = &args.generator_path {
+        let generator = HfstTransducer::from_path(&Fs, path)
+            .with_context(|| format!("failed to load generator transducer '{}'", path.display()))?;
+        Box::new(move |lemma| Paradigm::generate(&generator, lemma, &config))
+    } else {
+        anyhow::bail!(
+            "either a BHFST or ZHFST archive must be provided via --archive, or a generator via --generator"
+        );
+    };
+
+    let lemmas: Vec<String> = if args.inputs.is_empty() {
+        eprintln!("Reading from stdin...");
+        let mut buffer = String::new();
+        io::stdin()
+            .read_to_string(&mut buffer)
+            .context("failed to read from stdin")?;
+        buffer
+            .lines()
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty())
+            .collect()
+    } else {
+        args.inputs
+    };
+
+    let paradigms: Vec<Paradigm> = lemmas.iter().map(|lemma| generate(lemma)).collect();
+
+    match args.format {
+        ParadigmFormat::Json => println!("{}", serde_json::to_string_pretty(&paradigms)?),
+        format => {
+            for (index, paradigm) in paradigms.iter().enumerate() {
+                if paradigm.is_empty() {
+                    eprintln!("{}: no forms generated", paradigm.lemma);
+                    continue;
+                }
+                if index > 0 && format == ParadigmFormat::Table {
+                    println!();
+                }
+                print!("{}", paradigm.render(format));
+            }
         }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Lookups {
+    input: String,
+    outputs: Vec<LookupOutput>,
+}
+
+#[derive(Serialize)]
+struct LookupOutput {
+    output: String,
+    weight: Weight,
+}
+
+fn lookup(args: LookupArgs) -> anyhow::Result<()> {
+    let mut config = LookupConfig {
+        max_weight: args.max_weight.map(Weight),
+        ..LookupConfig::default()
+    };
+    if let Some(n) = args.max_results {
+        config.max_results = (n > 0).then_some(n);
+    }
+
+    let inputs: Vec<String> = if args.inputs.is_empty() {
+        eprintln!("Reading from stdin...");
+        let mut buffer = String::new();
+        io::stdin()
+            .read_to_string(&mut buffer)
+            .context("failed to read from stdin")?;
+        buffer
+            .lines()
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty())
+            .collect()
     } else {
-        for (index, token) in inputs.word_bound_indices() {
-            println!("{:>4}: \"{}\"", index, token);
+        args.inputs
+    };
+
+    let path = &args.transducer_path;
+    let context = || format!("failed to load transducer '{}'", path.display());
+    let results: Vec<Lookups> = if path.is_dir() {
+        let transducer = MmapThfstTransducer::from_path(&Fs, path).with_context(context)?;
+        lookup_all(&transducer, inputs, &config)
+    } else {
+        let transducer = HfstTransducer::from_path(&Fs, path).with_context(context)?;
+        lookup_all(&transducer, inputs, &config)
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        return Ok(());
+    }
+    // The format of hfst-lookup: one line per output and a blank line after
+    // each input, with "+?" and an infinite weight for no output.
+    for Lookups { input, outputs } in results {
+        if outputs.is_empty() {
+            println!("{}\t{}+?\tinf", input, input);
+        }
+        for LookupOutput { output, weight } in outputs {
+            println!("{}\t{}\t{}", input, output, weight);
         }
+        println!();
     }
 
     Ok(())
 }
 
+fn lookup_all<T: Transducer>(
+    transducer: &T,
+    inputs: Vec<String>,
+    config: &LookupConfig,
+) -> Vec<Lookups> {
+    inputs
+        .into_iter()
+        .map(|input| Lookups {
+            outputs: lookup_with_config(transducer, &input, config)
+                .into_iter()
+                .map(|(output, weight)| LookupOutput {
+                    output: output.to_string(),
+                    weight,
+                })
+                .collect(),
+            input,
+        })
+        .collect()
+}
+
 fn load_archive(path: &Path) -> Result<Box<dyn SpellerArchive>, SpellerArchiveError> {
     match path.extension() {
         Some(ext) if ext == "bhfst" => {
@@ -474,6 +901,9 @@ fn suggest(args: SuggestArgs) -> anyhow::Result<()> {
     if args.disable_recase {
         suggest_cfg.recase = false;
     }
+    if args.preserve_morphology {
+        suggest_cfg.morphology.get_or_insert_with(Default::default);
+    }
     suggest_cfg.completion_marker = args.continuation_marker.clone();
     suggest_cfg.verbose = args.verbose;
     if let Some(v) = args.nbest {
@@ -566,6 +996,10 @@ fn run_cli() -> anyhow::Result<()> {
         None => Ok(()),
         Some(Command::Suggest(args)) => suggest(args),
         Some(Command::Tokenize(args)) => tokenize(args),
+        Some(Command::Check(args)) => check(args),
+        Some(Command::Lemmatize(args)) => lemmatize(args),
+        Some(Command::Generate(args)) => generate(args),
+        Some(Command::Lookup(args)) => lookup(args),
         #[cfg(feature = "accuracy")]
         Some(Command::Accuracy(args)) => accuracy::run(args),
     }
@@ -4,14 +4,22 @@ use std::path::{Path, PathBuf};
 
 use box_format::{BoxPath, Compression, CompressionConfig, HashMap as BoxHashMap, sync::BoxWriter};
 use divvun_fst::archive::{
-    SpellerArchive, boxf::BoxSpellerArchive, boxf::ThfstBoxSpellerArchive, zip::ZipSpellerArchive,
+    SpellerArchive, boxf::BoxSpellerArchive, boxf::ThfstBoxSpellerArchive, meta::SpellerMetadata,
+    zip::ZipSpellerArchive,
 };
 use divvun_fst::transducer::{
-    TransducerLoader,
+    Transducer, TransducerLoader, att,
     convert::ConvertFile,
+    errmodel::EditDistance,
     hfst::HfstTransducer,
+    hunspell::{Dictionary, ErrorModelConfig},
+    optimize::{OptimizeConfig, OptimizeReport, optimize},
+    paths::{PathOrder, PathsConfig, paths},
+    rational,
     thfst::{self, MmapThfstTransducer},
+    wordlist::WordList,
 };
+use divvun_fst::types::Weight;
 
 #[derive(Debug, Parser)]
 #[command(
@@ -22,6 +30,15 @@ enum Opts {
     /// Convert an HFST file to THFST
     HfstToThfst { from: PathBuf },
 
+    /// Convert an AT&T text transducer to THFST
+    AttToThfst {
+        from: PathBuf,
+
+        /// Write HFST optimised-lookup instead
+        #[arg(long)]
+        hfst: bool,
+    },
+
     /// Convert a ZHFST file to BHFST
     ZhfstToBhfst { from: PathBuf },
 
@@ -32,8 +49,136 @@ enum Opts {
         output: PathBuf,
     },
 
+    /// Compile a word list, one word per line with an optional tab and count,
+    /// into a weighted acceptor THFST
+    WordlistToThfst { wordlist: PathBuf },
+
+    /// Compile a word list into a BHFST speller, with an edit distance error
+    /// model unless one is given
+    WordlistToBhfst {
+        wordlist: PathBuf,
+        output: PathBuf,
+
+        /// THFST error model to use instead
+        #[arg(long)]
+        errmodel: Option<PathBuf>,
+
+        /// Maximum number of edits to a word
+        #[arg(long, default_value_t = 2)]
+        max_edits: usize,
+
+        /// Weight of each edit
+        #[arg(long, default_value_t = 5.0)]
+        edit_weight: f32,
+    },
+
+    /// Import a Hunspell dictionary as a BHFST speller
+    HunspellToBhfst {
+        /// The .dic file
+        dic: PathBuf,
+        output: PathBuf,
+
+        /// The .aff file [default: the .dic file's, with .aff]
+        #[arg(long)]
+        aff: Option<PathBuf>,
+
+        /// Locale of the speller [default: the .aff file's LANG]
+        #[arg(long)]
+        locale: Option<String>,
+
+        /// Directory to keep the acceptor and error model THFST in
+        #[arg(long)]
+        thfst_dir: Option<PathBuf>,
+
+        /// Maximum number of edits to a word
+        #[arg(long, default_value_t = 2)]
+        max_edits: usize,
+    },
+
+    /// Determinise and minimise an HFST or THFST transducer into a new THFST,
+    /// reporting how its size changed
+    Optimize {
+        /// HFST file, or THFST directory
+        path: PathBuf,
+
+        /// Where to write the THFST [default: the input's name, with
+        /// .optimized.thfst]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Only minimise, which never adds arcs, where determinising an
+        /// error model can add many
+        #[arg(long)]
+        no_determinise: bool,
+
+        /// Most states to determinise into before only minimising
+        #[arg(long, default_value_t = 1 << 20)]
+        max_states: usize,
+    },
+
+    /// Combine two HFST or THFST transducers into a THFST accepting what
+    /// either does
+    Union {
+        first: PathBuf,
+        second: PathBuf,
+        output: PathBuf,
+    },
+
+    /// Combine two HFST or THFST transducers into a THFST accepting what the
+    /// first does followed by what the second does
+    Concatenate {
+        first: PathBuf,
+        second: PathBuf,
+        output: PathBuf,
+    },
+
+    /// Compose two HFST or THFST transducers into a THFST feeding what the
+    /// first writes to the second
+    Compose {
+        first: PathBuf,
+        second: PathBuf,
+        output: PathBuf,
+    },
+
+    /// Intersect two HFST or THFST transducers into a THFST accepting what
+    /// both do
+    Intersect {
+        first: PathBuf,
+        second: PathBuf,
+        output: PathBuf,
+    },
+
     /// Print metadata for BHFST
     BhfstInfo { path: PathBuf },
+
+    /// Write the accepting paths of an HFST or THFST transducer as TSV
+    /// (input, output, weight): the cheapest first with -n, otherwise all of
+    /// them depth first
+    DumpPaths {
+        /// HFST file, or THFST directory
+        path: PathBuf,
+
+        /// Maximum number of transitions on a path
+        #[arg(long, default_value_t = 64)]
+        max_depth: usize,
+
+        /// Maximum path weight
+        #[arg(short = 'w', long)]
+        max_weight: Option<f32>,
+
+        /// Maximum number of paths to write
+        #[arg(short = 'n', long)]
+        max_results: Option<usize>,
+
+        /// Write a random sample of -n paths instead, from random walks
+        /// started with this seed
+        #[arg(long, value_name = "SEED", requires = "max_results")]
+        sample: Option<u64>,
+
+        /// Where to write the paths [default: stdout]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 const ALIGNMENT: u32 = 8;
@@ -102,6 +247,27 @@ fn convert_hfst_to_thfst(hfst_path: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn convert_att(att_path: &Path, hfst: bool) -> anyhow::Result<()> {
+    let text = std::fs::read_to_string(att_path)
+        .with_context(|| format!("failed to read '{}'", att_path.display()))?;
+    let transducer = att::parse(&text)
+        .with_context(|| format!("failed to read AT&T text '{}'", att_path.display()))?;
+    let target = att_path.with_extension(if hfst { "hfst" } else { "thfst" });
+    println!(
+        "Converting {:?} to {:?}...",
+        att_path.file_name().unwrap_or_default(),
+        target.file_name().unwrap_or_default()
+    );
+
+    if hfst {
+        HfstTransducer::convert_file(&transducer, att_path)
+    } else {
+        MmapThfstTransducer::convert_file(&transducer, att_path)
+    }
+    .with_context(|| format!("failed to write '{}'", target.display()))?;
+    Ok(())
+}
+
 fn convert_thfsts_to_bhfst(
     acceptor_path: &Path,
     errmodel_path: &Path,
@@ -123,6 +289,159 @@ fn convert_thfsts_to_bhfst(
     Ok(())
 }
 
+fn read_wordlist(path: &Path) -> anyhow::Result<WordList> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read '{}'", path.display()))?;
+    WordList::parse(&text).with_context(|| format!("failed to read word list '{}'", path.display()))
+}
+
+fn convert_wordlist_to_thfst(wordlist_path: &Path) -> anyhow::Result<()> {
+    let list = read_wordlist(wordlist_path)?;
+    let target = wordlist_path.with_extension("thfst");
+    println!(
+        "Compiling {} words from {:?} to {:?}...",
+        list.len(),
+        wordlist_path.file_name().unwrap_or_default(),
+        target.file_name().unwrap_or_default()
+    );
+    let acceptor = list.acceptor().context("failed to compile word list")?;
+    MmapThfstTransducer::convert_file(&acceptor, wordlist_path)
+        .with_context(|| format!("failed to write '{}'", target.display()))?;
+    Ok(())
+}
+
+fn convert_wordlist_to_bhfst(
+    wordlist_path: &Path,
+    output_path: &Path,
+    errmodel_path: Option<&Path>,
+    edits: EditDistance,
+) -> anyhow::Result<()> {
+    let list = read_wordlist(wordlist_path)?;
+    let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+
+    println!(
+        "Compiling {} words from {:?}...",
+        list.len(),
+        wordlist_path.file_name().unwrap_or_default()
+    );
+    let acceptor = list.acceptor().context("failed to compile word list")?;
+    let acceptor_path = dir.as_ref().join("acceptor.default.thfst");
+    MmapThfstTransducer::convert_file(&acceptor, &acceptor_path)
+        .context("failed to write acceptor")?;
+
+    // The archive finds its transducers by name.
+    let errmodel_target = dir.as_ref().join("errmodel.default.thfst");
+    match errmodel_path {
+        Some(path) => {
+            std::fs::create_dir_all(&errmodel_target).context("failed to write error model")?;
+            for name in ["alphabet", "index", "transition"] {
+                std::fs::copy(path.join(name), errmodel_target.join(name))
+                    .with_context(|| format!("failed to copy '{}'", path.join(name).display()))?;
+            }
+        }
+        None => {
+            println!(
+                "Building an error model of up to {} edits...",
+                edits.max_edits
+            );
+            let errmodel = edits
+                .error_model(&list.alphabet())
+                .context("failed to build error model")?;
+            MmapThfstTransducer::convert_file(&errmodel, &errmodel_target)
+                .context("failed to write error model")?;
+        }
+    }
+
+    write_speller_bhfst(output_path, &acceptor_path, &errmodel_target, None)
+}
+
+/// Package an acceptor and error model, named as the archive expects them,
+/// and metadata for them into a BHFST.
+fn write_speller_bhfst(
+    output_path: &Path,
+    acceptor_path: &Path,
+    errmodel_path: &Path,
+    metadata: Option<&SpellerMetadata>,
+) -> anyhow::Result<()> {
+    let mut boxfile = BoxWriter::create_with_alignment(output_path, ALIGNMENT)
+        .with_context(|| format!("failed to create box archive '{}'", output_path.display()))?;
+    insert_thfst_files(&mut boxfile, acceptor_path)?;
+    insert_thfst_files(&mut boxfile, errmodel_path)?;
+    if let Some(metadata) = metadata {
+        println!("Inserting \"meta.json\"...");
+        let json =
+            serde_json::to_string_pretty(metadata).context("failed to serialise meta.json")?;
+        boxfile
+            .insert(
+                &CompressionConfig::new(Compression::Stored),
+                BoxPath::new("meta.json").context("failed to construct meta.json path")?,
+                std::io::Cursor::new(json.into_bytes()),
+                BoxHashMap::new(),
+            )
+            .context("failed to insert meta.json")?;
+    }
+    boxfile.finish().context("failed to finalise bhfst")?;
+    println!("Wrote to {:?}.", output_path);
+
+    Ok(())
+}
+
+fn convert_hunspell_to_bhfst(
+    dic_path: &Path,
+    aff_path: &Path,
+    output_path: &Path,
+    locale: Option<&str>,
+    thfst_dir: Option<&Path>,
+    config: &ErrorModelConfig,
+) -> anyhow::Result<()> {
+    let read = |path: &Path| {
+        std::fs::read(path).with_context(|| format!("failed to read '{}'", path.display()))
+    };
+    let dictionary =
+        Dictionary::from_bytes(&read(dic_path)?, &read(aff_path)?).with_context(|| {
+            format!(
+                "failed to read Hunspell dictionary '{}'",
+                dic_path.display()
+            )
+        })?;
+    let locale = locale
+        .or(dictionary.language())
+        .or(dic_path.file_stem().and_then(|stem| stem.to_str()))
+        .context("no locale given, and the dictionary names none")?;
+
+    let temp = tempfile::tempdir().context("failed to create temporary directory")?;
+    let dir = thfst_dir.unwrap_or(temp.as_ref());
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("failed to create '{}'", dir.display()))?;
+
+    println!(
+        "Expanding {:?} by {:?}...",
+        dic_path.file_name().unwrap_or_default(),
+        aff_path.file_name().unwrap_or_default()
+    );
+    let words = dictionary.words();
+    println!("Compiling {} words...", words.len());
+    let acceptor = words.acceptor().context("failed to compile acceptor")?;
+    let acceptor_path = dir.join("acceptor.default.thfst");
+    MmapThfstTransducer::convert_file(&acceptor, &acceptor_path)
+        .context("failed to write acceptor")?;
+
+    println!("Building the error model...");
+    let errmodel = dictionary
+        .error_model(&words, config)
+        .context("failed to build error model")?;
+    let errmodel_path = dir.join("errmodel.default.thfst");
+    MmapThfstTransducer::convert_file(&errmodel, &errmodel_path)
+        .context("failed to write error model")?;
+
+    write_speller_bhfst(
+        output_path,
+        &acceptor_path,
+        &errmodel_path,
+        Some(&dictionary.metadata(locale)),
+    )
+}
+
 fn convert_zhfst_to_bhfst(zhfst_path: &Path) -> anyhow::Result<()> {
     let zhfst_path = std::fs::canonicalize(zhfst_path)
         .with_context(|| format!("failed to resolve '{}'", zhfst_path.display()))?;
@@ -144,6 +463,11 @@ fn convert_zhfst_to_bhfst(zhfst_path: &Path) -> anyhow::Result<()> {
     let mut boxfile = BoxWriter::create_with_alignment(&bhfst_path, ALIGNMENT)
         .with_context(|| format!("failed to create box archive '{}'", bhfst_path.display()))?;
 
+    let hyphenator = zhfst
+        .metadata()
+        .and_then(|m| m.hyphenator())
+        .map(|h| h.id().to_string());
+
     let meta_json = match zhfst.metadata() {
         Some(metadata) => {
             println!("Converting \"index.xml\" to \"meta.json\"...");
@@ -152,6 +476,10 @@ fn convert_zhfst_to_bhfst(zhfst_path: &Path) -> anyhow::Result<()> {
                 .set_id(metadata.acceptor().id().replace(".hfst", ".thfst"));
             m.errmodel_mut()
                 .set_id(metadata.errmodel().id().replace(".hfst", ".thfst"));
+            if let Some(h) = m.hyphenator_mut() {
+                let id = h.id().replace(".hfst", ".thfst");
+                h.set_id(id);
+            }
             Some(serde_json::to_string_pretty(&m).context("failed to serialise meta.json")?)
         }
         None => None,
@@ -165,6 +493,12 @@ fn convert_zhfst_to_bhfst(zhfst_path: &Path) -> anyhow::Result<()> {
     convert_hfst_to_thfst(&errmodel_path)?;
     insert_thfst_files(&mut boxfile, &errmodel_path.with_extension("thfst"))?;
 
+    if let Some(id) = hyphenator {
+        let hyphenator_path = dir.as_ref().join(id);
+        convert_hfst_to_thfst(&hyphenator_path)?;
+        insert_thfst_files(&mut boxfile, &hyphenator_path.with_extension("thfst"))?;
+    }
+
     if let Some(v) = meta_json {
         println!("Inserting \"meta.json\"...");
         boxfile
@@ -183,17 +517,268 @@ fn convert_zhfst_to_bhfst(zhfst_path: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn write_paths<T: Transducer>(
+    transducer: &T,
+    config: PathsConfig,
+    out: &mut dyn std::io::Write,
+) -> anyhow::Result<()> {
+    for path in paths(transducer, config) {
+        writeln!(out, "{}\t{}\t{}", path.input, path.output, path.weight.0)
+            .context("failed to write path")?;
+    }
+    out.flush().context("failed to write path")?;
+    Ok(())
+}
+
+fn dump_paths(path: &Path, config: PathsConfig, output: Option<&Path>) -> anyhow::Result<()> {
+    let mut out: Box<dyn std::io::Write> = match output {
+        Some(output) => Box::new(std::io::BufWriter::new(
+            std::fs::File::create(output)
+                .with_context(|| format!("failed to create '{}'", output.display()))?,
+        )),
+        None => Box::new(std::io::BufWriter::new(std::io::stdout().lock())),
+    };
+
+    let fs = divvun_fst::vfs::Fs;
+    if path.is_dir() {
+        let transducer = MmapThfstTransducer::from_path(&fs, path)
+            .with_context(|| format!("failed to load THFST transducer '{}'", path.display()))?;
+        write_paths(&transducer, config, &mut out)
+    } else {
+        let transducer = HfstTransducer::from_path(&fs, path)
+            .with_context(|| format!("failed to load HFST transducer '{}'", path.display()))?;
+        write_paths(&transducer, config, &mut out)
+    }
+}
+
+/// The bytes of a file, or of the files in a directory.
+fn size_on_disk(path: &Path) -> anyhow::Result<u64> {
+    let metadata =
+        std::fs::metadata(path).with_context(|| format!("failed to read '{}'", path.display()))?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+    let mut size = 0;
+    for entry in
+        std::fs::read_dir(path).with_context(|| format!("failed to read '{}'", path.display()))?
+    {
+        size += size_on_disk(&entry?.path())?;
+    }
+    Ok(size)
+}
+
+fn change(before: u64, after: u64) -> String {
+    let percent = if before == 0 {
+        0.0
+    } else {
+        (after as f64 - before as f64) / before as f64 * 100.0
+    };
+    format!("{before} -> {after} ({percent:+.1}%)")
+}
+
+fn optimize_transducer<T: Transducer>(
+    transducer: &T,
+    config: &OptimizeConfig,
+    output: &Path,
+) -> anyhow::Result<OptimizeReport> {
+    let (optimised, report) = optimize(transducer, config).context("failed to optimise")?;
+    MmapThfstTransducer::convert_file(&optimised, output)
+        .with_context(|| format!("failed to write '{}'", output.display()))?;
+    Ok(report)
+}
+
+fn optimize_file(path: &Path, output: &Path, config: &OptimizeConfig) -> anyhow::Result<()> {
+    let target = output.with_extension("thfst");
+    println!(
+        "Optimising {:?} to {:?}...",
+        path.file_name().unwrap_or_default(),
+        target.file_name().unwrap_or_default()
+    );
+
+    let fs = divvun_fst::vfs::Fs;
+    let report = if path.is_dir() {
+        let transducer = MmapThfstTransducer::from_path(&fs, path)
+            .with_context(|| format!("failed to load THFST transducer '{}'", path.display()))?;
+        optimize_transducer(&transducer, config, output)?
+    } else {
+        let transducer = HfstTransducer::from_path(&fs, path)
+            .with_context(|| format!("failed to load HFST transducer '{}'", path.display()))?;
+        optimize_transducer(&transducer, config, output)?
+    };
+
+    println!(
+        "{}",
+        if report.determinised {
+            "Determinised and minimised."
+        } else if config.determinise {
+            "Too large to determinise; minimised only."
+        } else {
+            "Minimised."
+        }
+    );
+    println!(
+        "States: {}",
+        change(report.states_before as u64, report.states_after as u64)
+    );
+    println!(
+        "Arcs: {}",
+        change(report.arcs_before as u64, report.arcs_after as u64)
+    );
+    println!(
+        "Bytes: {}",
+        change(size_on_disk(path)?, size_on_disk(&target)?)
+    );
+    Ok(())
+}
+
+/// Load a THFST directory, or an HFST file by way of THFST in `dir`.
+fn load_thfst(path: &Path, dir: &Path) -> anyhow::Result<MmapThfstTransducer> {
+    let fs = divvun_fst::vfs::Fs;
+    let thfst_path = if path.is_dir() {
+        path.to_path_buf()
+    } else {
+        let hfst = HfstTransducer::from_path(&fs, path)
+            .with_context(|| format!("failed to load HFST transducer '{}'", path.display()))?;
+        let name = path.file_name().unwrap_or_default();
+        let converted = dir.join(name);
+        MmapThfstTransducer::convert_file(&hfst, &converted)
+            .with_context(|| format!("failed to convert '{}' to THFST", path.display()))?;
+        converted.with_extension("thfst")
+    };
+    MmapThfstTransducer::from_path(&fs, &thfst_path)
+        .with_context(|| format!("failed to load THFST transducer '{}'", thfst_path.display()))
+}
+
+type Operation =
+    fn(
+        &MmapThfstTransducer,
+        &MmapThfstTransducer,
+    ) -> Result<thfst::MemoryThfstTransducer, divvun_fst::transducer::TransducerError>;
+
+fn combine(
+    name: &str,
+    operation: Operation,
+    first: &Path,
+    second: &Path,
+    output: &Path,
+) -> anyhow::Result<()> {
+    let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+    let first_dir = dir.as_ref().join("first");
+    let second_dir = dir.as_ref().join("second");
+    for dir in [&first_dir, &second_dir] {
+        std::fs::create_dir(dir).context("failed to create temporary directory")?;
+    }
+    let a = load_thfst(first, &first_dir)?;
+    let b = load_thfst(second, &second_dir)?;
+
+    let target = output.with_extension("thfst");
+    println!(
+        "Writing the {name} of {:?} and {:?} to {:?}...",
+        first.file_name().unwrap_or_default(),
+        second.file_name().unwrap_or_default(),
+        target.file_name().unwrap_or_default()
+    );
+    let combined = operation(&a, &b).with_context(|| format!("failed to build the {name}"))?;
+    MmapThfstTransducer::convert_file(&combined, output)
+        .with_context(|| format!("failed to write '{}'", target.display()))?;
+    Ok(())
+}
+
 fn run() -> anyhow::Result<()> {
     let opts = Opts::parse();
 
     match opts {
         Opts::HfstToThfst { from } => convert_hfst_to_thfst(&from),
+        Opts::AttToThfst { from, hfst } => convert_att(&from, hfst),
         Opts::ThfstsToBhfst {
             acceptor,
             errmodel,
             output,
         } => convert_thfsts_to_bhfst(&acceptor, &errmodel, &output),
         Opts::ZhfstToBhfst { from } => convert_zhfst_to_bhfst(&from),
+        Opts::WordlistToThfst { wordlist } => convert_wordlist_to_thfst(&wordlist),
+        Opts::WordlistToBhfst {
+            wordlist,
+            output,
+            errmodel,
+            max_edits,
+            edit_weight,
+        } => {
+            let edits = EditDistance {
+                max_edits,
+                substitution: Weight(edit_weight),
+                insertion: Weight(edit_weight),
+                deletion: Weight(edit_weight),
+                transposition: Weight(edit_weight),
+            };
+            convert_wordlist_to_bhfst(&wordlist, &output, errmodel.as_deref(), edits)
+        }
+        Opts::HunspellToBhfst {
+            dic,
+            output,
+            aff,
+            locale,
+            thfst_dir,
+            max_edits,
+        } => {
+            let aff = aff.unwrap_or_else(|| dic.with_extension("aff"));
+            let mut config = ErrorModelConfig::default();
+            config.edits.max_edits = max_edits;
+            convert_hunspell_to_bhfst(
+                &dic,
+                &aff,
+                &output,
+                locale.as_deref(),
+                thfst_dir.as_deref(),
+                &config,
+            )
+        }
+        Opts::Optimize {
+            path,
+            output,
+            no_determinise,
+            max_states,
+        } => {
+            // The THFST writer swaps the last extension for .thfst.
+            let output = output.unwrap_or_else(|| path.with_extension("optimized.thfst"));
+            let config = OptimizeConfig {
+                determinise: !no_determinise,
+                max_states,
+            };
+            optimize_file(&path, &output, &config)
+        }
+        Opts::Union {
+            first,
+            second,
+            output,
+        } => combine("union", rational::union, &first, &second, &output),
+        Opts::Concatenate {
+            first,
+            second,
+            output,
+        } => combine(
+            "concatenation",
+            rational::concatenate,
+            &first,
+            &second,
+            &output,
+        ),
+        Opts::Compose {
+            first,
+            second,
+            output,
+        } => combine("composition", rational::compose, &first, &second, &output),
+        Opts::Intersect {
+            first,
+            second,
+            output,
+        } => combine(
+            "intersection",
+            rational::intersect,
+            &first,
+            &second,
+            &output,
+        ),
         Opts::BhfstInfo { path } => {
             let ar: ThfstBoxSpellerArchive = BoxSpellerArchive::open(&path)
                 .with_context(|| format!("failed to open BHFST archive '{}'", path.display()))?;
@@ -203,6 +788,29 @@ fn run() -> anyhow::Result<()> {
             }
             Ok(())
         }
+        Opts::DumpPaths {
+            path,
+            max_depth,
+            max_weight,
+            max_results,
+            sample,
+            output,
+        } => {
+            // Best-first keeps every path it has yet to extend, which only
+            // pays when the cheapest few are wanted.
+            let order = match (sample, max_results) {
+                (Some(seed), _) => PathOrder::Random { seed },
+                (None, Some(_)) => PathOrder::Cheapest,
+                (None, None) => PathOrder::DepthFirst,
+            };
+            let config = PathsConfig {
+                max_depth,
+                max_weight: max_weight.map(Weight),
+                max_results,
+                order,
+            };
+            dump_paths(&path, config, output.as_deref())
+        }
     }
 }
 
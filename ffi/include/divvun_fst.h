@@ -35,6 +35,8 @@ typedef cffi_trait_object DFST_SpellerArchive;
 typedef cffi_trait_object DFST_Speller;
 typedef rust_slice_t DFST_VecSuggestion;
 typedef void* DFST_WordIndices;
+typedef void* DFST_SentenceBoundIndices;
+typedef const void* DFST_Tokenizer;
 
 // CFFI exception callback type
 typedef void (*_Nonnull cffi_exception_callback)(const uint8_t*_Nonnull msg, size_t msg_len);
@@ -52,6 +54,12 @@ rust_slice_t DFST_SpellerArchive_locale(
     DFST_SpellerArchive archive,
     cffi_exception_callback exception
 );
+// A tokenizer following the archive's tokenizer rules and knowing its
+// language's abbreviations. Free with DFST_Tokenizer_free.
+DFST_Tokenizer _Nonnull DFST_SpellerArchive_tokenizer(
+    DFST_SpellerArchive archive,
+    cffi_exception_callback exception
+);
 
 // Speller configuration (matches the Rust #[repr(C)] FfiSpellerConfig layout).
 // A zeroed reweight (all penalties 0.0) disables reweighting. Set verbose to a
@@ -89,6 +97,21 @@ DFST_VecSuggestion DFST_Speller_suggestWithConfig(
     cffi_exception_callback exception
 );
 
+// The lemmas of a word, cheapest first, in a suggestion vector: the value of
+// each entry is a lemma, and its weight that of the lemma's best analysis.
+// Case variants of the word are lemmatized too.
+DFST_VecSuggestion DFST_Speller_lemmatize(
+    DFST_Speller speller,
+    const rust_slice_t word,
+    cffi_exception_callback exception
+);
+DFST_VecSuggestion DFST_Speller_lemmatizeWithConfig(
+    DFST_Speller speller,
+    const rust_slice_t word,
+    const DFST_SpellerConfig *_Nullable config,
+    cffi_exception_callback exception
+);
+
 // Suggestion vector functions - slices passed by value
 rust_usize_t DFST_VecSuggestion_len(
     DFST_VecSuggestion suggestions,
@@ -150,11 +173,33 @@ void DFST_cstr_free(const char *_Nonnull str);
 void cffi_string_free(rust_slice_t str);
 void cffi_vec_free(rust_slice_t vec);
 
-// Word indices (tokenization) functions
+// Units offsets into a string can be counted in
+typedef uint8_t DFST_OffsetUnit;
+#define DFST_OFFSET_UNIT_BYTE ((DFST_OffsetUnit)0)
+#define DFST_OFFSET_UNIT_UTF16 ((DFST_OffsetUnit)1)
+#define DFST_OFFSET_UNIT_CODE_POINT ((DFST_OffsetUnit)2)
+
+// Word indices (tokenization) functions. Indices are in bytes, unless another
+// unit is given to DFST_WordIndices_newWithUnit (which returns NULL for an
+// unknown unit).
 DFST_WordIndices _Nonnull DFST_WordIndices_new(const char*_Nonnull utf8_string);
+DFST_WordIndices _Nullable DFST_WordIndices_newWithUnit(const char*_Nonnull utf8_string, DFST_OffsetUnit unit);
 uint8_t DFST_WordIndices_next(DFST_WordIndices _Nonnull iterator, uint64_t*_Nonnull out_index, char*_Nonnull*_Nonnull out_string);
 void DFST_WordIndices_free(DFST_WordIndices _Nonnull iterator);
 
+// Sentence boundary (segmentation) functions. The iterator borrows utf8_string,
+// which must outlive it. Every byte of the input belongs to exactly one sentence.
+DFST_SentenceBoundIndices _Nonnull DFST_SentenceBoundIndices_new(const char*_Nonnull utf8_string);
+DFST_SentenceBoundIndices _Nonnull DFST_SentenceBoundIndices_newWithLanguage(const char*_Nonnull utf8_string, const char*_Nonnull language);
+uint8_t DFST_SentenceBoundIndices_next(DFST_SentenceBoundIndices _Nonnull iterator, uint64_t*_Nonnull out_index, char*_Nonnull*_Nonnull out_string);
+void DFST_SentenceBoundIndices_free(DFST_SentenceBoundIndices _Nonnull iterator);
+
+// The same iterators, following the rules of a tokenizer from
+// DFST_SpellerArchive_tokenizer. They borrow utf8_string but not the tokenizer.
+DFST_WordIndices _Nullable DFST_Tokenizer_wordIndices(DFST_Tokenizer _Nonnull tokenizer, const char*_Nonnull utf8_string, DFST_OffsetUnit unit);
+DFST_SentenceBoundIndices _Nonnull DFST_Tokenizer_sentenceBoundIndices(DFST_Tokenizer _Nonnull tokenizer, const char*_Nonnull utf8_string);
+void DFST_Tokenizer_free(DFST_Tokenizer _Nonnull tokenizer);
+
 // Tokenizer cursor context types
 typedef struct CCow_s {
     const uint8_t *_Nullable ptr;
@@ -184,6 +229,25 @@ CWordContext DFST_Tokenizer_cursorContext(
 );
 void DFST_WordContext_freeCurrent(CCow current);
 
+// Offsets of the words DFST_Tokenizer_cursorContext returns for the same input,
+// counted in unit from the start of first_half; -1 where there is no word (or
+// for an unknown unit).
+typedef struct CWordOffsets_s {
+    int64_t current;
+    int64_t first_before;
+    int64_t second_before;
+    int64_t first_after;
+    int64_t second_after;
+} CWordOffsets;
+
+CWordOffsets DFST_Tokenizer_cursorContextOffsets(
+    const uint8_t *_Nonnull first_half_ptr,
+    uintptr_t first_half_len,
+    const uint8_t *_Nonnull second_half_ptr,
+    uintptr_t second_half_len,
+    DFST_OffsetUnit unit
+);
+
 #ifdef __cplusplus
 }
 #endif
@@ -0,0 +1,44 @@
+package no.divvun.fst;
+
+/**
+ * A token of a string with its offset, in UTF-16 code units like all Java
+ * string indices.
+ */
+public class IndexedWord {
+    private final int index;
+    private final String word;
+
+    IndexedWord(int index, String word) {
+        if (word == null) {
+            throw new NullPointerException("word cannot be null");
+        }
+        this.index = index;
+        this.word = word;
+    }
+
+    public int getIndex() {
+        return index;
+    }
+
+    public String getWord() {
+        return word;
+    }
+
+    @Override
+    public String toString() {
+        return "IndexedWord{index=" + index + ", word='" + word + "'}";
+    }
+
+    @Override
+    public boolean equals(Object obj) {
+        if (this == obj) return true;
+        if (!(obj instanceof IndexedWord)) return false;
+        IndexedWord other = (IndexedWord) obj;
+        return index == other.index && word.equals(other.word);
+    }
+
+    @Override
+    public int hashCode() {
+        return 31 * index + word.hashCode();
+    }
+}
@@ -1,5 +1,8 @@
 package no.divvun.fst;
 
+import java.util.ArrayList;
+import java.util.List;
+
 public class Tokenizer {
     static {
         String osName = System.getProperty("os.name").toLowerCase();
@@ -31,8 +34,47 @@ public class Tokenizer {
         return new WordContext(handle);
     }
 
+    /** Words of the text (tokens with a letter or digit), with their UTF-16 offsets. */
+    public static List<IndexedWord> wordIndices(String text) {
+        return indexedWords(text, wordBoundIndices0(text, true));
+    }
+
+    /** All tokens of the text, including spaces and punctuation, with their UTF-16 offsets. */
+    public static List<IndexedWord> wordBoundIndices(String text) {
+        return indexedWords(text, wordBoundIndices0(text, false));
+    }
+
+    private static List<IndexedWord> indexedWords(String text, int[] bounds) {
+        List<IndexedWord> words = new ArrayList<>(bounds.length / 2);
+        for (int i = 0; i + 1 < bounds.length; i += 2) {
+            words.add(new IndexedWord(bounds[i], text.substring(bounds[i], bounds[i + 1])));
+        }
+        return words;
+    }
+
+    public static List<String> sentences(String text) {
+        return sentences(text, null);
+    }
+
+    /**
+     * Splits text into sentences. The whitespace after a sentence belongs to it,
+     * so the sentences concatenate back to the text. When language is given,
+     * its abbreviations ("Dr.", "f.eks.") do not end a sentence.
+     */
+    public static List<String> sentences(String text, String language) {
+        int[] bounds = sentenceBounds0(text, language);
+        List<String> sentences = new ArrayList<>(bounds.length);
+        for (int i = 0; i + 1 < bounds.length; i++) {
+            sentences.add(text.substring(bounds[i], bounds[i + 1]));
+        }
+        return sentences;
+    }
+
     private static native long cursorContext0(String firstHalf, String secondHalf);
+    private static native int[] sentenceBounds0(String text, String language);
+    private static native int[] wordBoundIndices0(String text, boolean wordsOnly);
     static native void freeContext(long handle);
+    static native int getOffset(long handle, int which);
 
     static native long getCurrentPtr(long handle);
     static native long getCurrentLen(long handle);
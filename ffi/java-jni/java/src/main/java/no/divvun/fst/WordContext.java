@@ -52,6 +52,38 @@ public class WordContext implements AutoCloseable {
         return secondAfter;
     }
 
+    /**
+     * UTF-16 offset of the current word from the start of firstHalf, counting
+     * into secondHalf as if the two were joined; -1 if there is none. The same
+     * goes for the other offset getters.
+     */
+    public int getCurrentOffset() {
+        return offset(0);
+    }
+
+    public int getFirstBeforeOffset() {
+        return offset(1);
+    }
+
+    public int getSecondBeforeOffset() {
+        return offset(2);
+    }
+
+    public int getFirstAfterOffset() {
+        return offset(3);
+    }
+
+    public int getSecondAfterOffset() {
+        return offset(4);
+    }
+
+    private int offset(int which) {
+        if (handle == 0) {
+            throw new IllegalStateException("WordContext is closed");
+        }
+        return Tokenizer.getOffset(handle, which);
+    }
+
     @Override
     public void close() {
         if (handle != 0) {
@@ -1,5 +1,7 @@
 package no.divvun.fst;
 
+import java.util.List;
+
 import org.junit.jupiter.api.Test;
 import static org.junit.jupiter.api.Assertions.*;
 
@@ -119,4 +121,42 @@ public class TokenizerTest {
         }
         assertEquals("word", result);
     }
+
+    @Test
+    public void testSentences() {
+        assertEquals(
+            List.of("Hello world. ", "How are you? ", "Fine!"),
+            Tokenizer.sentences("Hello world. How are you? Fine!"));
+    }
+
+    @Test
+    public void testSentencesWithAbbreviations() {
+        String text = "Ta med f.eks. Ola. Han kan.";
+        assertEquals(List.of("Ta med f.eks. ", "Ola. ", "Han kan."), Tokenizer.sentences(text));
+        assertEquals(List.of("Ta med f.eks. Ola. ", "Han kan."), Tokenizer.sentences(text, "nb"));
+    }
+
+    @Test
+    public void testSentencesNonBmp() {
+        assertEquals(List.of("😀 Yes. ", "No."), Tokenizer.sentences("😀 Yes. No."));
+    }
+
+    @Test
+    public void testWordIndicesUseUtf16Offsets() {
+        assertEquals(
+            List.of(new IndexedWord(0, "Čáhppes"), new IndexedWord(11, "bussá")),
+            Tokenizer.wordIndices("Čáhppes 😄 bussá"));
+        assertEquals(4, Tokenizer.wordBoundIndices("a, b").size());
+    }
+
+    @Test
+    public void testCursorContextOffsets() {
+        try (WordContext ctx = Tokenizer.cursorContext("é sámi wo", "rld é next")) {
+            assertEquals(7, ctx.getCurrentOffset());
+            assertEquals(2, ctx.getFirstBeforeOffset());
+            assertEquals(0, ctx.getSecondBeforeOffset());
+            assertEquals(13, ctx.getFirstAfterOffset());
+            assertEquals(15, ctx.getSecondAfterOffset());
+        }
+    }
 }
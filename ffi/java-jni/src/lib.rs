@@ -1,7 +1,7 @@
 use jni::JNIEnv;
 use jni::objects::{JClass, JString};
 use jni::strings::JavaStr;
-use jni::sys::{jboolean, jlong};
+use jni::sys::{jboolean, jint, jintArray, jlong};
 use std::ffi::c_char;
 
 #[repr(C)]
@@ -34,6 +34,9 @@ struct CWordContext<'local> {
     second_before: CRustStr,
     first_after: CRustStr,
     second_after: CRustStr,
+    /// UTF-16 offsets of current, first_before, second_before, first_after and
+    /// second_after from the start of the first half; -1 where there is no word
+    offsets: [jint; 5],
     _handles: (
         (JString<'local>, *const c_char),
         (JString<'local>, *const c_char),
@@ -117,6 +120,7 @@ pub extern "system" fn Java_no_divvun_fst_Tokenizer_cursorContext0<'local>(
     };
 
     let ctx = divvun_fst::tokenizer::cursor_context(first_str, second_str);
+    let offsets = utf16_offsets(first_str, second_str);
 
     fn to_rust_str(opt: Option<(usize, &str)>) -> CRustStr {
         opt.map(|(_, word)| CRustStr {
@@ -151,12 +155,97 @@ pub extern "system" fn Java_no_divvun_fst_Tokenizer_cursorContext0<'local>(
         second_before: to_rust_str(ctx.second_before),
         first_after: to_rust_str(ctx.first_after),
         second_after: to_rust_str(ctx.second_after),
+        offsets,
         _handles: ((first_half, first_ptr), (second_half, second_ptr)),
     };
 
     Box::into_raw(Box::new(c_ctx)) as jlong
 }
 
+fn utf16_offsets(first_half: &str, second_half: &str) -> [jint; 5] {
+    use divvun_fst::tokenizer::offsets::OffsetUnit;
+
+    let ctx = divvun_fst::tokenizer::cursor_context_in(first_half, second_half, OffsetUnit::Utf16);
+    let first_half_len = OffsetUnit::Utf16.len_of(first_half) as jint;
+    let offset =
+        |word: Option<(usize, &str)>, base: jint| word.map_or(-1, |(i, _)| base + i as jint);
+    [
+        ctx.current.0 as jint,
+        offset(ctx.first_before, 0),
+        offset(ctx.second_before, 0),
+        offset(ctx.first_after, first_half_len),
+        offset(ctx.second_after, first_half_len),
+    ]
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_no_divvun_fst_Tokenizer_getOffset(
+    _env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    which: jint,
+) -> jint {
+    if handle == 0 {
+        return -1;
+    }
+    let ctx = unsafe { &*(handle as *const CWordContext) };
+    usize::try_from(which)
+        .ok()
+        .and_then(|i| ctx.offsets.get(i))
+        .copied()
+        .unwrap_or(-1)
+}
+
+/// Word boundaries of `text` as pairs of UTF-16 start and end offsets. With
+/// `words_only`, tokens without a letter or digit are left out.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_no_divvun_fst_Tokenizer_wordBoundIndices0<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    text: JString<'local>,
+    words_only: jboolean,
+) -> jintArray {
+    use divvun_fst::tokenizer::Tokenize;
+    use divvun_fst::tokenizer::offsets::OffsetUnit;
+
+    if text.is_null() {
+        let _ = env.throw_new("java/lang/NullPointerException", "Text cannot be null");
+        return std::ptr::null_mut();
+    }
+
+    let text: String = match env.get_string(&text) {
+        Ok(s) => s.into(),
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/RuntimeException",
+                format!("Failed to get text string: {}", e),
+            );
+            return std::ptr::null_mut();
+        }
+    };
+
+    let mut bounds = vec![];
+    let mut push = |(index, token): (usize, &str)| {
+        bounds.push(index as jint);
+        bounds.push((index + OffsetUnit::Utf16.len_of(token)) as jint);
+    };
+    if words_only != 0 {
+        text.word_indices_in(OffsetUnit::Utf16).for_each(&mut push);
+    } else {
+        text.word_bound_indices_in(OffsetUnit::Utf16)
+            .for_each(&mut push);
+    }
+
+    let array = match env.new_int_array(bounds.len() as jint) {
+        Ok(array) => array,
+        Err(_) => return std::ptr::null_mut(),
+    };
+    if env.set_int_array_region(&array, 0, &bounds).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_no_divvun_fst_Tokenizer_freeContext(
     env: JNIEnv,
@@ -327,6 +416,65 @@ pub extern "system" fn Java_no_divvun_fst_Tokenizer_getSecondAfterLen(
     ctx.second_after.len as jlong
 }
 
+/// Sentence boundaries of `text` as UTF-16 offsets: the start of every
+/// sentence, followed by the length of the text. `language` may be null.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_no_divvun_fst_Tokenizer_sentenceBounds0<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    text: JString<'local>,
+    language: JString<'local>,
+) -> jintArray {
+    if text.is_null() {
+        let _ = env.throw_new("java/lang/NullPointerException", "Text cannot be null");
+        return std::ptr::null_mut();
+    }
+
+    let text: String = match env.get_string(&text) {
+        Ok(s) => s.into(),
+        Err(e) => {
+            let _ = env.throw_new(
+                "java/lang/RuntimeException",
+                format!("Failed to get text string: {}", e),
+            );
+            return std::ptr::null_mut();
+        }
+    };
+
+    let abbreviations = if language.is_null() {
+        vec![]
+    } else {
+        match env.get_string(&language) {
+            Ok(s) => divvun_fst::tokenizer::sentence::abbreviations(&String::from(s)),
+            Err(e) => {
+                let _ = env.throw_new(
+                    "java/lang/RuntimeException",
+                    format!("Failed to get language string: {}", e),
+                );
+                return std::ptr::null_mut();
+            }
+        }
+    };
+
+    use divvun_fst::tokenizer::Tokenize;
+    let mut bounds = vec![];
+    let mut offset = 0;
+    for (_, sentence) in text.sentence_bound_indices_with_abbreviations(abbreviations) {
+        bounds.push(offset as jint);
+        offset += sentence.encode_utf16().count();
+    }
+    bounds.push(offset as jint);
+
+    let array = match env.new_int_array(bounds.len() as jint) {
+        Ok(array) => array,
+        Err(_) => return std::ptr::null_mut(),
+    };
+    if env.set_int_array_region(&array, 0, &bounds).is_err() {
+        return std::ptr::null_mut();
+    }
+    array.into_raw()
+}
+
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_no_divvun_fst_RustStr_copyBytes(
     env: JNIEnv,
@@ -33,6 +33,13 @@ def _get_library_path():
         )
 
 
+# Units for word indices: UTF-8 bytes, UTF-16 code units, or code points (the
+# unit Python strings are indexed in).
+OFFSET_BYTE = 0
+OFFSET_UTF16 = 1
+OFFSET_CODE_POINT = 2
+
+
 class RustSlice(ctypes.Structure):
     """Rust slice representation (pointer + length)."""
     _fields_ = [
@@ -104,6 +111,9 @@ class _DivvunFstLib:
         self.lib.DFST_SpellerArchive_locale.argtypes = [CffiTraitObject, self._error_callback_type]
         self.lib.DFST_SpellerArchive_locale.restype = RustSlice
 
+        self.lib.DFST_SpellerArchive_tokenizer.argtypes = [CffiTraitObject, self._error_callback_type]
+        self.lib.DFST_SpellerArchive_tokenizer.restype = ctypes.c_void_p
+
         # Speller functions
         self.lib.DFST_Speller_isCorrect.argtypes = [CffiTraitObject, RustSlice, self._error_callback_type]
         self.lib.DFST_Speller_isCorrect.restype = ctypes.c_uint8
@@ -111,6 +121,9 @@ class _DivvunFstLib:
         self.lib.DFST_Speller_suggest.argtypes = [CffiTraitObject, RustSlice, self._error_callback_type]
         self.lib.DFST_Speller_suggest.restype = RustSlice
 
+        self.lib.DFST_Speller_lemmatize.argtypes = [CffiTraitObject, RustSlice, self._error_callback_type]
+        self.lib.DFST_Speller_lemmatize.restype = RustSlice
+
         # Suggestion vector functions
         self.lib.DFST_VecSuggestion_len.argtypes = [RustSlice, self._error_callback_type]
         self.lib.DFST_VecSuggestion_len.restype = ctypes.c_size_t
@@ -138,6 +151,9 @@ class _DivvunFstLib:
         self.lib.DFST_WordIndices_new.argtypes = [ctypes.c_char_p]
         self.lib.DFST_WordIndices_new.restype = ctypes.c_void_p
 
+        self.lib.DFST_WordIndices_newWithUnit.argtypes = [ctypes.c_char_p, ctypes.c_uint8]
+        self.lib.DFST_WordIndices_newWithUnit.restype = ctypes.c_void_p
+
         self.lib.DFST_WordIndices_next.argtypes = [
             ctypes.c_void_p,
             ctypes.POINTER(ctypes.c_uint64),
@@ -148,6 +164,32 @@ class _DivvunFstLib:
         self.lib.DFST_WordIndices_free.argtypes = [ctypes.c_void_p]
         self.lib.DFST_WordIndices_free.restype = None
 
+        self.lib.DFST_SentenceBoundIndices_new.argtypes = [ctypes.c_char_p]
+        self.lib.DFST_SentenceBoundIndices_new.restype = ctypes.c_void_p
+
+        self.lib.DFST_SentenceBoundIndices_newWithLanguage.argtypes = [ctypes.c_char_p, ctypes.c_char_p]
+        self.lib.DFST_SentenceBoundIndices_newWithLanguage.restype = ctypes.c_void_p
+
+        self.lib.DFST_SentenceBoundIndices_next.argtypes = [
+            ctypes.c_void_p,
+            ctypes.POINTER(ctypes.c_uint64),
+            ctypes.POINTER(ctypes.c_char_p)
+        ]
+        self.lib.DFST_SentenceBoundIndices_next.restype = ctypes.c_uint8
+
+        self.lib.DFST_SentenceBoundIndices_free.argtypes = [ctypes.c_void_p]
+        self.lib.DFST_SentenceBoundIndices_free.restype = None
+
+        # Tokenizers configured by a speller archive
+        self.lib.DFST_Tokenizer_wordIndices.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_uint8]
+        self.lib.DFST_Tokenizer_wordIndices.restype = ctypes.c_void_p
+
+        self.lib.DFST_Tokenizer_sentenceBoundIndices.argtypes = [ctypes.c_void_p, ctypes.c_char_p]
+        self.lib.DFST_Tokenizer_sentenceBoundIndices.restype = ctypes.c_void_p
+
+        self.lib.DFST_Tokenizer_free.argtypes = [ctypes.c_void_p]
+        self.lib.DFST_Tokenizer_free.restype = None
+
 
 _lib = _DivvunFstLib()
 
@@ -211,6 +253,34 @@ class Speller:
         _lib.lib.cffi_vec_free(suggestions_slice)
         return results
 
+    def lemmatize(self, word: str) -> List[Tuple[str, float]]:
+        """Get the lemmas of a word with their weights, cheapest first."""
+        word_bytes = word.encode('utf-8')
+        word_slice = RustSlice(ctypes.cast(word_bytes, ctypes.c_void_p), len(word_bytes))
+        lemmas_slice = _lib.lib.DFST_Speller_lemmatize(self._handle, word_slice, _lib._error_callback)
+        _lib._check_error()
+
+        if lemmas_slice.data is None:
+            return []
+
+        length = _lib.lib.DFST_VecSuggestion_len(lemmas_slice, _lib._error_callback)
+        _lib._check_error()
+
+        results = []
+        for i in range(length):
+            value_slice = _lib.lib.DFST_VecSuggestion_getValue(lemmas_slice, i, _lib._error_callback)
+            _lib._check_error()
+            lemma = value_slice.to_string()
+            _lib.lib.cffi_string_free(value_slice)
+
+            weight = _lib.lib.DFST_VecSuggestion_getWeight(lemmas_slice, i, _lib._error_callback)
+            _lib._check_error()
+
+            results.append((lemma, weight))
+
+        _lib.lib.cffi_vec_free(lemmas_slice)
+        return results
+
 
 class SpellerArchive:
     """Spell checker archive (.bhfst file)."""
@@ -244,14 +314,53 @@ class SpellerArchive:
         _lib.lib.cffi_string_free(locale_slice)
         return locale
 
+    def tokenizer(self) -> 'Tokenizer':
+        """Get a tokenizer following this archive's tokenizer rules."""
+        handle = _lib.lib.DFST_SpellerArchive_tokenizer(self._handle, _lib._error_callback)
+        _lib._check_error()
+
+        if handle is None:
+            raise RuntimeError("Failed to get tokenizer from archive")
+
+        return Tokenizer(handle)
+
+
+class Tokenizer:
+    """A tokenizer following the rules of a speller archive's language."""
+
+    def __init__(self, handle: int):
+        self._handle = handle
+
+    def tokenize(self, text: str, unit: int = OFFSET_BYTE) -> List[Tuple[int, str]]:
+        """Tokenize text into words with their indices (in bytes by default)."""
+        return list(WordIndices(text, unit, tokenizer=self))
+
+    def sentences(self, text: str) -> List[Tuple[int, str]]:
+        """Split text into sentences with their byte indices."""
+        return list(SentenceBoundIndices(text, tokenizer=self))
+
+    def __del__(self):
+        """Free the tokenizer."""
+        if hasattr(self, '_handle') and self._handle is not None:
+            _lib.lib.DFST_Tokenizer_free(self._handle)
+
 
 class WordIndices:
     """Iterator over word boundaries in a string."""
 
-    def __init__(self, text: str):
-        """Create a word tokenizer for the given text."""
+    def __init__(self, text: str, unit: int = OFFSET_BYTE, tokenizer: Optional[Tokenizer] = None):
+        """Create a word tokenizer for the given text, with indices counted in unit,
+        optionally following the rules of a tokenizer from a speller archive."""
         self._text_bytes = text.encode('utf-8') + b'\0'
-        self._handle = _lib.lib.DFST_WordIndices_new(ctypes.c_char_p(self._text_bytes))
+        if tokenizer is None:
+            self._handle = _lib.lib.DFST_WordIndices_newWithUnit(ctypes.c_char_p(self._text_bytes), unit)
+        else:
+            self._tokenizer = tokenizer
+            self._handle = _lib.lib.DFST_Tokenizer_wordIndices(
+                tokenizer._handle,
+                ctypes.c_char_p(self._text_bytes),
+                unit
+            )
         if self._handle is None:
             raise RuntimeError("Failed to create word indices iterator")
 
@@ -284,6 +393,64 @@ class WordIndices:
             _lib.lib.DFST_WordIndices_free(self._handle)
 
 
-def tokenize(text: str) -> List[Tuple[int, str]]:
-    """Tokenize text into words with their byte indices."""
-    return list(WordIndices(text))
+class SentenceBoundIndices:
+    """Iterator over sentence boundaries in a string."""
+
+    def __init__(self, text: str, language: Optional[str] = None, tokenizer: Optional[Tokenizer] = None):
+        """Create a sentence splitter for the given text, optionally using the abbreviations of a language
+        or of a tokenizer from a speller archive."""
+        self._text_bytes = text.encode('utf-8') + b'\0'
+        if tokenizer is not None:
+            self._tokenizer = tokenizer
+            self._handle = _lib.lib.DFST_Tokenizer_sentenceBoundIndices(
+                tokenizer._handle,
+                ctypes.c_char_p(self._text_bytes)
+            )
+        elif language is None:
+            self._handle = _lib.lib.DFST_SentenceBoundIndices_new(ctypes.c_char_p(self._text_bytes))
+        else:
+            self._language_bytes = language.encode('utf-8') + b'\0'
+            self._handle = _lib.lib.DFST_SentenceBoundIndices_newWithLanguage(
+                ctypes.c_char_p(self._text_bytes),
+                ctypes.c_char_p(self._language_bytes)
+            )
+        if self._handle is None:
+            raise RuntimeError("Failed to create sentence bound indices iterator")
+
+    def __iter__(self) -> Iterator[Tuple[int, str]]:
+        """Iterate over (index, sentence) pairs."""
+        return self
+
+    def __next__(self) -> Tuple[int, str]:
+        """Get the next sentence."""
+        index = ctypes.c_uint64()
+        sentence_ptr = ctypes.c_char_p()
+
+        result = _lib.lib.DFST_SentenceBoundIndices_next(
+            self._handle,
+            ctypes.byref(index),
+            ctypes.byref(sentence_ptr)
+        )
+
+        if result == 0:
+            raise StopIteration
+
+        sentence = sentence_ptr.value.decode('utf-8')
+        _lib.lib.DFST_cstr_free(sentence_ptr)
+
+        return (index.value, sentence)
+
+    def __del__(self):
+        """Clean up the iterator."""
+        if hasattr(self, '_handle') and self._handle is not None:
+            _lib.lib.DFST_SentenceBoundIndices_free(self._handle)
+
+
+def tokenize(text: str, unit: int = OFFSET_BYTE) -> List[Tuple[int, str]]:
+    """Tokenize text into words with their indices (in bytes by default)."""
+    return list(WordIndices(text, unit))
+
+
+def sentences(text: str, language: Optional[str] = None) -> List[Tuple[int, str]]:
+    """Split text into sentences with their byte indices."""
+    return list(SentenceBoundIndices(text, language))
@@ -8,6 +8,9 @@ use std::sync::Arc;
 
 use divvun_fst::archive::{SpellerArchive, error::SpellerArchiveError};
 use divvun_fst::speller::{ReweightingConfig, Speller, SpellerConfig, suggestion::Suggestion};
+use divvun_fst::tokenizer::offsets::{OffsetUnit, UnitIndices};
+use divvun_fst::tokenizer::rules::Tokenizer;
+use divvun_fst::tokenizer::sentence::SentenceBoundIndices;
 use divvun_fst::tokenizer::{Tokenize, WordIndices};
 use divvun_fst::types::Weight;
 
@@ -53,17 +56,42 @@ pub struct CWordContext {
     pub second_after: CRustStr,
 }
 
+type FfiWordIndices<'a> = UnitIndices<'a, WordIndices<'a>>;
+
+/// `DFST_OffsetUnit` values: 0 = bytes, 1 = UTF-16 code units, 2 = code points.
+fn offset_unit(unit: u8) -> Option<OffsetUnit> {
+    match unit {
+        0 => Some(OffsetUnit::Byte),
+        1 => Some(OffsetUnit::Utf16),
+        2 => Some(OffsetUnit::CodePoint),
+        _ => None,
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn DFST_WordIndices_new<'a>(utf8_string: *const c_char) -> *mut FfiWordIndices<'a> {
+    DFST_WordIndices_newWithUnit(utf8_string, 0)
+}
+
+/// Like `DFST_WordIndices_new`, with the indices returned by
+/// `DFST_WordIndices_next` counted in `unit`. Returns null for an unknown unit.
 #[unsafe(no_mangle)]
-pub extern "C" fn DFST_WordIndices_new<'a>(utf8_string: *const c_char) -> *mut WordIndices<'a> {
+pub extern "C" fn DFST_WordIndices_newWithUnit<'a>(
+    utf8_string: *const c_char,
+    unit: u8,
+) -> *mut FfiWordIndices<'a> {
+    let Some(unit) = offset_unit(unit) else {
+        return std::ptr::null_mut();
+    };
     let c_str = unsafe { CStr::from_ptr(utf8_string) };
     let string = c_str.to_str().unwrap();
-    let iterator = string.word_indices();
+    let iterator = string.word_indices_in(unit);
     Box::into_raw(Box::new(iterator)) as *mut _
 }
 
 #[unsafe(no_mangle)]
 pub extern "C" fn DFST_WordIndices_next<'a>(
-    iterator: *mut WordIndices<'a>,
+    iterator: *mut FfiWordIndices<'a>,
     out_index: *mut u64,
     out_string: *mut *const c_char,
 ) -> u8 {
@@ -83,10 +111,131 @@ pub extern "C" fn DFST_WordIndices_next<'a>(
 }
 
 #[unsafe(no_mangle)]
-pub extern "C" fn DFST_WordIndices_free<'a>(handle: *mut WordIndices<'a>) {
+pub extern "C" fn DFST_WordIndices_free<'a>(handle: *mut FfiWordIndices<'a>) {
+    drop(unsafe { Box::from_raw(handle) });
+}
+
+/// # Safety
+///
+/// `utf8_string` must be a valid NUL-terminated UTF-8 string. The iterator
+/// borrows it, so it must stay alive until `DFST_SentenceBoundIndices_free`
+/// is called.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn DFST_SentenceBoundIndices_new<'a>(
+    utf8_string: *const c_char,
+) -> *mut SentenceBoundIndices<'a> {
+    let c_str = unsafe { CStr::from_ptr(utf8_string) };
+    let string = c_str.to_str().unwrap();
+    let iterator = string.sentence_bound_indices();
+    Box::into_raw(Box::new(iterator)) as *mut _
+}
+
+/// Like `DFST_SentenceBoundIndices_new`, with the abbreviations of `language`
+/// (a BCP 47 tag) kept from ending a sentence.
+///
+/// # Safety
+///
+/// As for `DFST_SentenceBoundIndices_new`, and `language` must be a valid
+/// NUL-terminated UTF-8 string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn DFST_SentenceBoundIndices_newWithLanguage<'a>(
+    utf8_string: *const c_char,
+    language: *const c_char,
+) -> *mut SentenceBoundIndices<'a> {
+    let c_str = unsafe { CStr::from_ptr(utf8_string) };
+    let string = c_str.to_str().unwrap();
+    let language = unsafe { CStr::from_ptr(language) }.to_str().unwrap();
+    let abbreviations = divvun_fst::tokenizer::sentence::abbreviations(language);
+    let iterator = string.sentence_bound_indices_with_abbreviations(abbreviations);
+    Box::into_raw(Box::new(iterator)) as *mut _
+}
+
+/// # Safety
+///
+/// `iterator` must come from `DFST_SentenceBoundIndices_new` and not have been
+/// freed, and `out_index` and `out_string` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn DFST_SentenceBoundIndices_next<'a>(
+    iterator: *mut SentenceBoundIndices<'a>,
+    out_index: *mut u64,
+    out_string: *mut *const c_char,
+) -> u8 {
+    let iterator = unsafe { &mut *iterator };
+
+    match iterator.next() {
+        Some((index, sentence)) => {
+            let c_sentence = CString::new(sentence).unwrap();
+            unsafe {
+                *out_index = index as u64;
+                *out_string = c_sentence.into_raw();
+            }
+            1
+        }
+        None => 0,
+    }
+}
+
+/// # Safety
+///
+/// `handle` must come from `DFST_SentenceBoundIndices_new` and be freed only
+/// once.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn DFST_SentenceBoundIndices_free<'a>(handle: *mut SentenceBoundIndices<'a>) {
     drop(unsafe { Box::from_raw(handle) });
 }
 
+/// Like `DFST_WordIndices_newWithUnit`, splitting words by the rules of
+/// `tokenizer` (see `DFST_SpellerArchive_tokenizer`). The iterator borrows
+/// `utf8_string`, but not the tokenizer.
+///
+/// # Safety
+///
+/// `tokenizer` must come from `DFST_SpellerArchive_tokenizer` and not have been
+/// freed, and `utf8_string` must be a valid NUL-terminated UTF-8 string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn DFST_Tokenizer_wordIndices<'a>(
+    tokenizer: *const Tokenizer,
+    utf8_string: *const c_char,
+    unit: u8,
+) -> *mut FfiWordIndices<'a> {
+    let Some(unit) = offset_unit(unit) else {
+        return std::ptr::null_mut();
+    };
+    let tokenizer = unsafe { &*tokenizer };
+    let c_str = unsafe { CStr::from_ptr(utf8_string) };
+    let string = c_str.to_str().unwrap();
+    let iterator = tokenizer.word_indices_in(string, unit);
+    Box::into_raw(Box::new(iterator)) as *mut _
+}
+
+/// Like `DFST_SentenceBoundIndices_new`, with the abbreviations `tokenizer`
+/// knows kept from ending a sentence.
+///
+/// # Safety
+///
+/// `tokenizer` must come from `DFST_SpellerArchive_tokenizer` and not have been
+/// freed, and `utf8_string` must be a valid NUL-terminated UTF-8 string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn DFST_Tokenizer_sentenceBoundIndices<'a>(
+    tokenizer: *const Tokenizer,
+    utf8_string: *const c_char,
+) -> *mut SentenceBoundIndices<'a> {
+    let tokenizer = unsafe { &*tokenizer };
+    let c_str = unsafe { CStr::from_ptr(utf8_string) };
+    let string = c_str.to_str().unwrap();
+    let iterator = tokenizer.sentence_bound_indices(string);
+    Box::into_raw(Box::new(iterator)) as *mut _
+}
+
+/// # Safety
+///
+/// `tokenizer` must come from `DFST_SpellerArchive_tokenizer` and be freed
+/// only once.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn DFST_Tokenizer_free(tokenizer: *const Tokenizer) {
+    drop(unsafe { Arc::from_raw(tokenizer) });
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn DFST_cstr_free(handle: *mut c_char) {
     drop(unsafe { CString::from_raw(handle) });
@@ -131,6 +280,61 @@ pub unsafe extern "C" fn DFST_Tokenizer_cursorContext(
     }
 }
 
+/// Offsets of the words of a `CWordContext`, counted from the start of the
+/// first half; -1 where there is no word.
+#[repr(C)]
+pub struct CWordOffsets {
+    pub current: i64,
+    pub first_before: i64,
+    pub second_before: i64,
+    pub first_after: i64,
+    pub second_after: i64,
+}
+
+/// Offsets for the words `DFST_Tokenizer_cursorContext()` returns for the same
+/// input, counted in `unit` (see `DFST_WordIndices_newWithUnit`). All offsets
+/// are -1 for an unknown unit.
+///
+/// # Safety
+///
+/// Both halves must point to valid UTF-8 of the given lengths.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn DFST_Tokenizer_cursorContextOffsets(
+    first_half_ptr: *const u8,
+    first_half_len: usize,
+    second_half_ptr: *const u8,
+    second_half_len: usize,
+    unit: u8,
+) -> CWordOffsets {
+    let Some(unit) = offset_unit(unit) else {
+        return CWordOffsets {
+            current: -1,
+            first_before: -1,
+            second_before: -1,
+            first_after: -1,
+            second_after: -1,
+        };
+    };
+    let first_half = unsafe {
+        std::str::from_utf8_unchecked(std::slice::from_raw_parts(first_half_ptr, first_half_len))
+    };
+    let second_half = unsafe {
+        std::str::from_utf8_unchecked(std::slice::from_raw_parts(second_half_ptr, second_half_len))
+    };
+
+    let ctx = divvun_fst::tokenizer::cursor_context_in(first_half, second_half, unit);
+    let first_half_len = unit.len_of(first_half) as i64;
+    let offset = |word: Option<(usize, &str)>, base: i64| word.map_or(-1, |(i, _)| base + i as i64);
+
+    CWordOffsets {
+        current: ctx.current.0 as i64,
+        first_before: offset(ctx.first_before, 0),
+        second_before: offset(ctx.second_before, 0),
+        first_after: offset(ctx.first_after, first_half_len),
+        second_after: offset(ctx.second_after, first_half_len),
+    }
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn DFST_WordContext_freeCurrent(current: CCow) {
     if current.is_owned != 0 && !current.ptr.is_null() {
@@ -262,6 +466,8 @@ impl FromForeign<*const std::ffi::c_void, SpellerConfig> for SpellerConfigMarsha
             // weights, which is the weight the search would have reached by
             // walking them one at a time.
             mutator_subsets: SpellerConfig::default().mutator_subsets,
+            // Not in the C struct, whose layout is fixed.
+            morphology: None,
             verbose: config.verbose != 0,
         };
 
@@ -300,6 +506,37 @@ pub extern "C" fn DFST_Speller_suggestWithConfig(
     speller.suggest_with_config(word, &config)
 }
 
+/// The lemmas of `word`, cheapest first, as suggestions: the value of each is
+/// a lemma, and its weight the weight of the lemma's best analysis.
+#[cffi::marshal(return_marshaler = "SuggestionVecMarshaler")]
+pub extern "C" fn DFST_Speller_lemmatize(
+    #[marshal(cffi::ArcRefMarshaler::<dyn Speller + Sync + Send>)] speller: Arc<
+        dyn Speller + Sync + Send,
+    >,
+    #[marshal(cffi::StrMarshaler)] word: &str,
+) -> Vec<Suggestion> {
+    speller
+        .lemmatize(word)
+        .into_iter()
+        .map(|(lemma, weight)| Suggestion::new(lemma, weight, None))
+        .collect()
+}
+
+#[cffi::marshal(return_marshaler = "SuggestionVecMarshaler")]
+pub extern "C" fn DFST_Speller_lemmatizeWithConfig(
+    #[marshal(cffi::ArcRefMarshaler::<dyn Speller + Sync + Send>)] speller: Arc<
+        dyn Speller + Sync + Send,
+    >,
+    #[marshal(cffi::StrMarshaler)] word: &str,
+    #[marshal(SpellerConfigMarshaler)] config: SpellerConfig,
+) -> Vec<Suggestion> {
+    speller
+        .lemmatize_with_config(word, &config)
+        .into_iter()
+        .map(|(lemma, weight)| Suggestion::new(lemma, weight, None))
+        .collect()
+}
+
 #[cffi::marshal]
 pub extern "C" fn DFST_VecSuggestion_len(
     #[marshal(SuggestionVecRefMarshaler)] suggestions: &[Suggestion],
@@ -421,6 +658,15 @@ pub extern "C" fn DFST_SpellerArchive_speller(
     handle.speller()
 }
 
+#[cffi::marshal(return_marshaler = "cffi::ArcMarshaler::<Tokenizer>")]
+pub extern "C" fn DFST_SpellerArchive_tokenizer(
+    #[marshal(cffi::ArcRefMarshaler::<dyn SpellerArchive + Send + Sync>)] handle: Arc<
+        dyn SpellerArchive + Send + Sync,
+    >,
+) -> Arc<Tokenizer> {
+    Arc::new(handle.tokenizer())
+}
+
 #[cffi::marshal(return_marshaler = "cffi::StringMarshaler")]
 pub extern "C" fn DFST_SpellerArchive_locale(
     #[marshal(cffi::ArcRefMarshaler::<dyn SpellerArchive + Send + Sync>)] handle: Arc<
@@ -5,6 +5,7 @@ use box_format::sync::BoxReader as BoxFileReader;
 
 use super::error::SpellerArchiveError;
 use super::{SpellerArchive, meta::SpellerMetadata};
+use crate::hyphenator::{Hyphenate, Hyphenator};
 use crate::speller::{HfstSpeller, Speller};
 use crate::transducer::{
     Transducer,
@@ -37,6 +38,7 @@ where
 {
     metadata: Option<SpellerMetadata>,
     speller: Arc<HfstSpeller<T, U>>,
+    hyphenator: Option<Arc<Hyphenator<U>>>,
 }
 
 impl<T, U> BoxSpellerArchive<T, U>
@@ -112,8 +114,26 @@ where
             }
         })?;
 
+        let hyphenator = match metadata.as_ref().and_then(SpellerMetadata::hyphenator) {
+            Some(meta) => {
+                let transducer = U::from_path(&fs, meta.id()).map_err(|source| {
+                    SpellerArchiveError::Transducer {
+                        archive: file_path.to_path_buf(),
+                        member: meta.id().into(),
+                        source,
+                    }
+                })?;
+                Some(Arc::new(Hyphenator::new(transducer, meta.mark())))
+            }
+            None => None,
+        };
+
         let speller = HfstSpeller::new(errmodel, acceptor);
-        Ok(BoxSpellerArchive { speller, metadata })
+        Ok(BoxSpellerArchive {
+            speller,
+            metadata,
+            hyphenator,
+        })
     }
 
     fn speller(&self) -> Arc<dyn Speller + Send + Sync> {
@@ -123,4 +143,10 @@ where
     fn metadata(&self) -> Option<&SpellerMetadata> {
         self.metadata.as_ref()
     }
+
+    fn hyphenator(&self) -> Option<Arc<dyn Hyphenate + Send + Sync>> {
+        self.hyphenator
+            .clone()
+            .map(|h| h as Arc<dyn Hyphenate + Send + Sync>)
+    }
 }
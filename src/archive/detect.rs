@@ -0,0 +1,259 @@
+//! Identifying the language of a text with the spellers at hand.
+//!
+//! There is no separate language model here: each installed speller is asked
+//! how much of the text it recognises, which is the question an application
+//! choosing a speller actually cares about. Each word of the text is one vote,
+//! shared among the spellers accepting it: a word only one speller accepts
+//! counts fully for it, and one several accept is split by its lexicon weight
+//! in each — how ordinary each speller finds it. That separates closely
+//! related languages sharing most of their short words, without letting the
+//! weights, which lexicons scale as they like, outvote what is accepted.
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use super::{SpellerArchive, locale_or_position};
+use crate::speller::{Speller, SpellerConfig};
+use crate::tokenizer::Tokenize;
+use crate::types::Weight;
+
+/// How well one speller archive matches a text.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LanguageScore {
+    /// locale of the archive, from its metadata; its position in the list of
+    /// candidates if it has none
+    pub locale: SmolStr,
+    /// share of the votes of the text's words for this locale, between 0 and
+    /// 1; the confidences of all candidates sum to 1 unless none accepted
+    /// anything, when all are 0
+    pub confidence: f32,
+    /// share of the text's words the speller accepts, between 0 and 1
+    pub acceptance: f32,
+    /// average lexicon weight of the accepted words; lower means the words
+    /// are more typical of the language
+    pub mean_lexicon_weight: Weight,
+}
+
+/// Rank `archives` by how likely `text` is to be written in their language.
+///
+/// The text is split with [`Tokenize::word_indices`], and tokens without a
+/// letter in them (numbers, mostly) are left out, since every speller accepts
+/// them alike. Candidates are ordered by confidence, then by acceptance rate,
+/// then by their order in `archives`. Text with no words in it gives every
+/// candidate an acceptance and confidence of 0.
+pub fn detect_language(
+    text: &str,
+    archives: &[Arc<dyn SpellerArchive + Send + Sync>],
+) -> Vec<LanguageScore> {
+    let config = SpellerConfig::default();
+    let words = evidence_words(text);
+    let weights: Vec<Vec<Option<Weight>>> = archives
+        .iter()
+        .map(|archive| word_weights(archive.speller(), &words, &config))
+        .collect();
+
+    let mut scores: Vec<LanguageScore> = weights
+        .iter()
+        .enumerate()
+        .map(|(i, weights)| {
+            let accepted: Vec<Weight> = weights.iter().flatten().copied().collect();
+            let total = accepted.iter().fold(Weight::ZERO, |total, w| total + *w);
+            LanguageScore {
+                locale: locale_or_position(archives[i].as_ref(), i),
+                confidence: 0.0,
+                acceptance: if words.is_empty() {
+                    0.0
+                } else {
+                    accepted.len() as f32 / words.len() as f32
+                },
+                mean_lexicon_weight: if accepted.is_empty() {
+                    Weight::ZERO
+                } else {
+                    Weight(total.0 / accepted.len() as f32)
+                },
+            }
+        })
+        .collect();
+
+    // Lexicon weights are negative log probabilities, so a word's vote is
+    // shared in proportion to e^-weight, taken from the cheapest to keep the
+    // shares from underflowing.
+    let mut votes = vec![0.0f32; archives.len()];
+    let mut voters = 0;
+    for word in 0..words.len() {
+        let accepting: Vec<(usize, Weight)> = weights
+            .iter()
+            .enumerate()
+            .filter_map(|(i, weights)| Some((i, weights[word]?)))
+            .collect();
+        let Some(best) = accepting.iter().map(|(_, weight)| *weight).min() else {
+            continue;
+        };
+        voters += 1;
+        let shares: Vec<f32> = accepting
+            .iter()
+            .map(|(_, weight)| (best.0 - weight.0).exp())
+            .collect();
+        let sum: f32 = shares.iter().sum();
+        for ((i, _), share) in accepting.iter().zip(shares) {
+            votes[*i] += share / sum;
+        }
+    }
+    if voters > 0 {
+        for (score, vote) in scores.iter_mut().zip(votes) {
+            score.confidence = vote / voters as f32;
+        }
+    }
+
+    scores.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then(b.acceptance.total_cmp(&a.acceptance))
+    });
+    scores
+}
+
+/// The words of `text` that say something about its language.
+pub(crate) fn evidence_words(text: &str) -> Vec<&str> {
+    text.word_indices()
+        .map(|(_, word)| word)
+        .filter(|word| word.chars().any(char::is_alphabetic))
+        .collect()
+}
+
+/// How many of `words` the speller accepts, and the sum of their lexicon
+/// weights.
+pub(crate) fn score_words(
+    speller: Arc<dyn Speller + Send + Sync>,
+    words: &[&str],
+    config: &SpellerConfig,
+) -> (usize, Weight) {
+    word_weights(speller, words, config)
+        .into_iter()
+        .flatten()
+        .fold((0, Weight::ZERO), |(accepted, total), weight| {
+            (accepted + 1, total + weight)
+        })
+}
+
+/// The lexicon weight of each of `words` the speller accepts.
+fn word_weights(
+    speller: Arc<dyn Speller + Send + Sync>,
+    words: &[&str],
+    config: &SpellerConfig,
+) -> Vec<Option<Weight>> {
+    words
+        .iter()
+        .map(|word| {
+            speller
+                .clone()
+                .is_correct_with_config(word, config)
+                .then(|| speller.clone().get_lexicon_weight_with_config(word, config))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::error::SpellerArchiveError;
+    use crate::archive::meta::SpellerMetadata;
+    use crate::speller::testing::ListSpeller;
+    use std::path::Path;
+
+    struct ListArchive {
+        speller: Arc<ListSpeller>,
+        metadata: SpellerMetadata,
+    }
+
+    impl SpellerArchive for ListArchive {
+        fn open(path: &Path) -> Result<Self, SpellerArchiveError> {
+            Err(SpellerArchiveError::UnsupportedExt {
+                path: path.to_path_buf(),
+                ext: Default::default(),
+            })
+        }
+
+        fn speller(&self) -> Arc<dyn Speller + Send + Sync> {
+            self.speller.clone()
+        }
+
+        fn metadata(&self) -> Option<&SpellerMetadata> {
+            Some(&self.metadata)
+        }
+    }
+
+    fn archive(
+        locale: &str,
+        words: &'static [(&'static str, f32)],
+    ) -> Arc<dyn SpellerArchive + Send + Sync> {
+        let metadata = serde_json::from_value(serde_json::json!({
+            "info": { "locale": locale, "title": [], "description": "", "producer": "" },
+            "acceptor": { "id": "", "title": [], "description": "", "continuation": null },
+            "errmodel": { "id": "", "title": [], "description": "" },
+        }))
+        .unwrap();
+        Arc::new(ListArchive {
+            speller: Arc::new(ListSpeller { words, fix: None }),
+            metadata,
+        })
+    }
+
+    fn candidates() -> Vec<Arc<dyn SpellerArchive + Send + Sync>> {
+        vec![
+            archive(
+                "nb",
+                &[("jeg", 2.0), ("er", 1.0), ("her", 3.0), ("da", 4.0)],
+            ),
+            archive(
+                "da",
+                &[("jeg", 1.0), ("er", 1.0), ("her", 1.0), ("da", 1.0)],
+            ),
+            archive(
+                "se",
+                &[("mun", 1.0), ("lean", 1.0), ("dál", 1.0), ("da", 1.0)],
+            ),
+        ]
+    }
+
+    fn locales(scores: &[LanguageScore]) -> Vec<&str> {
+        scores.iter().map(|s| s.locale.as_str()).collect()
+    }
+
+    #[test]
+    fn ranks_by_acceptance() {
+        let scores = detect_language("mun lean dál, jeg", &candidates());
+        assert_eq!(locales(&scores), ["se", "da", "nb"]);
+        assert_eq!(scores[0].acceptance, 0.75);
+        assert_eq!(scores[1].acceptance, 0.25);
+        let total: f32 = scores.iter().map(|s| s.confidence).sum();
+        assert!((total - 1.0).abs() < 1e-6, "{scores:?}");
+    }
+
+    // "nb" and "da" accept every word; the one that finds them more ordinary
+    // has more of the votes, whatever the order the archives were given in.
+    #[test]
+    fn equal_acceptance_is_decided_by_lexicon_weight() {
+        let scores = detect_language("jeg er her", &candidates());
+        assert_eq!(locales(&scores), ["da", "nb", "se"]);
+        assert_eq!(scores[0].acceptance, scores[1].acceptance);
+        // "jeg" is one weight cheaper in "da", "er" the same, "her" two.
+        let da = (1.0 / (1.0 + (-1.0f32).exp()) + 0.5 + 1.0 / (1.0 + (-2.0f32).exp())) / 3.0;
+        assert!((scores[0].confidence - da).abs() < 1e-6, "{scores:?}");
+        assert!(
+            (scores[1].confidence - (1.0 - da)).abs() < 1e-6,
+            "{scores:?}"
+        );
+        assert_eq!(scores[0].mean_lexicon_weight, Weight(1.0));
+        assert_eq!(scores[1].mean_lexicon_weight, Weight(2.0));
+        assert_eq!(scores[2].confidence, 0.0);
+    }
+
+    #[test]
+    fn text_without_words_is_no_evidence() {
+        let scores = detect_language("  12, 345 … ", &candidates());
+        assert_eq!(locales(&scores), ["nb", "da", "se"]);
+        assert!(scores.iter().all(|s| s.confidence == 0.0));
+    }
+}
@@ -5,6 +5,8 @@
 use serde::{Deserialize, Serialize};
 use serde_xml_rs::{Error, ParserConfig, from_reader};
 
+use crate::tokenizer::rules::TokenizerRules;
+
 /// Speller metadata
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SpellerMetadata {
@@ -14,9 +16,31 @@ pub struct SpellerMetadata {
     acceptor: SpellerMetadataAcceptor,
     /// error model metadata
     errmodel: SpellerMetadataErrmodel,
+    /// tokenizer rules for the speller's language
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tokenizer: Option<TokenizerRules>,
+    /// hyphenator metadata, if the archive has a hyphenator
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hyphenator: Option<SpellerMetadataHyphenator>,
 }
 
 impl SpellerMetadata {
+    /// Metadata for a speller built by other means than the HFST tools,
+    /// without tokenizer rules or a hyphenator.
+    pub fn new(
+        info: SpellerMetadataInfo,
+        acceptor: SpellerMetadataAcceptor,
+        errmodel: SpellerMetadataErrmodel,
+    ) -> Self {
+        SpellerMetadata {
+            info,
+            acceptor,
+            errmodel,
+            tokenizer: None,
+            hyphenator: None,
+        }
+    }
+
     /// Get the speller information
     pub fn info(&self) -> &SpellerMetadataInfo {
         &self.info
@@ -32,6 +56,16 @@ impl SpellerMetadata {
         &self.errmodel
     }
 
+    /// Get the tokenizer rules, if the speller declares any
+    pub fn tokenizer(&self) -> Option<&TokenizerRules> {
+        self.tokenizer.as_ref()
+    }
+
+    /// Get the hyphenator metadata, if the archive has a hyphenator
+    pub fn hyphenator(&self) -> Option<&SpellerMetadataHyphenator> {
+        self.hyphenator.as_ref()
+    }
+
     /// Get mutable reference to acceptor metadata
     ///
     /// # Warning
@@ -51,6 +85,16 @@ impl SpellerMetadata {
     pub fn errmodel_mut(&mut self) -> &mut SpellerMetadataErrmodel {
         &mut self.errmodel
     }
+
+    /// Get mutable reference to hyphenator metadata
+    ///
+    /// # Warning
+    /// This method is only for internal tooling use and should not be used in normal applications.
+    /// It may be removed in a future version.
+    #[doc(hidden)]
+    pub fn hyphenator_mut(&mut self) -> Option<&mut SpellerMetadataHyphenator> {
+        self.hyphenator.as_mut()
+    }
 }
 
 /// localised speller title
@@ -63,6 +107,15 @@ pub struct SpellerTitle {
     pub value: String,
 }
 
+impl SpellerTitle {
+    fn new(value: impl Into<String>) -> Self {
+        SpellerTitle {
+            lang: None,
+            value: value.into(),
+        }
+    }
+}
+
 /// Speller metadata
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SpellerMetadataInfo {
@@ -77,6 +130,21 @@ pub struct SpellerMetadataInfo {
 }
 
 impl SpellerMetadataInfo {
+    /// Speller information with one title, in no particular language.
+    pub fn new(
+        locale: impl Into<String>,
+        title: impl Into<String>,
+        description: impl Into<String>,
+        producer: impl Into<String>,
+    ) -> Self {
+        SpellerMetadataInfo {
+            locale: locale.into(),
+            title: vec![SpellerTitle::new(title)],
+            description: description.into(),
+            producer: producer.into(),
+        }
+    }
+
     /// Get the ISO-639 locale code
     pub fn locale(&self) -> &str {
         &self.locale
@@ -117,6 +185,21 @@ pub struct SpellerMetadataAcceptor {
 }
 
 impl SpellerMetadataAcceptor {
+    /// Metadata for a general dictionary acceptor with one title.
+    pub fn new(
+        id: impl Into<String>,
+        title: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        SpellerMetadataAcceptor {
+            type_: "general".into(),
+            id: id.into(),
+            title: vec![SpellerTitle::new(title)],
+            description: description.into(),
+            continuation: None,
+        }
+    }
+
     /// Get the acceptor type
     pub fn type_(&self) -> &str {
         &self.type_
@@ -165,6 +248,19 @@ pub struct SpellerMetadataErrmodel {
 }
 
 impl SpellerMetadataErrmodel {
+    /// Metadata for an error model with one title.
+    pub fn new(
+        id: impl Into<String>,
+        title: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        SpellerMetadataErrmodel {
+            id: id.into(),
+            title: vec![SpellerTitle::new(title)],
+            description: description.into(),
+        }
+    }
+
     /// Get the error model ID
     pub fn id(&self) -> &str {
         &self.id
@@ -191,6 +287,56 @@ impl SpellerMetadataErrmodel {
     }
 }
 
+/// Hyphenator metadata
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpellerMetadataHyphenator {
+    /// locally unique id for the hyphenator
+    id: String,
+    /// localised human readable titles for the hyphenator
+    #[serde(default)]
+    title: Vec<SpellerTitle>,
+    /// human readable description of the hyphenator
+    #[serde(default)]
+    description: String,
+    /// the mark the hyphenator writes at hyphenation points, if not `^`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mark: Option<String>,
+}
+
+impl SpellerMetadataHyphenator {
+    /// Get the hyphenator ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the localized titles
+    pub fn title(&self) -> &[SpellerTitle] {
+        &self.title
+    }
+
+    /// Get the description
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Get the mark written at hyphenation points
+    pub fn mark(&self) -> &str {
+        self.mark
+            .as_deref()
+            .unwrap_or(crate::hyphenator::DEFAULT_MARK)
+    }
+
+    /// Set the hyphenator ID
+    ///
+    /// # Warning
+    /// This method is only for internal tooling use and should not be used in normal applications.
+    /// It may be removed in a future version.
+    #[doc(hidden)]
+    pub fn set_id(&mut self, id: String) {
+        self.id = id;
+    }
+}
+
 impl std::str::FromStr for SpellerMetadata {
     type Err = Error;
 
@@ -258,3 +404,97 @@ fn test_xml_parse() {
     let s = SpellerMetadata::from_str(&xml_data).unwrap();
     println!("{:#?}", s);
 }
+
+#[test]
+fn test_tokenizer_rules() {
+    use crate::tokenizer::rules::Hyphens;
+    use std::str::FromStr;
+
+    let xml_data = r##"<?xml version="1.0" encoding="UTF-8"?>
+        <hfstspeller dtdversion="1.0" hfstversion="3">
+        <info>
+            <locale>fi</locale>
+            <title>Finnish</title>
+            <description>Finnish speller</description>
+            <producer>Divvun</producer>
+        </info>
+        <acceptor type="general" id="acceptor.default.hfst">
+            <title>Finnish dictionary</title>
+            <description>Finnish dictionary</description>
+        </acceptor>
+        <errmodel id="errmodel.default.hfst">
+            <title>Levenshtein edit distance transducer</title>
+            <description>Correction model</description>
+        </errmodel>
+        <tokenizer>
+            <word-internal>`</word-internal>
+            <hyphens>join</hyphens>
+        </tokenizer>
+        </hfstspeller>
+    "##;
+
+    let s = SpellerMetadata::from_str(xml_data).unwrap();
+    let rules = s.tokenizer().unwrap();
+    assert_eq!(rules.word_chars, "");
+    assert_eq!(rules.word_internal, "`");
+    assert_eq!(rules.hyphens, Hyphens::Join);
+
+    // The same rules survive the trip to the JSON metadata of .bhfst archives.
+    let json = serde_json::to_string(&s).unwrap();
+    let s: SpellerMetadata = serde_json::from_str(&json).unwrap();
+    assert_eq!(s.tokenizer(), Some(rules));
+
+    let s = SpellerMetadata::from_str(
+        &xml_data
+            .replace("<tokenizer>", "<x>")
+            .replace("</tokenizer>", "</x>"),
+    );
+    assert!(s.unwrap().tokenizer().is_none());
+}
+
+#[test]
+fn test_hyphenator() {
+    use std::str::FromStr;
+
+    let xml_data = r##"<?xml version="1.0" encoding="UTF-8"?>
+        <hfstspeller dtdversion="1.0" hfstversion="3">
+        <info>
+            <locale>se</locale>
+            <title>Northern Sami</title>
+            <description>Northern Sami speller</description>
+            <producer>Divvun</producer>
+        </info>
+        <acceptor type="general" id="acceptor.default.hfst">
+            <title>Northern Sami dictionary</title>
+            <description>Northern Sami dictionary</description>
+        </acceptor>
+        <errmodel id="errmodel.default.hfst">
+            <title>Levenshtein edit distance transducer</title>
+            <description>Correction model</description>
+        </errmodel>
+        <hyphenator id="hyphenator.default.hfst">
+            <title>Northern Sami hyphenator</title>
+        </hyphenator>
+        </hfstspeller>
+    "##;
+
+    let s = SpellerMetadata::from_str(xml_data).unwrap();
+    let hyphenator = s.hyphenator().unwrap();
+    assert_eq!(hyphenator.id(), "hyphenator.default.hfst");
+    assert_eq!(hyphenator.mark(), "^");
+
+    let json = serde_json::to_string(&s).unwrap();
+    let json = json.replace(
+        r#""id":"hyphenator.default.hfst""#,
+        r#""id":"hyphenator.default.hfst","mark":"-""#,
+    );
+    let s: SpellerMetadata = serde_json::from_str(&json).unwrap();
+    assert_eq!(s.hyphenator().unwrap().mark(), "-");
+
+    let s = SpellerMetadata::from_str(
+        &xml_data
+            .replace("<hyphenator ", "<x ")
+            .replace("</hyphenator>", "</x>"),
+    );
+    assert!(s.unwrap().hyphenator().is_none());
+}
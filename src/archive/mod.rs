@@ -1,8 +1,10 @@
 //! Handling of archives of spell-checking models.
 use memmap2::Mmap;
+use smol_str::SmolStr;
 use std::{ffi::OsString, path::Path, sync::Arc};
 
 pub mod boxf;
+pub mod detect;
 pub mod error;
 pub mod meta;
 pub mod zip;
@@ -10,7 +12,9 @@ pub mod zip;
 use self::{boxf::ThfstChunkedBoxSpellerArchive, meta::SpellerMetadata};
 use crate::{
     archive::{error::SpellerArchiveError, zip::ZipSpellerArchive},
+    hyphenator::Hyphenate,
     speller::Speller,
+    tokenizer::rules::Tokenizer,
 };
 
 /// Top-level, one-line hint printed by CLIs when an error chain indicates the
@@ -54,6 +58,35 @@ pub trait SpellerArchive {
 
     /// Retrieve metadata.
     fn metadata(&self) -> Option<&SpellerMetadata>;
+
+    /// Retrieve the hyphenator, if the archive has one declared in its
+    /// metadata.
+    fn hyphenator(&self) -> Option<Arc<dyn Hyphenate + Send + Sync>> {
+        None
+    }
+
+    /// Retrieve a tokenizer for the speller's language, following the
+    /// tokenizer rules in the metadata, if any, and knowing the language's
+    /// abbreviations.
+    fn tokenizer(&self) -> Tokenizer {
+        match self.metadata() {
+            Some(metadata) => Tokenizer::for_language(
+                metadata.info().locale(),
+                &metadata.tokenizer().cloned().unwrap_or_default(),
+            ),
+            None => Tokenizer::default(),
+        }
+    }
+}
+
+/// The locale an archive reports in its metadata, or its position in a list
+/// of archives if it reports none.
+pub(crate) fn locale_or_position(archive: &dyn SpellerArchive, position: usize) -> SmolStr {
+    archive
+        .metadata()
+        .map(|m| SmolStr::new(m.info().locale()))
+        .filter(|locale| !locale.is_empty())
+        .unwrap_or_else(|| SmolStr::new(position.to_string()))
 }
 
 /// Reads a speller archive.
@@ -0,0 +1,289 @@
+//! Checking running text: spelling plus cheap text-level rules.
+//!
+//! The speller only ever sees one word at a time, so it cannot notice that a
+//! word was typed twice, that a sentence starts in lower case, or that a word
+//! in the middle of a sentence is shouting. [`check_text`] walks the text with
+//! the tokenizer, asks the speller about each word, and runs those rules over
+//! the words in between, reporting everything as one list of [`Diagnostic`]s
+//! with byte spans into the text.
+//!
+//! Sentences are found by the sentence segmenter of the tokenizer, which
+//! follows UAX #29 and knows the abbreviations of [`CheckConfig::language`]:
+//! "Dr. Smith" is one sentence. Unlike plain UAX #29, it ends a sentence at a
+//! full stop before a lower-case word that follows no known abbreviation, as
+//! that word is what the check is after. A word starts a sentence if it is the
+//! first of the text, or the first after a sentence ending in terminal
+//! punctuation. A line break alone ends a sentence in UAX #29, but not in text
+//! wrapped at a fixed width, so it does not start one here.
+//!
+//! Marked-up text (see [`CheckConfig::markup`]) is checked one stretch of
+//! prose at a time, with spans still into the source. A new block — heading,
+//! paragraph, list item — starts a sentence, and code or a URL between two
+//! words keeps them from counting as repeated.
+use std::ops::Range;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use unic_ucd_common::alphanumeric::is_alphanumeric;
+
+use crate::speller::{Speller, SpellerConfig};
+use crate::tokenizer::Tokenize;
+use crate::tokenizer::case_handling::{is_all_caps, lower_case, upper_first};
+use crate::tokenizer::markup::{Boundary, Markup, prose_segments};
+use crate::tokenizer::sentence::{SentenceBoundIndices, abbreviations};
+use crate::tokenizer::sentence_break::SentenceBreak as SB;
+
+/// What a [`Diagnostic`] reports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum DiagnosticKind {
+    /// the speller does not accept the word
+    Spelling,
+    /// the same word twice in a row, as in "the the"
+    RepeatedWord,
+    /// a sentence starting with a lower-case letter
+    LowercaseSentenceStart,
+    /// an all-caps word in the middle of a sentence that the speller only
+    /// knows in lower case
+    AllCapsMidSentence,
+}
+
+/// A problem found in a text.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// what kind of problem this is
+    pub kind: DiagnosticKind,
+    /// byte range of the offending text
+    pub span: Range<usize>,
+    /// the offending text, as found at `span`
+    pub text: SmolStr,
+    /// replacements for the text at `span`, best first; may be empty
+    pub suggestions: Vec<SmolStr>,
+}
+
+/// Which checks [`check_text`] runs.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CheckConfig {
+    /// report words the speller does not accept
+    #[serde(default = "default_enabled")]
+    pub spelling: bool,
+    /// report doubled words
+    #[serde(default = "default_enabled")]
+    pub repeated_words: bool,
+    /// report sentences starting in lower case
+    #[serde(default = "default_enabled")]
+    pub sentence_case: bool,
+    /// report all-caps words in the middle of a sentence
+    #[serde(default = "default_enabled")]
+    pub mid_sentence_caps: bool,
+    /// the markup the text is written in; only its prose is checked
+    #[serde(default = "default_markup")]
+    pub markup: Markup,
+    /// the language of the text, a BCP 47 tag, whose abbreviations do not end
+    /// a sentence (see [`abbreviations`]); empty for none
+    #[serde(default)]
+    pub language: SmolStr,
+    /// configuration for the speller, used by every check that consults it
+    #[serde(default = "default_speller")]
+    pub speller: SpellerConfig,
+}
+
+impl CheckConfig {
+    /// create a default configuration with every check enabled and the
+    /// default speller configuration
+    pub const fn default() -> CheckConfig {
+        CheckConfig {
+            spelling: default_enabled(),
+            repeated_words: default_enabled(),
+            sentence_case: default_enabled(),
+            mid_sentence_caps: default_enabled(),
+            markup: default_markup(),
+            language: SmolStr::new_inline(""),
+            speller: default_speller(),
+        }
+    }
+}
+
+const fn default_enabled() -> bool {
+    true
+}
+
+const fn default_markup() -> Markup {
+    Markup::Plain
+}
+
+const fn default_speller() -> SpellerConfig {
+    SpellerConfig::default()
+}
+
+/// Check `text` with `speller` and the rules enabled in `config`.
+///
+/// Diagnostics come back in text order. A word can carry more than one: a
+/// misspelt word at the start of a sentence is reported for both.
+pub fn check_text(
+    speller: Arc<dyn Speller + Send + Sync>,
+    text: &str,
+    config: &CheckConfig,
+) -> Vec<Diagnostic> {
+    let mut out = vec![];
+
+    let abbreviations = abbreviations(&config.language);
+    let mut sentence_start = true;
+    // The previous word, for as long as only whitespace follows it.
+    let mut previous: Option<(usize, &str)> = None;
+
+    let segments = prose_segments(text, config.markup);
+    let mut sentence_ends = segments
+        .iter()
+        .flat_map(|segment| {
+            let offset = segment.span.start;
+            sentence_ends(&text[segment.span.clone()], &abbreviations).map(move |end| offset + end)
+        })
+        .peekable();
+    let tokens = segments.iter().flat_map(|segment| {
+        let offset = segment.span.start;
+        let boundary = segment.boundary;
+        text[segment.span.clone()]
+            .word_bound_indices()
+            .enumerate()
+            .map(move |(i, (index, token))| {
+                let boundary = if i == 0 { Some(boundary) } else { None };
+                (boundary, offset + index, token)
+            })
+    });
+
+    for (boundary, index, token) in tokens {
+        match boundary {
+            Some(Boundary::Block) => {
+                sentence_start = true;
+                previous = None;
+            }
+            Some(Boundary::Content) => previous = None,
+            _ => {}
+        }
+        while sentence_ends.next_if(|&end| end <= index).is_some() {
+            sentence_start = true;
+        }
+
+        if !token.chars().any(is_alphanumeric) {
+            if !token.chars().all(char::is_whitespace) {
+                previous = None;
+            }
+            continue;
+        }
+
+        let span = index..index + token.len();
+
+        if config.repeated_words
+            && let Some((prev_index, prev)) = previous
+            && lower_case(prev) == lower_case(token)
+        {
+            // The span runs from the end of the first word, so that deleting
+            // it leaves the spacing before it intact. Markup in between stays
+            // out of it, so only the word itself goes.
+            let prev_end = prev_index + prev.len();
+            let span = if text[prev_end..index].chars().all(char::is_whitespace) {
+                prev_end..span.end
+            } else {
+                span.clone()
+            };
+            out.push(Diagnostic {
+                kind: DiagnosticKind::RepeatedWord,
+                text: text[span.clone()].into(),
+                span,
+                suggestions: vec![SmolStr::default()],
+            });
+        }
+
+        if sentence_start {
+            if config.sentence_case && starts_lower_case(token) {
+                out.push(Diagnostic {
+                    kind: DiagnosticKind::LowercaseSentenceStart,
+                    span: span.clone(),
+                    text: token.into(),
+                    suggestions: vec![upper_first(token)],
+                });
+            }
+        } else if config.mid_sentence_caps && is_shouting(&speller, token, &config.speller) {
+            out.push(Diagnostic {
+                kind: DiagnosticKind::AllCapsMidSentence,
+                span: span.clone(),
+                text: token.into(),
+                suggestions: vec![lower_case(token)],
+            });
+        }
+
+        if config.spelling
+            && !speller
+                .clone()
+                .is_correct_with_config(token, &config.speller)
+        {
+            let suggestions = speller
+                .clone()
+                .suggest_with_config(token, &config.speller)
+                .into_iter()
+                .map(|s| s.value)
+                .collect();
+            out.push(Diagnostic {
+                kind: DiagnosticKind::Spelling,
+                span,
+                text: token.into(),
+                suggestions,
+            });
+        }
+
+        sentence_start = false;
+        previous = Some((index, token));
+    }
+
+    out.sort_by_key(|d| d.span.start);
+    out
+}
+
+/// Where the sentences of `prose` that end in terminal punctuation end, which
+/// is where the next sentence starts.
+fn sentence_ends<'a>(
+    prose: &'a str,
+    abbreviations: &[SmolStr],
+) -> impl Iterator<Item = usize> + 'a {
+    SentenceBoundIndices::new_with_abbreviations(prose, abbreviations.to_vec())
+        .lower_case_starts()
+        .filter(|(_, sentence)| {
+            let body = sentence.trim_end_matches(|c| !is_alphanumeric(c));
+            sentence[body.len()..]
+                .chars()
+                .any(|c| matches!(SB::of(c), SB::STerm | SB::ATerm))
+        })
+        .map(|(index, sentence)| index + sentence.len())
+}
+
+fn starts_lower_case(word: &str) -> bool {
+    word.chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() && c.is_lowercase())
+}
+
+/// Whether `word` is written in capitals that it does not carry in the
+/// lexicon. Acronyms the lexicon lists in capitals are left alone, and so are
+/// single capitals like "I".
+fn is_shouting(
+    speller: &Arc<dyn Speller + Send + Sync>,
+    word: &str,
+    config: &SpellerConfig,
+) -> bool {
+    if !is_all_caps(word) || word.chars().filter(|c| c.is_uppercase()).count() < 2 {
+        return false;
+    }
+
+    let verbatim = SpellerConfig {
+        recase: false,
+        ..config.clone()
+    };
+    !speller.clone().is_correct_with_config(word, &verbatim)
+        && speller
+            .clone()
+            .is_correct_with_config(&lower_case(word), &verbatim)
+}
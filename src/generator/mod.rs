@@ -19,24 +19,52 @@
 //! yields just the citation form. There's no morphology on the output
 //! tape to walk against.
 //!
-//! # Limitations of v0
+//! # Search order
 //!
-//! - **Cycles** are bounded by [`GeneratorConfig::max_depth`],
-//!   [`GeneratorConfig::max_weight`], and the hard-stop
-//!   [`GeneratorConfig::max_iterations`] cap. The walker is naive
-//!   DFS-with-pruning, not best-first; very deep budgets can run for
-//!   a long time even with the iteration cap.
-//! - **Identity / unknown** symbols are not substituted; raw symbol
-//!   passes through to the surface form.
+//! The lexicon is searched best-first: the open path of least weight is
+//! always extended next, and a complete path is only emitted once no open
+//! path is cheaper. Forms therefore come out cheapest first, and with
+//! [`GeneratorConfig::max_results`] set the search stops as soon as the
+//! requested number of forms is known to be the cheapest ones.
+//!
+//! A [`TagPattern`] in [`GeneratorConfig::tags`] narrows generation down to
+//! the forms whose tags match it. Paths are dropped as soon as their tags
+//! rule out a match, so asking for one form does not cost a walk through the
+//! whole paradigm.
+//!
+//! # Limitations
+//!
+//! - **Cycles**: a path only revisits a state it has already passed
+//!   through if it has read more of the lemma since, and is further bounded
+//!   by [`GeneratorConfig::max_depth`], [`GeneratorConfig::max_weight`] and
+//!   the hard-stop [`GeneratorConfig::max_iterations`] cap.
+//!
+//! # Characters outside the alphabet
+//!
+//! A lemma may contain characters the generator has never seen: a foreign
+//! name, a number. As in the speller, such a character can only be read by
+//! an `@_IDENTITY_@` or `@_UNKNOWN_@` arc on the input tape, and an arc
+//! writing either symbol on the output tape writes the character itself.
+//! Without those arcs, as in most lexicons, the lemma generates nothing.
+
+use std::collections::BinaryHeap;
 
 use hashbrown::HashSet;
+use lifeguard::{Pool, Recycled};
 use smol_str::SmolStr;
-use unic_segment::Graphemes;
+
+pub mod paradigm;
+pub mod pattern;
+
+pub use self::paradigm::{Paradigm, ParadigmFormat};
+pub use self::pattern::TagPattern;
 
 use crate::transducer::Transducer;
+use crate::transducer::lookup::tokenise;
+use crate::transducer::symbol_transition::SymbolTransition;
+use crate::transducer::tree_node::TreeNode;
 use crate::types::{
-    FlagDiacriticOperation, FlagDiacriticOperator, SymbolNumber, TransitionTableIndex, ValueNumber,
-    Weight,
+    FlagDiacriticOperation, InputIndex, SymbolNumber, TransitionTableIndex, ValueNumber, Weight,
 };
 
 /// One generated surface form for a lemma.
@@ -48,23 +76,29 @@ pub struct GenerationResult {
     /// the lemma on the FST's input tape, joined into a single string
     /// (e.g. `"+V+Inf"`, `"+V+Action+Acc+Sg"`).
     pub analysis: SmolStr,
+    /// The same tags one by one (e.g. `["+V", "+Action", "+Acc", "+Sg"]`).
+    pub tags: Vec<SmolStr>,
     /// Cumulative weight of the path producing this form.
     pub weight: Weight,
 }
 
 /// Configuration for [`Speller::generate_with_config`](crate::speller::Speller::generate_with_config).
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct GeneratorConfig {
     /// Maximum number of transitions to follow on a single path.
     pub max_depth: usize,
     /// Maximum cumulative path weight; paths exceeding this are pruned.
     pub max_weight: Weight,
-    /// Maximum number of generation results to return; further paths
-    /// are not emitted once this is reached. `None` = unbounded.
+    /// Maximum number of generation results to return: the cheapest this
+    /// many forms. `None` = unbounded.
     pub max_results: Option<usize>,
-    /// Hard cap on total recursive walk steps. Guarantees termination.
-    /// `None` = unbounded.
+    /// Hard cap on the number of paths extended. Guarantees termination;
+    /// when it cuts the search short, the forms found so far are returned,
+    /// still cheapest first. `None` = unbounded.
     pub max_iterations: Option<u64>,
+    /// Only generate forms whose analysis matches this pattern (see
+    /// [`TagPattern`]). `None` = every form.
+    pub tags: Option<TagPattern>,
 }
 
 impl Default for GeneratorConfig {
@@ -74,344 +108,454 @@ impl Default for GeneratorConfig {
             max_weight: Weight(40.0),
             max_results: Some(512),
             max_iterations: Some(200_000),
+            tags: None,
         }
     }
 }
 
+/// Recycled search nodes kept around between pops.
+const NODE_POOL_SIZE: usize = 128;
+
 /// Run forward generation against `lexicon` (a generator FST) and
 /// return every reachable surface form whose input-tape prefix
-/// equals `lemma`.
-pub(crate) fn generate_from_lexicon<T: Transducer>(
+/// equals `lemma`, cheapest first.
+///
+/// This is what [`Speller::generate_with_config`](crate::speller::Speller::generate_with_config)
+/// does with its lexicon; a generator that is not part of a speller, such
+/// as giellaLT's `generator-gt-norm.hfstol`, is passed here directly.
+pub fn generate<T: Transducer>(
     lexicon: &T,
     lemma: &str,
     config: &GeneratorConfig,
 ) -> Vec<GenerationResult> {
-    let Some(lemma_syms) = tokenise_lemma_in(lexicon, lemma) else {
+    if lemma.is_empty() {
+        return Vec::new();
+    }
+    let Some((lemma_syms, foreign)) = tokenise(lexicon, lemma) else {
         return Vec::new();
     };
 
+    let pool = Pool::with_size_and_max(NODE_POOL_SIZE, NODE_POOL_SIZE);
     let flag_size = lexicon.alphabet().state_size().0 as usize;
-    let mut state = WalkState {
+    let mut search = Search {
+        lexicon,
         lemma_syms: &lemma_syms,
-        input_acc: Vec::with_capacity(32),
-        output_acc: Vec::with_capacity(32),
-        flag_state: vec![ValueNumber::ZERO; flag_size],
-        results: Vec::new(),
-        seen: HashSet::new(),
-        on_path: HashSet::new(),
-        iterations: 0,
-        config: *config,
+        foreign: &foreign,
+        config,
+        pool: &pool,
+        steps: vec![Step {
+            parent: None,
+            state: TransitionTableIndex(0),
+            tag: SymbolNumber::ZERO,
+            reads_lemma: false,
+            depth: 0,
+        }],
+        queue: BinaryHeap::with_capacity(256),
+        sequence: 0,
     };
+    let start = TreeNode::empty(&pool, vec![ValueNumber::ZERO; flag_size]);
+    search.push(start, 0, false);
+    search.run()
+}
 
-    walk(
-        lexicon,
-        TransitionTableIndex(0),
-        0,
-        Weight::ZERO,
-        0,
-        &mut state,
-    );
-    state.results
+/// One transition taken on the way to a queued node.
+///
+/// Queued nodes share their paths' prefixes: each step points back at the
+/// one before it, so a path costs one step per transition however many
+/// nodes continue from it.
+struct Step {
+    parent: Option<usize>,
+    /// The lexicon state the transition leads to.
+    state: TransitionTableIndex,
+    /// The tag read on the input tape, or epsilon for anything else.
+    tag: SymbolNumber,
+    /// Whether the transition read a symbol of the lemma.
+    reads_lemma: bool,
+    /// Number of transitions from the start state.
+    depth: usize,
 }
 
-fn walk<T: Transducer>(
-    lexicon: &T,
-    node_state: TransitionTableIndex,
-    lemma_idx: usize,
+/// Min-order wrapper so `BinaryHeap` (a max-heap) pops the cheapest node
+/// first.
+struct Queued<'a> {
+    /// Weight of the path, including the final weight if `complete`.
     weight: Weight,
-    depth: usize,
-    ws: &mut WalkState<'_>,
-) {
-    ws.iterations += 1;
-    if let Some(max) = ws.config.max_iterations {
-        if ws.iterations > max {
-            return;
-        }
+    /// Whether the path is a finished form waiting to be emitted, rather
+    /// than one still to be extended.
+    complete: bool,
+    /// Queueing order, so that ties come out the same on every run.
+    sequence: u64,
+    node: Recycled<'a, TreeNode>,
+    step: usize,
+}
+
+impl PartialEq for Queued<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
     }
-    if depth > ws.config.max_depth {
-        return;
+}
+impl Eq for Queued<'_> {}
+impl PartialOrd for Queued<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
-    if weight > ws.config.max_weight {
-        return;
+}
+impl Ord for Queued<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Reversed: cheapest first out of the max-heap. At equal weight a
+        // finished form goes before the paths still open, and otherwise the
+        // earlier queued goes first.
+        other
+            .weight
+            .cmp(&self.weight)
+            .then_with(|| self.complete.cmp(&other.complete))
+            .then_with(|| other.sequence.cmp(&self.sequence))
     }
-    if let Some(max) = ws.config.max_results {
-        if ws.results.len() >= max {
+}
+
+struct Search<'a, T: Transducer> {
+    lexicon: &'a T,
+    lemma_syms: &'a [SymbolNumber],
+    /// The lemma's characters that have no symbol in the alphabet, standing
+    /// in it as the symbols following the alphabet's last.
+    foreign: &'a [SmolStr],
+    config: &'a GeneratorConfig,
+    pool: &'a Pool<TreeNode>,
+    steps: Vec<Step>,
+    queue: BinaryHeap<Queued<'a>>,
+    sequence: u64,
+}
+
+impl<'a, T: Transducer> Search<'a, T> {
+    fn run(&mut self) -> Vec<GenerationResult> {
+        let mut results = Vec::new();
+        // Multiple paths can yield identical (surface, analysis) pairs; the
+        // first one out of the queue is the cheapest.
+        let mut seen = HashSet::new();
+        let max_results = self.config.max_results.unwrap_or(usize::MAX);
+        let mut iterations = 0u64;
+
+        while let Some(Queued {
+            weight,
+            complete,
+            node,
+            step,
+            ..
+        }) = self.queue.pop()
+        {
+            if results.len() >= max_results {
+                break;
+            }
+
+            // Nothing still queued is cheaper, so this form's place in the
+            // results is final.
+            if complete {
+                let alphabet = self.lexicon.alphabet();
+                let surface = self.surface(&node.string);
+                let tags = self.tags(step);
+                let analysis = alphabet.string_from_symbols(&tags);
+                if seen.insert((surface.clone(), analysis.clone())) {
+                    let key_table = alphabet.key_table();
+                    results.push(GenerationResult {
+                        surface,
+                        analysis,
+                        tags: tags
+                            .iter()
+                            .map(|tag| key_table[tag.0 as usize].clone())
+                            .collect(),
+                        weight,
+                    });
+                }
+                continue;
+            }
+
+            iterations += 1;
+            if self
+                .config
+                .max_iterations
+                .is_some_and(|max| iterations > max)
+            {
+                break;
+            }
+
+            self.expand(&node, step);
+
+            // Emit if we're at a final state and the lemma is fully
+            // consumed on the input tape: queue the path again as a
+            // finished form, at its weight with the final weight added.
+            let state = node.lexicon_state;
+            if node.input_state.0 as usize >= self.lemma_syms.len()
+                && self.lexicon.is_final(state)
+                && self.tags_match(step, None, TagPattern::matches::<SmolStr>)
+            {
+                let final_weight = self.lexicon.final_weight(state).unwrap_or(Weight::ZERO);
+                let mut node = node;
+                node.weight = node.weight + final_weight;
+                self.push(node, step, true);
+            }
+        }
+
+        results
+    }
+
+    /// Queue `node`, reached by `step`, unless it is over the weight limit.
+    fn push(&mut self, node: Recycled<'a, TreeNode>, step: usize, complete: bool) {
+        if node.weight > self.config.max_weight {
             return;
         }
+        self.sequence += 1;
+        self.queue.push(Queued {
+            weight: node.weight,
+            complete,
+            sequence: self.sequence,
+            node,
+            step,
+        });
     }
 
-    // Emit if we're at a final state and the lemma is fully consumed
-    // on the input tape. Multiple paths can yield identical
-    // (surface, analysis) pairs; dedup at emit time.
-    if lemma_idx >= ws.lemma_syms.len() && lexicon.is_final(node_state) {
-        let final_w = lexicon.final_weight(node_state).unwrap_or(Weight::ZERO);
-        let total = weight + final_w;
-        if total <= ws.config.max_weight {
-            let alphabet = lexicon.alphabet();
-            // surface = output tape; analysis = input tape *after* the
-            // lemma chars (i.e. the morphology tag symbols).
-            let surface = alphabet.string_from_symbols(&ws.output_acc);
-            let analysis_syms = &ws.input_acc[ws.lemma_syms.len()..];
-            let analysis = alphabet.string_from_symbols(analysis_syms);
-            let key = (surface.clone(), analysis.clone());
-            if ws.seen.insert(key) {
-                ws.results.push(GenerationResult {
-                    surface,
-                    analysis,
-                    weight: total,
-                });
+    /// Decode an output tape, including the lemma's characters from outside
+    /// the alphabet.
+    fn surface(&self, syms: &[SymbolNumber]) -> SmolStr {
+        let key_table = self.lexicon.alphabet().key_table();
+        syms.iter()
+            .map(|s| match key_table.get(s.0 as usize) {
+                Some(key) => &**key,
+                None => &*self.foreign[s.0 as usize - key_table.len()],
+            })
+            .collect()
+    }
+
+    /// The tags read on the input tape along the path ending in `step`.
+    fn tags(&self, step: usize) -> Vec<SymbolNumber> {
+        let mut tags = vec![];
+        let mut current = Some(step);
+        while let Some(index) = current {
+            let step = &self.steps[index];
+            if step.tag != SymbolNumber::ZERO {
+                tags.push(step.tag);
+            }
+            current = step.parent;
+        }
+        tags.reverse();
+        tags
+    }
+
+    /// Whether the tags along the path ending in `step`, followed by `tag` if
+    /// given, pass `check` against the configured tag pattern. Without a
+    /// pattern, any tags do.
+    fn tags_match(
+        &self,
+        step: usize,
+        tag: Option<SymbolNumber>,
+        check: fn(&TagPattern, &[SmolStr]) -> bool,
+    ) -> bool {
+        let Some(pattern) = &self.config.tags else {
+            return true;
+        };
+        let key_table = self.lexicon.alphabet().key_table();
+        let mut tags = self.tags(step);
+        tags.extend(tag);
+        let tags: Vec<SmolStr> = tags
+            .iter()
+            .map(|tag| key_table[tag.0 as usize].clone())
+            .collect();
+        check(pattern, &tags)
+    }
+
+    /// Whether the path ending in `step` has passed through `state` since
+    /// it last read a symbol of the lemma.
+    fn on_path(&self, step: usize, state: TransitionTableIndex) -> bool {
+        let mut current = Some(step);
+        while let Some(index) = current {
+            let step = &self.steps[index];
+            if step.state == state {
+                return true;
             }
+            if step.reads_lemma {
+                break;
+            }
+            current = step.parent;
         }
+        false
     }
 
-    // 1. Free input moves: epsilon-input arcs and flag diacritics.
-    //
-    //    A state's eps slot lives at `state + 1` (slot 0 is the
-    //    final-state marker), so pass `state.incr()` to the check.
-    if lexicon.has_epsilons_or_flags(node_state.incr()) {
-        if let Some(mut pos) = lexicon.next(node_state, SymbolNumber::ZERO) {
+    fn expand(&mut self, node: &TreeNode, step: usize) {
+        if self.steps[step].depth >= self.config.max_depth {
+            return;
+        }
+        let lexicon = self.lexicon;
+        let state = node.lexicon_state;
+        let lemma_idx = node.input_state.0 as usize;
+
+        // 1. Free input moves: epsilon-input arcs and flag diacritics.
+        //
+        //    A state's eps slot lives at `state + 1` (slot 0 is the
+        //    final-state marker), so pass `state.incr()` to the check.
+        if lexicon.has_epsilons_or_flags(state.incr())
+            && let Some(mut pos) = lexicon.next(state, SymbolNumber::ZERO)
+        {
             let operations = lexicon.alphabet().operations();
             while let Some(trans) = lexicon.take_epsilons_and_flags(pos) {
                 let input_sym = lexicon
                     .transition_input_symbol(pos)
                     .unwrap_or(SymbolNumber::ZERO);
                 if input_sym == SymbolNumber::ZERO {
-                    try_advance(lexicon, input_sym, trans, lemma_idx, weight, depth, ws);
+                    self.advance(node, step, input_sym, None, trans, None);
                 } else if let Some(op) = operations.get(&input_sym) {
-                    try_advance_with_flag(
-                        lexicon, input_sym, op, trans, lemma_idx, weight, depth, ws,
-                    );
+                    self.advance(node, step, input_sym, None, trans, Some(op));
                 }
                 pos = pos.incr();
             }
         }
-    }
 
-    // 2. Symbol input moves.
-    //
-    //    Phase A: while we're still consuming lemma symbols, only
-    //    follow the *exact* next lemma symbol on the input tape
-    //    (input-side walk, like the speller). This is O(1) per step.
-    //
-    //    Phase B: once the lemma is consumed, enumerate all remaining
-    //    input arcs — these are the morphology-tag arcs that carry
-    //    multichar input symbols like `+V`, `+Sg`, etc.
-    //
-    //    `has_transitions(state.incr(), Some(sym))` MUST be checked
-    //    before `next(state, sym)` — see same caveat in input-side
-    //    callers in `speller/worker.rs`.
-    if lemma_idx < ws.lemma_syms.len() {
-        let sym = ws.lemma_syms[lemma_idx];
-        if lexicon.has_transitions(node_state.incr(), Some(sym)) {
-            if let Some(mut pos) = lexicon.next(node_state, sym) {
+        // 2. Symbol input moves.
+        //
+        //    Phase A: while we're still consuming lemma symbols, only
+        //    follow the *exact* next lemma symbol on the input tape
+        //    (input-side walk, like the speller). This is O(1) per step.
+        //    A symbol outside the lexicon's own alphabet is read by the
+        //    identity and unknown arcs instead.
+        //
+        //    Phase B: once the lemma is consumed, enumerate all remaining
+        //    input arcs — these are the morphology-tag arcs that carry
+        //    multichar input symbols like `+V`, `+Sg`, etc.
+        //
+        //    `has_transitions(state.incr(), Some(sym))` MUST be checked
+        //    before `next(state, sym)` — see same caveat in input-side
+        //    callers in `speller/worker.rs`.
+        let alphabet = lexicon.alphabet();
+        let wildcards = [alphabet.identity(), alphabet.unknown()];
+        if lemma_idx < self.lemma_syms.len() {
+            let sym = self.lemma_syms[lemma_idx];
+            let inputs = if sym >= alphabet.initial_symbol_count() {
+                wildcards
+            } else {
+                [Some(sym), None]
+            };
+            for input_sym in inputs.into_iter().flatten() {
+                if lexicon.has_transitions(state.incr(), Some(input_sym))
+                    && let Some(mut pos) = lexicon.next(state, input_sym)
+                {
+                    while let Some(trans) = lexicon.take_non_epsilons(pos, input_sym) {
+                        self.advance(node, step, input_sym, Some(sym), trans, None);
+                        pos = pos.incr();
+                    }
+                }
+            }
+        } else {
+            let alpha_len = alphabet.len();
+            for sym_raw in 1u32..(alpha_len as u32) {
+                let sym = SymbolNumber(sym_raw as u16);
+                // The wildcards read a character of the lemma, not a tag.
+                if wildcards.contains(&Some(sym))
+                    || !lexicon.has_transitions(state.incr(), Some(sym))
+                {
+                    continue;
+                }
+                let Some(mut pos) = lexicon.next(state, sym) else {
+                    continue;
+                };
                 while let Some(trans) = lexicon.take_non_epsilons(pos, sym) {
-                    try_advance(lexicon, sym, trans, lemma_idx, weight, depth, ws);
+                    self.advance(node, step, sym, None, trans, None);
                     pos = pos.incr();
                 }
             }
         }
-    } else {
-        let alpha_len = lexicon.alphabet().len();
-        for sym_raw in 1u32..(alpha_len as u32) {
-            let sym = SymbolNumber(sym_raw as u16);
-            if !lexicon.has_transitions(node_state.incr(), Some(sym)) {
-                continue;
-            }
-            let Some(mut pos) = lexicon.next(node_state, sym) else {
-                continue;
-            };
-            while let Some(trans) = lexicon.take_non_epsilons(pos, sym) {
-                try_advance(lexicon, sym, trans, lemma_idx, weight, depth, ws);
-                pos = pos.incr();
-            }
-        }
     }
-}
 
-/// Take a single transition: push input/output symbols, advance lemma
-/// index if we matched the next expected lemma symbol on the input
-/// tape, recurse, then undo on backtrack.
-#[inline]
-fn try_advance<T: Transducer>(
-    lexicon: &T,
-    input_sym: SymbolNumber,
-    trans: crate::transducer::symbol_transition::SymbolTransition,
-    lemma_idx: usize,
-    weight: Weight,
-    depth: usize,
-    ws: &mut WalkState<'_>,
-) {
-    let target = match trans.target() {
-        Some(t) => t,
-        None => return,
-    };
-    let output_sym = trans.symbol().unwrap_or(SymbolNumber::ZERO);
-    let trans_w = trans.weight().unwrap_or(Weight::ZERO);
-
-    let alphabet = lexicon.alphabet();
-    let input_is_real = input_sym != SymbolNumber::ZERO && !alphabet.is_flag(input_sym);
-    let output_is_real = output_sym != SymbolNumber::ZERO && !alphabet.is_flag(output_sym);
-
-    // Lemma matching on the input tape: while there are unmatched
-    // lemma symbols left, a real (non-eps, non-flag) input must equal
-    // the next expected lemma symbol. Once the lemma is consumed,
-    // any further real input must be a multichar tag — a single-
-    // grapheme input would mean we're walking through a longer lemma
-    // that has the requested lemma as a prefix.
-    let new_lemma_idx = if lemma_idx < ws.lemma_syms.len() && input_is_real {
-        if input_sym == ws.lemma_syms[lemma_idx] {
-            lemma_idx + 1
-        } else {
-            return;
-        }
-    } else if input_is_real && lemma_idx >= ws.lemma_syms.len() {
-        let key = &alphabet.key_table()[input_sym.0 as usize];
-        if key.chars().count() <= 1 {
+    /// Take a single transition from `node`: queue the node it leads to.
+    ///
+    /// `lemma_sym` is the lemma symbol the transition reads, if it reads
+    /// the next one; the lemma index is then advanced past it.
+    fn advance(
+        &mut self,
+        node: &TreeNode,
+        step: usize,
+        input_sym: SymbolNumber,
+        lemma_sym: Option<SymbolNumber>,
+        trans: SymbolTransition,
+        op: Option<&FlagDiacriticOperation>,
+    ) {
+        let Some(target) = trans.target() else {
             return;
-        }
-        lemma_idx
-    } else {
-        lemma_idx
-    };
+        };
+        let mut output_sym = trans.symbol().unwrap_or(SymbolNumber::ZERO);
 
-    // Path-local cycle detection. Without it, flag-mediated cycles
-    // and tag-cycles (compound-forming, etc.) blow up the search
-    // exponentially. Different DAG-paths to the same target are still
-    // explored: emission-time dedup by (surface, analysis) folds
-    // duplicates afterwards.
-    if !ws.on_path.insert(target) {
-        return;
-    }
+        let alphabet = self.lexicon.alphabet();
+        let input_is_real = input_sym != SymbolNumber::ZERO && !alphabet.is_flag(input_sym);
 
-    if input_is_real {
-        ws.input_acc.push(input_sym);
-    }
-    if output_is_real {
-        ws.output_acc.push(output_sym);
-    }
-
-    walk(
-        lexicon,
-        target,
-        new_lemma_idx,
-        weight + trans_w,
-        depth + 1,
-        ws,
-    );
-
-    if output_is_real {
-        ws.output_acc.pop();
-    }
-    if input_is_real {
-        ws.input_acc.pop();
-    }
-    ws.on_path.remove(&target);
-}
+        // A wildcard on the output tape writes the lemma character its
+        // partner on the input tape read, as the speller does. With no such
+        // character there is nothing to write.
+        let is_wildcard = |sym| sym == alphabet.identity() || sym == alphabet.unknown();
+        if is_wildcard(Some(output_sym)) {
+            match lemma_sym {
+                Some(sym) if is_wildcard(Some(input_sym)) => output_sym = sym,
+                _ => return,
+            }
+        }
+        let output_is_real = output_sym != SymbolNumber::ZERO && !alphabet.is_flag(output_sym);
 
-/// Mutable state threaded through the recursive walk.
-struct WalkState<'a> {
-    lemma_syms: &'a [SymbolNumber],
-    input_acc: Vec<SymbolNumber>,
-    output_acc: Vec<SymbolNumber>,
-    /// Flag-diacritic feature values, indexed by feature symbol number.
-    /// Sized to `alphabet.state_size()`.
-    flag_state: Vec<ValueNumber>,
-    results: Vec<GenerationResult>,
-    /// Emitted-result dedup keyed on (surface, analysis).
-    seen: HashSet<(SmolStr, SmolStr)>,
-    /// States currently on the root-to-here path; used to break cycles.
-    on_path: HashSet<TransitionTableIndex>,
-    /// Total recursive `walk` invocations so far; bounded by
-    /// [`GeneratorConfig::max_iterations`].
-    iterations: u64,
-    config: GeneratorConfig,
-}
+        // Lemma matching on the input tape: while there are unmatched
+        // lemma symbols left, only arcs reading the next one are taken.
+        // Once the lemma is consumed, any further real input must be a
+        // multichar tag — a single-grapheme input would mean we're
+        // walking through a longer lemma that has the requested lemma as
+        // a prefix.
+        let lemma_idx = node.input_state.0 as usize;
+        let mut tag = SymbolNumber::ZERO;
+        let new_lemma_idx = if lemma_sym.is_some() {
+            lemma_idx + 1
+        } else if input_is_real {
+            if !alphabet.is_multichar(input_sym) {
+                return;
+            }
+            // No continuation of these tags can match: drop the branch
+            // here rather than walk the paradigm below it.
+            if !self.tags_match(step, Some(input_sym), TagPattern::matches_prefix::<SmolStr>) {
+                return;
+            }
+            tag = input_sym;
+            lemma_idx
+        } else {
+            lemma_idx
+        };
 
-/// Apply a flag-diacritic operation to the walker's flag state. See
-/// `tree_node::TreeNode::apply_operation` in the speller for the
-/// canonical implementation; this is a port that mutates in place
-/// for use by the DFS walker, returning the previous value to enable
-/// O(1) backtrack.
-fn apply_flag(
-    flag_state: &mut [ValueNumber],
-    op: &FlagDiacriticOperation,
-) -> Option<Option<ValueNumber>> {
-    let idx = op.feature.0 as usize;
-    match op.operation {
-        FlagDiacriticOperator::PositiveSet => {
-            let prev = flag_state[idx];
-            flag_state[idx] = op.value;
-            Some(Some(prev))
-        }
-        FlagDiacriticOperator::NegativeSet => {
-            let prev = flag_state[idx];
-            flag_state[idx] = op.value.invert();
-            Some(Some(prev))
-        }
-        FlagDiacriticOperator::Require => {
-            let ok = if op.value.0 == 0 {
-                flag_state[idx] != ValueNumber(0)
-            } else {
-                flag_state[idx] == op.value
-            };
-            if ok { Some(None) } else { None }
-        }
-        FlagDiacriticOperator::Disallow => {
-            let ok = if op.value.0 == 0 {
-                flag_state[idx] == ValueNumber(0)
-            } else {
-                flag_state[idx] != op.value
-            };
-            if ok { Some(None) } else { None }
+        // Path-local cycle detection. Without it, flag-mediated cycles
+        // and tag-cycles (compound-forming, etc.) blow up the search
+        // exponentially. A cycle reading the lemma is bounded by the
+        // lemma's length, and is how a lexicon reads a name of any length
+        // with an identity loop, so it is allowed. Different DAG-paths to
+        // the same target are still explored: emission-time dedup by
+        // (surface, analysis) folds duplicates afterwards.
+        if lemma_sym.is_none() && self.on_path(step, target) {
+            return;
         }
-        FlagDiacriticOperator::Clear => {
-            let prev = flag_state[idx];
-            flag_state[idx] = ValueNumber(0);
-            Some(Some(prev))
+
+        let mut next = match op {
+            Some(op) => match node.apply_operation(self.pool, op, &trans) {
+                Some(next) => next,
+                None => return,
+            },
+            None => node.apply_transition(self.pool, &trans),
+        };
+        if output_is_real {
+            next.string.push(output_sym);
         }
-        FlagDiacriticOperator::Unification => {
-            let f = flag_state[idx];
-            if f.0 == 0 || f == op.value || (f.0 < 0 && f.invert() != op.value) {
-                let prev = flag_state[idx];
-                flag_state[idx] = op.value;
-                Some(Some(prev))
-            } else {
-                None
-            }
+        next.input_state = InputIndex(new_lemma_idx as u32);
+        if next.weight > self.config.max_weight {
+            return;
         }
-    }
-}
-
-#[inline]
-fn try_advance_with_flag<T: Transducer>(
-    lexicon: &T,
-    input_sym: SymbolNumber,
-    op: &FlagDiacriticOperation,
-    trans: crate::transducer::symbol_transition::SymbolTransition,
-    lemma_idx: usize,
-    weight: Weight,
-    depth: usize,
-    ws: &mut WalkState<'_>,
-) {
-    let undo = match apply_flag(&mut ws.flag_state, op) {
-        Some(u) => u,
-        None => return,
-    };
-    try_advance(lexicon, input_sym, trans, lemma_idx, weight, depth, ws);
-    if let Some(prev) = undo {
-        ws.flag_state[op.feature.0 as usize] = prev;
-    }
-}
 
-/// Map a lemma string to a sequence of symbol numbers in the lexicon's
-/// alphabet. Returns `None` if any grapheme is unknown.
-fn tokenise_lemma_in<T: Transducer>(transducer: &T, lemma: &str) -> Option<Vec<SymbolNumber>> {
-    let mut syms = Vec::new();
-    let alphabet = transducer.alphabet();
-    for grapheme in Graphemes::new(lemma) {
-        let key = SmolStr::new(grapheme);
-        let sym = alphabet.string_to_symbol().get(&key).copied()?;
-        syms.push(sym);
+        let depth = self.steps[step].depth + 1;
+        self.steps.push(Step {
+            parent: Some(step),
+            state: target,
+            tag,
+            reads_lemma: lemma_sym.is_some(),
+            depth,
+        });
+        let next_step = self.steps.len() - 1;
+        self.push(next, next_step, false);
     }
-    Some(syms)
 }
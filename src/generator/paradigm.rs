@@ -0,0 +1,302 @@
+//! Paradigm tables: every form of a lemma, grouped by its tags.
+//!
+//! [`Paradigm::generate`] generates the forms of a lemma and groups them the
+//! way a grammar lists them: by part of speech, the first tag of an analysis,
+//! and within that by the tags that follow, so all singular forms come
+//! together, then all plural forms, and so on. The groups and the rows in
+//! them keep the order the generator first produced them in, cheapest first.
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use super::{GenerationResult, GeneratorConfig};
+use crate::transducer::Transducer;
+use crate::types::Weight;
+
+/// How a [`Paradigm`] is written out by [`Paradigm::render`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum ParadigmFormat {
+    /// aligned columns for reading, one row per analysis
+    #[default]
+    Table,
+    /// tab-separated values, one line per form: lemma, part of speech, the
+    /// other tags, surface form and weight
+    Tsv,
+    /// the [`Paradigm`] serialized as JSON
+    Json,
+}
+
+/// Error parsing a [`ParadigmFormat`] from a string.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown paradigm format '{0}'. Expected one of: table, tsv, json")]
+pub struct ParseParadigmFormatError(pub String);
+
+impl std::str::FromStr for ParadigmFormat {
+    type Err = ParseParadigmFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "table" => Ok(ParadigmFormat::Table),
+            "tsv" => Ok(ParadigmFormat::Tsv),
+            "json" => Ok(ParadigmFormat::Json),
+            _ => Err(ParseParadigmFormatError(s.to_string())),
+        }
+    }
+}
+
+/// One surface form in a paradigm.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParadigmForm {
+    /// the surface form
+    pub surface: SmolStr,
+    /// the weight of the path generating it
+    pub weight: Weight,
+}
+
+/// The forms of one analysis, such as every spelling of the genitive plural.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParadigmRow {
+    /// the tags after the part of speech (`["+Pl", "+Gen"]`)
+    pub tags: Vec<SmolStr>,
+    /// the forms, cheapest first
+    pub forms: Vec<ParadigmForm>,
+}
+
+/// The rows of one part of speech.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParadigmGroup {
+    /// the first tag of the analyses (`"+N"`); empty for forms without tags
+    pub pos: SmolStr,
+    /// the rows, those sharing their first tag next to each other
+    pub rows: Vec<ParadigmRow>,
+}
+
+/// Every form of a lemma, grouped by part of speech and tags.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Paradigm {
+    /// the lemma the forms were generated from
+    pub lemma: SmolStr,
+    /// the parts of speech, each with its rows
+    pub groups: Vec<ParadigmGroup>,
+}
+
+impl Paradigm {
+    /// Generate the paradigm of `lemma` with the generator FST `generator`;
+    /// see [`generate`](super::generate). For a speller's lexicon, pass the
+    /// results of
+    /// [`Speller::generate_with_config`](crate::speller::Speller::generate_with_config)
+    /// to [`Paradigm::from_results`].
+    ///
+    /// A paradigm is usually wanted whole, so leave
+    /// [`GeneratorConfig::max_results`] unset, or high enough for the largest
+    /// one.
+    pub fn generate<T: Transducer>(
+        generator: &T,
+        lemma: &str,
+        config: &GeneratorConfig,
+    ) -> Paradigm {
+        Paradigm::from_results(lemma, &super::generate(generator, lemma, config))
+    }
+
+    /// Group generation results for `lemma` into a paradigm.
+    pub fn from_results(lemma: &str, results: &[GenerationResult]) -> Paradigm {
+        let mut groups: Vec<ParadigmGroup> = vec![];
+        for result in results {
+            let (pos, tags) = match result.tags.split_first() {
+                Some((pos, tags)) => (pos.clone(), tags),
+                None => (SmolStr::default(), &[][..]),
+            };
+            let group = match groups.iter().position(|g| g.pos == pos) {
+                Some(index) => &mut groups[index],
+                None => {
+                    groups.push(ParadigmGroup { pos, rows: vec![] });
+                    groups.last_mut().expect("just pushed")
+                }
+            };
+            let form = ParadigmForm {
+                surface: result.surface.clone(),
+                weight: result.weight,
+            };
+            match group.rows.iter_mut().find(|row| row.tags == tags) {
+                Some(row) => row.forms.push(form),
+                None => group.rows.push(ParadigmRow {
+                    tags: tags.to_vec(),
+                    forms: vec![form],
+                }),
+            }
+        }
+
+        // Bring the rows sharing their first tag together, keeping the order
+        // in which each first tag was first seen.
+        for group in &mut groups {
+            let mut order: Vec<&SmolStr> = vec![];
+            for row in &group.rows {
+                let first = row.tags.first().unwrap_or(&group.pos);
+                if !order.contains(&first) {
+                    order.push(first);
+                }
+            }
+            let order: Vec<SmolStr> = order.into_iter().cloned().collect();
+            group.rows.sort_by_key(|row| {
+                let first = row.tags.first().unwrap_or(&group.pos);
+                order.iter().position(|tag| tag == first)
+            });
+        }
+
+        Paradigm {
+            lemma: lemma.into(),
+            groups,
+        }
+    }
+
+    /// Whether the lemma generated no forms.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Write the paradigm out in `format`.
+    pub fn render(&self, format: ParadigmFormat) -> String {
+        match format {
+            ParadigmFormat::Table => self.to_table(),
+            ParadigmFormat::Tsv => self.to_tsv(),
+            ParadigmFormat::Json => {
+                serde_json::to_string_pretty(self).expect("a paradigm serializes to JSON")
+            }
+        }
+    }
+
+    /// The paradigm as aligned columns: a heading for each part of speech,
+    /// then one row per analysis, with a tag left out where it repeats the
+    /// row above.
+    fn to_table(&self) -> String {
+        let mut out = String::new();
+        for group in &self.groups {
+            let columns = group.rows.iter().map(|r| r.tags.len()).max().unwrap_or(0);
+            let mut widths = vec![0; columns];
+            for row in &group.rows {
+                for (width, tag) in widths.iter_mut().zip(&row.tags) {
+                    *width = (*width).max(tag.chars().count());
+                }
+            }
+
+            let _ = writeln!(out, "{} {}", self.lemma, group.pos);
+            let mut previous: &[SmolStr] = &[];
+            for row in &group.rows {
+                let mut line = String::from(" ");
+                let mut same = true;
+                for (i, width) in widths.iter().enumerate() {
+                    let tag = row.tags.get(i).map(SmolStr::as_str).unwrap_or_default();
+                    same = same && previous.get(i).is_some_and(|p| p == tag);
+                    let shown = if same { "" } else { tag };
+                    let _ = write!(line, " {shown:<width$}");
+                }
+                let forms: Vec<&str> = row.forms.iter().map(|f| f.surface.as_str()).collect();
+                let _ = writeln!(out, "{}   {}", line, forms.join(", "));
+                previous = &row.tags;
+            }
+        }
+        out
+    }
+
+    /// The paradigm as tab-separated values, one line per form.
+    fn to_tsv(&self) -> String {
+        let mut out = String::new();
+        for group in &self.groups {
+            for row in &group.rows {
+                for form in &row.forms {
+                    let _ = writeln!(
+                        out,
+                        "{}\t{}\t{}\t{}\t{}",
+                        self.lemma,
+                        group.pos,
+                        row.tags.concat(),
+                        form.surface,
+                        form.weight
+                    );
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(surface: &str, tags: &[&str], weight: f32) -> GenerationResult {
+        GenerationResult {
+            surface: surface.into(),
+            analysis: tags.concat().into(),
+            tags: tags.iter().map(|&t| t.into()).collect(),
+            weight: Weight(weight),
+        }
+    }
+
+    fn paradigm() -> Paradigm {
+        Paradigm::from_results(
+            "giella",
+            &[
+                result("giella", &["+N", "+Sg", "+Nom"], 0.0),
+                result("gielat", &["+N", "+Pl", "+Nom"], 0.0),
+                result("giela", &["+N", "+Sg", "+Gen"], 0.0),
+                result("gielaid", &["+N", "+Pl", "+Acc"], 0.5),
+                result("giellii", &["+N", "+Sg", "+Ill"], 1.0),
+                result("gillii", &["+N", "+Sg", "+Ill"], 2.0),
+                result("giellat", &["+V", "+Inf"], 3.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn groups_by_pos_then_tags() {
+        let paradigm = paradigm();
+        let pos: Vec<&str> = paradigm.groups.iter().map(|g| g.pos.as_str()).collect();
+        assert_eq!(pos, ["+N", "+V"]);
+        let rows: Vec<String> = paradigm.groups[0]
+            .rows
+            .iter()
+            .map(|r| r.tags.concat())
+            .collect();
+        assert_eq!(
+            rows,
+            ["+Sg+Nom", "+Sg+Gen", "+Sg+Ill", "+Pl+Nom", "+Pl+Acc"]
+        );
+        let ill = &paradigm.groups[0].rows[2];
+        let forms: Vec<&str> = ill.forms.iter().map(|f| f.surface.as_str()).collect();
+        assert_eq!(forms, ["giellii", "gillii"]);
+    }
+
+    #[test]
+    fn renders_table_and_tsv() {
+        let paradigm = paradigm();
+        let table = paradigm.render(ParadigmFormat::Table);
+        assert_eq!(
+            table.lines().collect::<Vec<_>>(),
+            [
+                "giella +N",
+                "  +Sg +Nom   giella",
+                "      +Gen   giela",
+                "      +Ill   giellii, gillii",
+                "  +Pl +Nom   gielat",
+                "      +Acc   gielaid",
+                "giella +V",
+                "  +Inf   giellat",
+            ]
+        );
+        let tsv = paradigm.render(ParadigmFormat::Tsv);
+        assert_eq!(tsv.lines().count(), 7);
+        assert_eq!(tsv.lines().nth(3), Some("giella\t+N\t+Sg+Ill\tgillii\t2"));
+        let json: Paradigm = serde_json::from_str(&paradigm.render(ParadigmFormat::Json)).unwrap();
+        assert_eq!(json, paradigm);
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!("TSV".parse(), Ok(ParadigmFormat::Tsv));
+        assert!("csv".parse::<ParadigmFormat>().is_err());
+    }
+}
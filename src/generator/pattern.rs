@@ -0,0 +1,217 @@
+//! Patterns over the tags of generated forms.
+//!
+//! A [`TagPattern`] is a sequence of tags and wildcards, matched against the
+//! tags of a form one whole tag at a time. Tags are written as they read on
+//! the generator's input tape, either run together, each starting with `+` as
+//! in giellaLT (`"+N+Pl+Gen"`), or separated by spaces (`"N Pl Gen"`). A `*`
+//! stands for any number of whole tags: `"+V+Ind+Prs*"` matches every present
+//! indicative, `"*+Gen"` every genitive, and `"+N*"` every noun but not a
+//! `+Num`.
+//!
+//! The generator checks the tags of a path as it reads them, and leaves a
+//! path as soon as no continuation of its tags can match.
+use std::fmt;
+
+use smol_str::SmolStr;
+
+/// The wildcard standing for any number of tags.
+const WILDCARD: char = '*';
+
+/// One element of a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Tag(SmolStr),
+    Wildcard,
+}
+
+/// A pattern the tags of a generated form must match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagPattern {
+    pattern: SmolStr,
+    tokens: Vec<Token>,
+}
+
+impl TagPattern {
+    /// Create a pattern from its text, in which `*` matches any number of
+    /// tags, and tags start with `+` or are separated by spaces.
+    pub fn new(pattern: &str) -> TagPattern {
+        let mut tokens = vec![];
+        for word in pattern.split_whitespace() {
+            let mut tag = String::new();
+            for ch in word.chars() {
+                if (ch == WILDCARD || ch == '+') && !tag.is_empty() {
+                    tokens.push(Token::Tag(std::mem::take(&mut tag).into()));
+                }
+                match ch {
+                    WILDCARD => tokens.push(Token::Wildcard),
+                    _ => tag.push(ch),
+                }
+            }
+            if !tag.is_empty() {
+                tokens.push(Token::Tag(tag.into()));
+            }
+        }
+        TagPattern {
+            pattern: pattern.into(),
+            tokens,
+        }
+    }
+
+    /// A pattern matching exactly `tags`, in this order.
+    pub fn exact<S: AsRef<str>>(tags: &[S]) -> TagPattern {
+        let tags: Vec<&str> = tags.iter().map(AsRef::as_ref).collect();
+        let separator = match tags.iter().all(|tag| tag.starts_with('+')) {
+            true => "",
+            false => " ",
+        };
+        TagPattern {
+            pattern: tags.join(separator).into(),
+            tokens: tags.iter().map(|&tag| Token::Tag(tag.into())).collect(),
+        }
+    }
+
+    /// The pattern text.
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Whether `tags` matches the whole pattern.
+    pub fn matches<S: AsRef<str>>(&self, tags: &[S]) -> bool {
+        self.positions(tags)
+            .is_some_and(|live| live[self.tokens.len()])
+    }
+
+    /// Whether `tags` can be continued into tags matching the pattern.
+    pub fn matches_prefix<S: AsRef<str>>(&self, tags: &[S]) -> bool {
+        self.positions(tags).is_some()
+    }
+
+    /// The pattern positions reachable after reading `tags`, or `None` if
+    /// there are none left.
+    fn positions<S: AsRef<str>>(&self, tags: &[S]) -> Option<Vec<bool>> {
+        let len = self.tokens.len();
+        let mut live = vec![false; len + 1];
+        live[0] = true;
+        self.close(&mut live);
+
+        for tag in tags {
+            let tag = tag.as_ref();
+            let mut next = vec![false; len + 1];
+            for (i, token) in self.tokens.iter().enumerate() {
+                if !live[i] {
+                    continue;
+                }
+                match token {
+                    Token::Wildcard => next[i] = true,
+                    Token::Tag(t) if t == tag => next[i + 1] = true,
+                    Token::Tag(_) => {}
+                }
+            }
+            self.close(&mut next);
+            if !next.contains(&true) {
+                return None;
+            }
+            live = next;
+        }
+
+        Some(live)
+    }
+
+    /// Add the positions past every wildcard that can match nothing.
+    fn close(&self, live: &mut [bool]) {
+        for (i, token) in self.tokens.iter().enumerate() {
+            if live[i] && *token == Token::Wildcard {
+                live[i + 1] = true;
+            }
+        }
+    }
+}
+
+impl From<&str> for TagPattern {
+    fn from(pattern: &str) -> TagPattern {
+        TagPattern::new(pattern)
+    }
+}
+
+impl fmt::Display for TagPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `"+N+Pl"` as the tags `["+N", "+Pl"]`.
+    fn split(analysis: &str) -> Vec<String> {
+        analysis
+            .split('+')
+            .skip(1)
+            .map(|tag| format!("+{tag}"))
+            .collect()
+    }
+
+    #[test]
+    fn exact_tags() {
+        let pattern = TagPattern::new("+N+Pl+Gen");
+        assert!(pattern.matches(&split("+N+Pl+Gen")));
+        assert!(!pattern.matches(&split("+N+Pl")));
+        assert!(!pattern.matches(&split("+N+Pl+Gen+PxSg1")));
+        assert!(pattern.matches_prefix::<&str>(&[]));
+        assert!(pattern.matches_prefix(&split("+N+Pl")));
+        assert!(!pattern.matches_prefix(&split("+N+Sg")));
+        assert!(!pattern.matches_prefix(&split("+V")));
+        // The same text split into other tags is another sequence.
+        assert!(!pattern.matches(&["+N+Pl", "+Gen"]));
+        assert!(!pattern.matches(&["+N", "+Pl+Gen"]));
+    }
+
+    #[test]
+    fn exact_sequences() {
+        let pattern = TagPattern::exact(&["+N", "+Pl", "+Gen"]);
+        assert_eq!(pattern, TagPattern::new("+N+Pl+Gen"));
+        assert!(pattern.matches(&["+N", "+Pl", "+Gen"]));
+        assert!(!pattern.matches(&["+N+Pl", "+Gen"]));
+        assert!(!pattern.matches_prefix(&["+N+Pl"]));
+
+        let pattern = TagPattern::exact(&["N", "Pl"]);
+        assert_eq!(pattern, TagPattern::new("N Pl"));
+        assert!(pattern.matches(&["N", "Pl"]));
+        assert!(!pattern.matches(&["NPl"]));
+    }
+
+    #[test]
+    fn wildcards() {
+        let pattern = TagPattern::new("+V+Ind+Prs*");
+        assert!(pattern.matches(&split("+V+Ind+Prs")));
+        assert!(pattern.matches(&split("+V+Ind+Prs+Sg1")));
+        assert!(!pattern.matches(&split("+V+Ind+Prt+Sg1")));
+        assert!(!pattern.matches_prefix(&split("+V+Ind+Prt")));
+
+        let pattern = TagPattern::new("*+Gen");
+        assert!(pattern.matches(&split("+N+Pl+Gen")));
+        assert!(pattern.matches(&split("+Gen")));
+        assert!(!pattern.matches(&split("+N+Pl+Gen+PxSg1")));
+        assert!(pattern.matches_prefix(&split("+N+Pl+Gen+PxSg1")));
+
+        let pattern = TagPattern::new("+N*+Gen*");
+        assert!(pattern.matches(&split("+N+Sg+Gen+PxSg1")));
+        assert!(!pattern.matches(&split("+V+Gen")));
+        assert!(TagPattern::new("*").matches::<&str>(&[]));
+        assert!(TagPattern::new("N * Gen").matches(&["N", "Sg", "Gen"]));
+    }
+
+    // A wildcard stands for whole tags, never for the rest of one.
+    #[test]
+    fn wildcards_span_whole_tags() {
+        let pattern = TagPattern::new("+N*");
+        assert!(pattern.matches(&split("+N+Sg")));
+        assert!(!pattern.matches(&split("+Num+Sg")));
+        assert!(!pattern.matches_prefix(&split("+Num")));
+
+        let pattern = TagPattern::new("+V+Ind+Prs*");
+        assert!(!pattern.matches(&split("+V+Ind+PrsPrc")));
+        assert!(!pattern.matches_prefix(&split("+V+Ind+PrsPrc")));
+    }
+}
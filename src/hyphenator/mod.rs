@@ -0,0 +1,180 @@
+//! Hyphenation with a hyphenator transducer.
+//!
+//! giellaLT compiles a hyphenator for each language: a transducer reading a
+//! word and writing it back with a mark, `^` by default, at every point where
+//! it may be broken across lines. A word can be hyphenated more than one way,
+//! and each way is a path with a weight, so the cheapest is the best.
+//!
+//! A `.bhfst` archive declares its hyphenator in its metadata (see
+//! [`SpellerMetadataHyphenator`](crate::archive::meta::SpellerMetadataHyphenator)),
+//! and [`SpellerArchive::hyphenator`](crate::archive::SpellerArchive::hyphenator)
+//! gives it as a [`Hyphenate`] trait object.
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use unic_segment::Graphemes;
+
+use crate::tokenizer::case_handling::{CaseMode, word_variants};
+use crate::transducer::Transducer;
+use crate::transducer::lookup::lookup;
+use crate::types::Weight;
+
+/// The mark a hyphenator writes at a hyphenation point, unless its metadata
+/// says otherwise.
+pub const DEFAULT_MARK: &str = "^";
+
+/// A point where a word may be hyphenated.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HyphenationPoint {
+    /// byte offset in the word of the character the break comes before
+    pub index: usize,
+    /// the weight of the cheapest hyphenation with this point
+    pub weight: Weight,
+}
+
+/// One way of hyphenating a word.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Hyphenation {
+    /// byte offsets in the word of the characters the breaks come before, in
+    /// order
+    pub points: Vec<usize>,
+    /// the weight of the hyphenation
+    pub weight: Weight,
+}
+
+/// Hyphenation of words.
+pub trait Hyphenate {
+    /// The points where `word` may be hyphenated, ranked: those of the
+    /// cheapest hyphenations first, and in the order of the word among equals.
+    ///
+    /// Every point of every hyphenation is given, once, at the weight of the
+    /// cheapest hyphenation it is in.
+    #[must_use]
+    fn hyphenate(&self, word: &str) -> Vec<HyphenationPoint> {
+        let mut points: Vec<HyphenationPoint> = vec![];
+        for hyphenation in self.hyphenations(word) {
+            for &index in &hyphenation.points {
+                if !points.iter().any(|p| p.index == index) {
+                    points.push(HyphenationPoint {
+                        index,
+                        weight: hyphenation.weight,
+                    });
+                }
+            }
+        }
+        points.sort_by(|a, b| a.weight.cmp(&b.weight).then(a.index.cmp(&b.index)));
+        points
+    }
+
+    /// The ways `word` can be hyphenated, cheapest first.
+    ///
+    /// A word the hyphenator does not know in the case it is written in is
+    /// looked up in the case variants [`word_variants`] gives, as the speller
+    /// does: "Giella" is hyphenated as "giella" is.
+    #[must_use]
+    fn hyphenations(&self, word: &str) -> Vec<Hyphenation>;
+}
+
+/// A hyphenator reading words with a transducer.
+#[derive(Debug)]
+pub struct Hyphenator<T: Transducer> {
+    transducer: T,
+    mark: SmolStr,
+}
+
+impl<T: Transducer> Hyphenator<T> {
+    /// Create a hyphenator writing `mark` at hyphenation points.
+    pub fn new(transducer: T, mark: &str) -> Hyphenator<T> {
+        Hyphenator {
+            transducer,
+            mark: mark.into(),
+        }
+    }
+
+    /// The hyphenator transducer.
+    pub fn transducer(&self) -> &T {
+        &self.transducer
+    }
+
+    /// The hyphenations of `word` exactly as written, as grapheme indices.
+    fn lookup(&self, word: &str) -> Vec<(Vec<usize>, Weight)> {
+        let graphemes: Vec<&str> = Graphemes::new(word).collect();
+        let mut hyphenations = vec![];
+        for (output, weight) in lookup(&self.transducer, word) {
+            // An output that is not the word with marks added is not a
+            // hyphenation of it.
+            let mut points = vec![];
+            let mut index = 0;
+            let mut matches = true;
+            for grapheme in Graphemes::new(&output) {
+                if grapheme == self.mark {
+                    if index > 0 && index < graphemes.len() && points.last() != Some(&index) {
+                        points.push(index);
+                    }
+                } else if graphemes.get(index) == Some(&grapheme) {
+                    index += 1;
+                } else {
+                    matches = false;
+                    break;
+                }
+            }
+            if matches && index == graphemes.len() {
+                hyphenations.push((points, weight));
+            }
+        }
+        hyphenations
+    }
+}
+
+impl<T: Transducer> Hyphenate for Hyphenator<T> {
+    fn hyphenations(&self, word: &str) -> Vec<Hyphenation> {
+        if word.is_empty() {
+            return vec![];
+        }
+
+        let case = word_variants(word);
+        let word_len = Graphemes::new(word).count();
+        let mut best: HashMap<Vec<usize>, Weight> = HashMap::new();
+        let variants = std::iter::once(word).chain(
+            case.words
+                .iter()
+                .map(SmolStr::as_str)
+                .filter(|w| *w != word),
+        );
+        for variant in variants {
+            // Points are carried over from a variant by their grapheme index,
+            // which only works if changing the case kept the graphemes.
+            if Graphemes::new(variant).count() != word_len {
+                continue;
+            }
+            let hyphenations = self.lookup(variant);
+            let found = !hyphenations.is_empty();
+            for (points, weight) in hyphenations {
+                let entry = best.entry(points).or_insert(weight);
+                if *entry > weight {
+                    *entry = weight;
+                }
+            }
+            if found && matches!(case.mode, CaseMode::FirstResults) {
+                break;
+            }
+        }
+
+        let offsets: Vec<usize> = Graphemes::new(word)
+            .scan(0, |offset, grapheme| {
+                let start = *offset;
+                *offset += grapheme.len();
+                Some(start)
+            })
+            .collect();
+        let mut hyphenations: Vec<Hyphenation> = best
+            .into_iter()
+            .map(|(points, weight)| Hyphenation {
+                points: points.into_iter().map(|i| offsets[i]).collect(),
+                weight,
+            })
+            .collect();
+        hyphenations.sort_by(|a, b| a.weight.cmp(&b.weight).then(a.points.cmp(&b.points)));
+        hyphenations
+    }
+}
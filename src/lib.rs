@@ -20,7 +20,9 @@ Further examples of how to use divvunspell library can be found in the
 
 pub mod archive;
 
+pub mod check;
 pub mod generator;
+pub mod hyphenator;
 pub mod paths;
 pub mod speller;
 pub mod tokenizer;
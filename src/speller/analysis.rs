@@ -0,0 +1,193 @@
+//! Morphological analyses split into lemma and tags.
+//!
+//! [`Speller::analyze_input`](super::Speller::analyze_input) gives an analysis
+//! as one string, `"ruovttu+N+Cmp/SgGen+Cmp#giella+N+Sg+Nom"`, which callers
+//! would have to take apart again, guessing where a tag ends. An [`Analysis`]
+//! is built from the symbols of the path instead: a multicharacter symbol of
+//! the alphabet is a tag, and every other symbol is a character of the lemma.
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use crate::transducer::alphabet::TransducerAlphabet;
+use crate::types::{SymbolNumber, Weight};
+
+/// One part of a compound word, as it appears in its analysis.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CompoundPart {
+    /// the lemma of the part
+    pub lemma: SmolStr,
+    /// the tags of the part, ending with the compound boundary tag
+    pub tags: Vec<SmolStr>,
+}
+
+/// A morphological analysis of a word form.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Analysis {
+    /// the lemma (of the last part, for a compound)
+    pub lemma: SmolStr,
+    /// the tags, in the order the analysis gives them (of the last part, for
+    /// a compound)
+    pub tags: Vec<SmolStr>,
+    /// the weight of the analysis
+    pub weight: Weight,
+    /// the parts of a compound before its last, in order; empty if the word
+    /// is not a compound
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compound_parts: Vec<CompoundPart>,
+}
+
+impl Analysis {
+    /// Build the analysis of the path writing `symbols` in `alphabet`.
+    ///
+    /// A tag ending in `#`, as Giella's `+Cmp#` does, is a compound boundary,
+    /// and closes a part.
+    pub(crate) fn from_symbols(
+        alphabet: &TransducerAlphabet,
+        symbols: &[SymbolNumber],
+        weight: Weight,
+    ) -> Analysis {
+        let key_table = alphabet.key_table();
+        let mut parts = vec![];
+        let mut lemma = String::new();
+        let mut tags: Vec<SmolStr> = vec![];
+
+        for &symbol in symbols {
+            if symbol == SymbolNumber::ZERO || alphabet.is_flag(symbol) {
+                continue;
+            }
+            let key = &key_table[symbol.0 as usize];
+            if !alphabet.is_multichar(symbol) {
+                lemma.push_str(key);
+                continue;
+            }
+            tags.push(key.clone());
+            if key.ends_with('#') {
+                parts.push(CompoundPart {
+                    lemma: std::mem::take(&mut lemma).into(),
+                    tags: std::mem::take(&mut tags),
+                });
+            }
+        }
+
+        Analysis {
+            lemma: lemma.into(),
+            tags,
+            weight,
+            compound_parts: parts,
+        }
+    }
+
+    /// Whether the word is a compound.
+    pub fn is_compound(&self) -> bool {
+        !self.compound_parts.is_empty()
+    }
+
+    /// Whether the analysis has `tag` among its tags (those of the last part,
+    /// for a compound).
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+/// The analysis as the transducer writes it, parts and all:
+/// `"ruovttu+N+Cmp/SgGen+Cmp#giella+N+Sg+Nom"`.
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in &self.compound_parts {
+            f.write_str(&part.lemma)?;
+            for tag in &part.tags {
+                f.write_str(tag)?;
+            }
+        }
+        f.write_str(&self.lemma)?;
+        for tag in &self.tags {
+            f.write_str(tag)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hashbrown::HashMap;
+
+    use super::*;
+
+    fn alphabet(keys: &[&str]) -> TransducerAlphabet {
+        let key_table: Vec<SmolStr> = keys.iter().map(|&k| k.into()).collect();
+        TransducerAlphabet {
+            string_to_symbol: key_table
+                .iter()
+                .enumerate()
+                .map(|(i, k)| (k.clone(), SymbolNumber(i as u16)))
+                .collect(),
+            initial_symbol_count: SymbolNumber(keys.len() as u16),
+            flag_state_size: SymbolNumber(0),
+            length: keys.len(),
+            operations: HashMap::new(),
+            identity_symbol: None,
+            unknown_symbol: None,
+            key_table,
+        }
+    }
+
+    fn symbols(alphabet: &TransducerAlphabet, keys: &[&str]) -> Vec<SymbolNumber> {
+        keys.iter()
+            .map(|k| alphabet.string_to_symbol()[*k])
+            .collect()
+    }
+
+    #[test]
+    fn lemma_and_tags() {
+        let alphabet = alphabet(&["@_EPSILON_SYMBOL_@", "g", "á", "+N", "+Sg", "+Nom", "+"]);
+        let path = symbols(
+            &alphabet,
+            &["g", "á", "@_EPSILON_SYMBOL_@", "+N", "+Sg", "+Nom"],
+        );
+        let analysis = Analysis::from_symbols(&alphabet, &path, Weight(2.5));
+        assert_eq!(analysis.lemma, "gá");
+        assert_eq!(analysis.tags, ["+N", "+Sg", "+Nom"]);
+        assert_eq!(analysis.weight, Weight(2.5));
+        assert!(!analysis.is_compound());
+        assert!(analysis.has_tag("+Sg"));
+        assert_eq!(analysis.to_string(), "gá+N+Sg+Nom");
+
+        // A one-character symbol is part of the lemma, even if it looks like
+        // the start of a tag.
+        let path = symbols(&alphabet, &["g", "+", "á", "+N"]);
+        let analysis = Analysis::from_symbols(&alphabet, &path, Weight(0.0));
+        assert_eq!(analysis.lemma, "g+á");
+        assert_eq!(analysis.tags, ["+N"]);
+    }
+
+    #[test]
+    fn compound_parts() {
+        let alphabet = alphabet(&[
+            "@_EPSILON_SYMBOL_@",
+            "a",
+            "b",
+            "+N",
+            "+Cmp/SgGen",
+            "+Cmp#",
+            "+Sg",
+        ]);
+        let path = symbols(
+            &alphabet,
+            &["a", "+N", "+Cmp/SgGen", "+Cmp#", "b", "b", "+N", "+Sg"],
+        );
+        let analysis = Analysis::from_symbols(&alphabet, &path, Weight(1.0));
+        assert!(analysis.is_compound());
+        assert_eq!(
+            analysis.compound_parts,
+            [CompoundPart {
+                lemma: "a".into(),
+                tags: vec!["+N".into(), "+Cmp/SgGen".into(), "+Cmp#".into()],
+            }]
+        );
+        assert_eq!(analysis.lemma, "bb");
+        assert_eq!(analysis.tags, ["+N", "+Sg"]);
+        assert_eq!(analysis.to_string(), "a+N+Cmp/SgGen+Cmp#bb+N+Sg");
+    }
+}
@@ -17,16 +17,23 @@ use unic_emoji_char::is_emoji;
 use unic_segment::Graphemes;
 use unic_ucd_category::GeneralCategory;
 
+use self::analysis::Analysis;
+use self::morphology::{MorphologyConfig, MorphologyRanker};
 use self::worker::SpellerWorker;
 use crate::speller::suggestion::Suggestion;
 use crate::tokenizer::case_handling::{CaseHandler, CaseMutation, upper_case, upper_first};
 use crate::transducer::Transducer;
 use crate::types::{SymbolNumber, Weight};
 
+pub mod analysis;
 pub mod error;
+pub mod morphology;
+pub mod multi;
 pub mod suggestion;
 
 mod subset;
+#[cfg(test)]
+pub(crate) mod testing;
 mod worker;
 
 /// Calculate Damerau-Levenshtein distance between pre-split grapheme slices.
@@ -675,6 +682,10 @@ pub struct SpellerConfig {
     /// leave it on.
     #[serde(default = "default_mutator_subsets")]
     pub mutator_subsets: bool,
+    /// whether suggestions keeping the grammatical form of the misspelt word
+    /// rank first (see [`morphology`]); needs a lexicon that analyses
+    #[serde(default)]
+    pub morphology: Option<MorphologyConfig>,
     /// whether to output detailed weight information (not serialized)
     #[serde(skip)]
     pub verbose: bool,
@@ -689,6 +700,7 @@ impl SpellerConfig {
     /// * node_pool_size = 128
     /// * recase = true
     /// * astar_lookahead = false
+    /// * morphology = None
     /// * verbose = false
     pub const fn default() -> SpellerConfig {
         SpellerConfig {
@@ -702,6 +714,7 @@ impl SpellerConfig {
             astar_lookahead: default_astar_lookahead(),
             search_dedup: default_search_dedup(),
             mutator_subsets: default_mutator_subsets(),
+            morphology: None,
             verbose: false,
         }
     }
@@ -794,6 +807,81 @@ pub trait Speller {
         config: &SpellerConfig,
     ) -> Vec<Suggestion>;
 
+    /// Analyze the input word form into lemmas and tags.
+    ///
+    /// The same lexicon-only traversal as [`analyze_input`](Self::analyze_input),
+    /// but every analysis comes split into its lemma, its tags and the parts
+    /// of a compound, cheapest first.
+    ///
+    /// Default implementation returns an empty Vec to preserve API
+    /// compatibility for custom `Speller` impls.
+    #[must_use]
+    fn analyze(self: Arc<Self>, word: &str) -> Vec<Analysis> {
+        self.analyze_with_config(word, &SpellerConfig::default())
+    }
+
+    /// Analyze the input word form into lemmas and tags with config options.
+    #[must_use]
+    fn analyze_with_config(self: Arc<Self>, _word: &str, _config: &SpellerConfig) -> Vec<Analysis> {
+        Vec::new()
+    }
+
+    /// The lemmas of the input word form, each with the weight of its best
+    /// analysis, cheapest first.
+    ///
+    /// Every lemma is given once, however many analyses lead to it. A
+    /// compound gives the lemma of its last part; the lemmas of the other
+    /// parts are in the [`analyze`](Self::analyze) results.
+    #[must_use]
+    fn lemmatize(self: Arc<Self>, word: &str) -> Vec<(SmolStr, Weight)> {
+        self.lemmatize_with_config(word, &SpellerConfig::default())
+    }
+
+    /// The lemmas of the input word form with config options.
+    ///
+    /// With [`SpellerConfig::recase`] set, the case variants of the word are
+    /// lemmatized too, as [`CaseHandler`] gives them: "Giella" also has the
+    /// lemmas of "giella". For a word in mixed case, the first variant with
+    /// any analysis is the only one used.
+    #[must_use]
+    fn lemmatize_with_config(
+        self: Arc<Self>,
+        word: &str,
+        config: &SpellerConfig,
+    ) -> Vec<(SmolStr, Weight)> {
+        use crate::tokenizer::case_handling::{CaseMode, word_variants};
+
+        let word = SmolStr::new(word);
+        let (mode, variants) = if config.recase {
+            let case = word_variants(&word);
+            (case.mode, case.words)
+        } else {
+            (CaseMode::MergeAll, vec![])
+        };
+
+        let mut best: HashMap<SmolStr, Weight> = HashMap::new();
+        for variant in std::iter::once(&word).chain(variants.iter().filter(|w| **w != word)) {
+            let analyses = self.clone().analyze_with_config(variant, config);
+            let found = !analyses.is_empty();
+            for analysis in analyses {
+                let entry = best.entry(analysis.lemma).or_insert(analysis.weight);
+                if *entry > analysis.weight {
+                    *entry = analysis.weight;
+                }
+            }
+            if found && matches!(mode, CaseMode::FirstResults) {
+                break;
+            }
+        }
+
+        let mut lemmas: Vec<(SmolStr, Weight)> = best.into_iter().collect();
+        lemmas.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        if let Some(n) = config.n_best {
+            lemmas.truncate(n);
+        }
+        lemmas
+    }
+
     /// Get lexicon weight for a word form (lexicon-only traversal).
     ///
     /// Returns the weight of the best analysis using only the lexicon FST.
@@ -853,7 +941,8 @@ pub trait Speller {
     /// The inverse direction of [`analyze_input`](Self::analyze_input):
     /// where `analyze_input("dieđaheami")` returns the analysis
     /// `"dieđahit+V+Action+Acc+Sg"`, `generate("dieđahit")` returns
-    /// `[("dieđahit","+V+Inf"), ("dieđaheami","+V+Action+Acc+Sg"), …]`.
+    /// `[("dieđahit","+V+Inf"), ("dieđaheami","+V+Action+Acc+Sg"), …]`,
+    /// cheapest first.
     ///
     /// Default implementation returns an empty Vec to preserve API
     /// compatibility for custom `Speller` impls. `HfstSpeller`
@@ -932,6 +1021,9 @@ where
     }
 
     fn suggest_with_config(self: Arc<Self>, word: &str, config: &SpellerConfig) -> Vec<Suggestion> {
+        if config.morphology.is_some() {
+            return MorphologyRanker::new(self).suggest(word, config);
+        }
         self._suggest_with_config(word, config, OutputMode::WithoutTags)
     }
 
@@ -960,6 +1052,21 @@ where
         self.analyze_input_with_config(word, &SpellerConfig::default())
     }
 
+    fn analyze_with_config(self: Arc<Self>, word: &str, config: &SpellerConfig) -> Vec<Analysis> {
+        if word.is_empty() {
+            return vec![];
+        }
+
+        let worker = SpellerWorker::new_lexicon_input(
+            self.clone(),
+            self.to_input_vec_lexicon(word),
+            config,
+            OutputMode::WithTags,
+        );
+
+        worker.analyses()
+    }
+
     fn get_lexicon_weight_with_config(
         self: Arc<Self>,
         word: &str,
@@ -1034,10 +1141,7 @@ where
         lemma: &str,
         config: &crate::generator::GeneratorConfig,
     ) -> Vec<crate::generator::GenerationResult> {
-        if lemma.is_empty() {
-            return Vec::new();
-        }
-        crate::generator::generate_from_lexicon(self.lexicon(), lemma, config)
+        crate::generator::generate(self.lexicon(), lemma, config)
     }
 
     #[inline]
@@ -1457,6 +1561,7 @@ where
                                 reweight_end: d.reweight_end,
                             }),
                             lexicon_weight,
+                            source: None,
                         }
                     })
                     .collect::<Vec<_>>();
@@ -1477,6 +1582,7 @@ where
                                 reweight_end: d.reweight_end,
                             }),
                             lexicon_weight,
+                            source: None,
                         }
                     })
                     .collect::<Vec<_>>();
@@ -1492,6 +1598,7 @@ where
                         completed: Some(!k.ends_with(s)),
                         weight_details: None,
                         lexicon_weight,
+                        source: None,
                     })
                     .collect::<Vec<_>>();
             } else {
@@ -1503,6 +1610,7 @@ where
                         completed: None,
                         weight_details: None,
                         lexicon_weight,
+                        source: None,
                     })
                     .collect::<Vec<_>>();
             }
@@ -0,0 +1,407 @@
+//! Ranking suggestions by the grammatical form the writer was after.
+//!
+//! The error model only knows about letters, so for a misspelt inflected
+//! form it may well rank a different inflection of another word first: for
+//! "gielaiid" it has no way to prefer the plural accusative "gielaid" to the
+//! singular genitive "giela", which is about as few edits away. The nearest
+//! candidates tell, though: most of them are forms with the same tags,
+//! because the ending the writer typed is still there.
+//!
+//! [`MorphologyRanker`] analyses the nearest candidates, takes the tags most of
+//! them share as the form meant, and ranks the suggestions with those tags
+//! above the rest. Given a generator, it also generates the form with those
+//! tags of the lemmas of the nearest candidates that have other tags, so the
+//! right form of the right word is suggested even if the error model never
+//! reached it.
+//!
+//! Set [`SpellerConfig::morphology`] to rank every suggestion of a speller this
+//! way.
+use std::sync::Arc;
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use super::analysis::Analysis;
+use super::suggestion::Suggestion;
+use super::{Speller, SpellerConfig};
+use crate::generator::{GeneratorConfig, TagPattern};
+use crate::tokenizer::case_handling::{lower_case, upper_first};
+use crate::types::Weight;
+
+/// configuration of morphology-preserving ranking
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MorphologyConfig {
+    /// how many of the nearest candidates the tags are inferred from
+    #[serde(default = "default_candidates")]
+    pub candidates: usize,
+    /// extra weight for a suggestion without the inferred tags
+    #[serde(default = "default_penalty")]
+    pub penalty: f32,
+    /// extra weight for a form generated from a candidate's lemma, on top of
+    /// the candidate's own
+    #[serde(default = "default_generated_penalty")]
+    pub generated_penalty: f32,
+}
+
+impl Default for MorphologyConfig {
+    fn default() -> Self {
+        Self::default_const()
+    }
+}
+
+impl MorphologyConfig {
+    pub const fn default_const() -> Self {
+        Self {
+            candidates: default_candidates(),
+            penalty: default_penalty(),
+            generated_penalty: default_generated_penalty(),
+        }
+    }
+}
+
+const fn default_candidates() -> usize {
+    5
+}
+
+const fn default_penalty() -> f32 {
+    5.0
+}
+
+const fn default_generated_penalty() -> f32 {
+    1.0
+}
+
+/// Suggests corrections that keep the grammatical form of the misspelt word.
+pub struct MorphologyRanker<S: Speller + ?Sized> {
+    speller: Arc<S>,
+    generator: Option<Arc<dyn Speller + Send + Sync>>,
+}
+
+impl<S: Speller + ?Sized> MorphologyRanker<S> {
+    /// Rank the suggestions of `speller`, which must analyse words, as its
+    /// lexicon does when it is an analyser.
+    pub fn new(speller: Arc<S>) -> MorphologyRanker<S> {
+        MorphologyRanker {
+            speller,
+            generator: None,
+        }
+    }
+
+    /// Generate the inferred form of the nearest candidates' lemmas with
+    /// `generator`, a speller whose lexicon is a generator (see
+    /// [`crate::generator`]).
+    pub fn with_generator(
+        mut self,
+        generator: Arc<dyn Speller + Send + Sync>,
+    ) -> MorphologyRanker<S> {
+        self.generator = Some(generator);
+        self
+    }
+
+    /// Suggest corrections for `word`, those with the tags inferred for it
+    /// first.
+    ///
+    /// The suggestions are those of
+    /// [`Speller::analyze_suggest_with_config`], reweighted, with the
+    /// generated forms added. [`SpellerConfig::morphology`] tunes the ranking,
+    /// with the defaults of [`MorphologyConfig`] if it is not set.
+    pub fn suggest(&self, word: &str, config: &SpellerConfig) -> Vec<Suggestion> {
+        let morphology = config.morphology.clone().unwrap_or_default();
+        let config = SpellerConfig {
+            morphology: None,
+            ..config.clone()
+        };
+
+        let mut suggestions = self
+            .speller
+            .clone()
+            .analyze_suggest_with_config(word, &config);
+        let analyses: Vec<Vec<Analysis>> = suggestions
+            .iter()
+            .map(|s| self.analyses(s.value(), &config))
+            .collect();
+        let nearest = morphology.candidates.min(suggestions.len());
+        let Some(tags) = infer_tags(&analyses[..nearest]) else {
+            return suggestions;
+        };
+        tracing::trace!("inferred tags for {}: {}", word, tags.concat());
+
+        let has_tags = |analyses: &[Analysis]| analyses.iter().any(|a| a.tags == tags);
+        let mut generated: Vec<Suggestion> = vec![];
+        if let Some(generator) = &self.generator {
+            let generator_config = GeneratorConfig {
+                tags: Some(TagPattern::exact(&tags)),
+                ..GeneratorConfig::default()
+            };
+            for (suggestion, analyses) in suggestions.iter().zip(&analyses).take(nearest) {
+                if has_tags(analyses) {
+                    continue;
+                }
+                let capitalised = suggestion
+                    .value()
+                    .chars()
+                    .next()
+                    .is_some_and(char::is_uppercase);
+                for analysis in analyses {
+                    for result in generator
+                        .clone()
+                        .generate_with_config(&analysis.lemma, &generator_config)
+                    {
+                        let value = match capitalised {
+                            true => upper_first(&result.surface),
+                            false => result.surface,
+                        };
+                        if suggestions
+                            .iter()
+                            .chain(&generated)
+                            .any(|s| s.value == value)
+                        {
+                            continue;
+                        }
+                        let weight = suggestion.weight + Weight(morphology.generated_penalty);
+                        generated.push(Suggestion::new(value, weight, suggestion.completed));
+                    }
+                }
+            }
+        }
+
+        for (suggestion, analyses) in suggestions.iter_mut().zip(&analyses) {
+            if !has_tags(analyses) {
+                suggestion.weight = suggestion.weight + Weight(morphology.penalty);
+            }
+        }
+        suggestions.extend(generated);
+        suggestions.sort();
+        if let Some(n_best) = config.n_best {
+            suggestions.truncate(n_best);
+        }
+        suggestions
+    }
+
+    /// The analyses of a suggestion, looked up in lower case if it was
+    /// recased and is not known as it is.
+    fn analyses(&self, value: &str, config: &SpellerConfig) -> Vec<Analysis> {
+        let analyses = self.speller.clone().analyze_with_config(value, config);
+        let lower = lower_case(value);
+        if analyses.is_empty() && lower != value {
+            return self.speller.clone().analyze_with_config(&lower, config);
+        }
+        analyses
+    }
+}
+
+/// The tags most of the candidates share, given the analyses of each, nearest
+/// first.
+///
+/// Each candidate votes once for every tag sequence among its analyses, a
+/// nearer candidate with a greater say: the `n`th votes `1/n`. Ties go to the
+/// tags of the nearer candidate.
+fn infer_tags(candidates: &[Vec<Analysis>]) -> Option<Vec<SmolStr>> {
+    let mut order: Vec<&[SmolStr]> = vec![];
+    let mut votes: HashMap<&[SmolStr], f32> = HashMap::new();
+    for (rank, analyses) in candidates.iter().enumerate() {
+        let mut voted: Vec<&[SmolStr]> = vec![];
+        for analysis in analyses {
+            let tags = analysis.tags.as_slice();
+            if tags.is_empty() || voted.contains(&tags) {
+                continue;
+            }
+            voted.push(tags);
+            if !votes.contains_key(tags) {
+                order.push(tags);
+            }
+            *votes.entry(tags).or_default() += 1.0 / (rank + 1) as f32;
+        }
+    }
+
+    let mut best: Option<(&[SmolStr], f32)> = None;
+    for tags in order {
+        let score = votes[tags];
+        if best.is_none_or(|(_, best)| score > best) {
+            best = Some((tags, score));
+        }
+    }
+    best.map(|(tags, _)| tags.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::GenerationResult;
+
+    /// A speller with fixed suggestions, analyses and generated forms.
+    struct TableSpeller {
+        /// every suggestion, for any word
+        suggestions: &'static [(&'static str, f32)],
+        /// word form, lemma and tags
+        analyses: &'static [(&'static str, &'static str, &'static [&'static str])],
+    }
+
+    impl Speller for TableSpeller {
+        fn is_correct(self: Arc<Self>, word: &str) -> bool {
+            self.analyses.iter().any(|(form, ..)| *form == word)
+        }
+
+        fn is_correct_with_config(self: Arc<Self>, word: &str, _config: &SpellerConfig) -> bool {
+            self.is_correct(word)
+        }
+
+        fn suggest(self: Arc<Self>, _word: &str) -> Vec<Suggestion> {
+            self.suggestions
+                .iter()
+                .map(|&(value, weight)| Suggestion::new(value.into(), Weight(weight), None))
+                .collect()
+        }
+
+        fn suggest_with_config(
+            self: Arc<Self>,
+            word: &str,
+            _config: &SpellerConfig,
+        ) -> Vec<Suggestion> {
+            self.suggest(word)
+        }
+
+        fn analyze_input(self: Arc<Self>, _word: &str) -> Vec<Suggestion> {
+            vec![]
+        }
+
+        fn analyze_input_with_config(
+            self: Arc<Self>,
+            _word: &str,
+            _config: &SpellerConfig,
+        ) -> Vec<Suggestion> {
+            vec![]
+        }
+
+        fn analyze_with_config(
+            self: Arc<Self>,
+            word: &str,
+            _config: &SpellerConfig,
+        ) -> Vec<Analysis> {
+            self.analyses
+                .iter()
+                .filter(|(form, ..)| *form == word)
+                .map(|&(_, lemma, tags)| Analysis {
+                    lemma: lemma.into(),
+                    tags: tags.iter().map(|&t| t.into()).collect(),
+                    weight: Weight::ZERO,
+                    compound_parts: vec![],
+                })
+                .collect()
+        }
+
+        fn analyze_output(self: Arc<Self>, word: &str) -> Vec<Suggestion> {
+            self.suggest(word)
+        }
+
+        fn analyze_output_with_config(
+            self: Arc<Self>,
+            word: &str,
+            _config: &SpellerConfig,
+        ) -> Vec<Suggestion> {
+            self.suggest(word)
+        }
+
+        fn analyze_suggest(self: Arc<Self>, word: &str) -> Vec<Suggestion> {
+            self.suggest(word)
+        }
+
+        fn analyze_suggest_with_config(
+            self: Arc<Self>,
+            word: &str,
+            _config: &SpellerConfig,
+        ) -> Vec<Suggestion> {
+            self.suggest(word)
+        }
+
+        fn generate_with_config(
+            self: Arc<Self>,
+            lemma: &str,
+            config: &GeneratorConfig,
+        ) -> Vec<GenerationResult> {
+            self.analyses
+                .iter()
+                .filter(|&&(_, l, tags)| {
+                    l == lemma && config.tags.as_ref().is_none_or(|p| p.matches(tags))
+                })
+                .map(|&(form, _, tags)| GenerationResult {
+                    surface: form.into(),
+                    analysis: tags.concat().into(),
+                    tags: tags.iter().map(|&t| t.into()).collect(),
+                    weight: Weight::ZERO,
+                })
+                .collect()
+        }
+    }
+
+    const ANALYSES: &[(&str, &str, &[&str])] = &[
+        ("giela", "giella", &["+N", "+Sg", "+Gen"]),
+        ("gielaid", "giella", &["+N", "+Pl", "+Acc"]),
+        ("girjjiid", "girji", &["+N", "+Pl", "+Acc"]),
+        ("gáhkuid", "gáhkku", &["+N", "+Pl", "+Acc"]),
+        ("gielas", "gielas", &["+A", "+Sg", "+Nom"]),
+    ];
+
+    fn speller(suggestions: &'static [(&'static str, f32)]) -> Arc<TableSpeller> {
+        Arc::new(TableSpeller {
+            suggestions,
+            analyses: ANALYSES,
+        })
+    }
+
+    fn values(suggestions: &[Suggestion]) -> Vec<&str> {
+        suggestions.iter().map(Suggestion::value).collect()
+    }
+
+    #[test]
+    fn suggestions_with_the_inferred_tags_come_first() {
+        let speller = speller(&[
+            ("giela", 1.0),
+            ("girjjiid", 2.0),
+            ("gielaid", 3.0),
+            ("gáhkuid", 4.0),
+        ]);
+        let suggestions =
+            MorphologyRanker::new(speller).suggest("gielaiid", &SpellerConfig::default());
+        assert_eq!(
+            values(&suggestions),
+            ["girjjiid", "gielaid", "gáhkuid", "giela"]
+        );
+        assert_eq!(suggestions[3].weight, Weight(6.0));
+    }
+
+    #[test]
+    fn the_inferred_form_of_a_near_lemma_is_generated() {
+        let speller = speller(&[("girjjiid", 1.0), ("giela", 2.0), ("gáhkuid", 4.0)]);
+        let generator = Arc::new(TableSpeller {
+            suggestions: &[],
+            analyses: ANALYSES,
+        });
+        let suggestions = MorphologyRanker::new(speller)
+            .with_generator(generator)
+            .suggest("gielaiid", &SpellerConfig::default());
+        assert_eq!(
+            values(&suggestions),
+            ["girjjiid", "gielaid", "gáhkuid", "giela"]
+        );
+        assert_eq!(suggestions[1].weight, Weight(3.0));
+    }
+
+    #[test]
+    fn nearer_candidates_have_a_greater_say() {
+        let analyses =
+            |word: &str| speller(&[]).analyze_with_config(word, &SpellerConfig::default());
+        let candidates = [analyses("gielas"), analyses("giela"), analyses("gielaid")];
+        assert_eq!(infer_tags(&candidates).unwrap(), ["+A", "+Sg", "+Nom"]);
+        let candidates = [
+            analyses("gielas"),
+            analyses("girjjiid"),
+            analyses("gielaid"),
+            analyses("gáhkuid"),
+        ];
+        assert_eq!(infer_tags(&candidates).unwrap(), ["+N", "+Pl", "+Acc"]);
+        assert!(infer_tags(&[vec![]]).is_none());
+    }
+}
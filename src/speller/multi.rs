@@ -0,0 +1,369 @@
+//! Spell-checking text against several languages at once.
+//!
+//! Text written in one language routinely borrows words, names and whole
+//! phrases from another — North Sámi prose quoting Norwegian, say — and a
+//! single speller flags every one of them. [`MultiSpeller`] wraps several
+//! spellers and treats a word as correct if *any* of them accepts it.
+//!
+//! Suggestions are a different matter: merging every language's corrections
+//! into one list would rank a Norwegian correction of a Sámi typo alongside the
+//! Sámi ones, on weights that were never meant to be compared. Instead the
+//! suggestions come from the one language most likely for the surrounding
+//! text, and each [`Suggestion`] records which speller it came from in
+//! [`Suggestion::source`].
+use std::sync::Arc;
+
+use smol_str::SmolStr;
+
+use super::{Speller, SpellerConfig, analysis::Analysis, suggestion::Suggestion};
+use crate::archive::detect::{evidence_words, score_words};
+use crate::archive::{SpellerArchive, locale_or_position};
+use crate::generator::{GenerationResult, GeneratorConfig};
+use crate::types::Weight;
+
+struct Member {
+    id: SmolStr,
+    speller: Arc<dyn Speller + Send + Sync>,
+}
+
+/// A [`Speller`] that checks words against several spellers at once.
+///
+/// Members are kept in the order they were given, and that order is the last
+/// resort when nothing else tells two languages apart.
+pub struct MultiSpeller {
+    members: Vec<Member>,
+}
+
+impl MultiSpeller {
+    /// Create a multi-language speller from opened archives.
+    ///
+    /// Each archive is identified by the locale in its metadata, or by its
+    /// position in `archives` if it has none.
+    pub fn new(archives: &[Arc<dyn SpellerArchive + Send + Sync>]) -> Arc<MultiSpeller> {
+        let members = archives
+            .iter()
+            .enumerate()
+            .map(|(i, archive)| Member {
+                id: locale_or_position(archive.as_ref(), i),
+                speller: archive.speller(),
+            })
+            .collect();
+        Arc::new(MultiSpeller { members })
+    }
+
+    /// Create a multi-language speller from spellers and the identifiers to
+    /// report them by.
+    pub fn from_spellers(
+        spellers: Vec<(SmolStr, Arc<dyn Speller + Send + Sync>)>,
+    ) -> Arc<MultiSpeller> {
+        let members = spellers
+            .into_iter()
+            .map(|(id, speller)| Member { id, speller })
+            .collect();
+        Arc::new(MultiSpeller { members })
+    }
+
+    /// Identifiers of the member spellers, in order.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.members.iter().map(|m| m.id.as_str())
+    }
+
+    /// Identifiers of every member speller that accepts `word`.
+    pub fn accepted_by(&self, word: &str, config: &SpellerConfig) -> Vec<SmolStr> {
+        self.members
+            .iter()
+            .filter(|m| m.speller.clone().is_correct_with_config(word, config))
+            .map(|m| m.id.clone())
+            .collect()
+    }
+
+    /// Generate suggestions for `word` from the language most likely for
+    /// `context`, the text surrounding it.
+    ///
+    /// Every member is scored on the words of `context`: the more of them it
+    /// accepts the likelier it is, and among members accepting equally many,
+    /// the lower the total lexicon weight of those words the likelier. When
+    /// the context still cannot tell the leaders apart — most obviously when
+    /// there is none — the one offering the cheapest correction wins. A
+    /// member with no suggestions at all is passed over for the next likeliest.
+    pub fn suggest_in_context(
+        self: Arc<Self>,
+        word: &str,
+        context: &str,
+        config: &SpellerConfig,
+    ) -> Vec<Suggestion> {
+        self.ranked_suggestions(word, context, config, |speller, word, config| {
+            speller.suggest_with_config(word, config)
+        })
+    }
+
+    /// Like [`suggest_in_context`](Self::suggest_in_context), with morphological
+    /// tags preserved on the suggestions.
+    pub fn analyze_output_in_context(
+        self: Arc<Self>,
+        word: &str,
+        context: &str,
+        config: &SpellerConfig,
+    ) -> Vec<Suggestion> {
+        self.ranked_suggestions(word, context, config, |speller, word, config| {
+            speller.analyze_output_with_config(word, config)
+        })
+    }
+
+    /// Context score of each member, in member order: how many words of
+    /// `context` it accepts, and what the lexicon charged for them.
+    fn context_scores(&self, context: &str, config: &SpellerConfig) -> Vec<(usize, Weight)> {
+        let words = evidence_words(context);
+        self.members
+            .iter()
+            .map(|m| score_words(m.speller.clone(), &words, config))
+            .collect()
+    }
+
+    fn ranked_suggestions<F>(
+        &self,
+        word: &str,
+        context: &str,
+        config: &SpellerConfig,
+        suggest: F,
+    ) -> Vec<Suggestion>
+    where
+        F: Fn(Arc<dyn Speller + Send + Sync>, &str, &SpellerConfig) -> Vec<Suggestion>,
+    {
+        let scores = self.context_scores(context, config);
+        let mut order: Vec<usize> = (0..self.members.len()).collect();
+        // More accepted words first, then the cheaper lexicon total; the sort
+        // is stable, so member order breaks what remains.
+        order.sort_by(|&a, &b| {
+            scores[b]
+                .0
+                .cmp(&scores[a].0)
+                .then(scores[a].1.cmp(&scores[b].1))
+        });
+
+        let mut rest = order.as_slice();
+        while let Some(&first) = rest.first() {
+            let tied = rest
+                .iter()
+                .take_while(|&&i| scores[i] == scores[first])
+                .count();
+            let (group, next) = rest.split_at(tied);
+            rest = next;
+
+            // The context cannot separate this group, so let the corrections
+            // do it: the first member whose best suggestion is cheapest.
+            let best = group
+                .iter()
+                .map(|&i| {
+                    let member = &self.members[i];
+                    (member, suggest(member.speller.clone(), word, config))
+                })
+                .filter(|(_, suggs)| !suggs.is_empty())
+                .min_by(|(_, a), (_, b)| a[0].weight.cmp(&b[0].weight));
+
+            if let Some((member, suggs)) = best {
+                return suggs
+                    .into_iter()
+                    .map(|s| s.with_source(member.id.clone()))
+                    .collect();
+            }
+        }
+
+        vec![]
+    }
+}
+
+impl Speller for MultiSpeller {
+    #[allow(clippy::wrong_self_convention)]
+    fn is_correct_with_config(self: Arc<Self>, word: &str, config: &SpellerConfig) -> bool {
+        self.members
+            .iter()
+            .any(|m| m.speller.clone().is_correct_with_config(word, config))
+    }
+
+    #[inline]
+    fn is_correct(self: Arc<Self>, word: &str) -> bool {
+        self.is_correct_with_config(word, &SpellerConfig::default())
+    }
+
+    /// Suggestions from the member offering the cheapest correction, since a
+    /// lone word gives no context to choose a language by. Use
+    /// [`MultiSpeller::suggest_in_context`] when the surrounding text is known.
+    fn suggest_with_config(self: Arc<Self>, word: &str, config: &SpellerConfig) -> Vec<Suggestion> {
+        self.suggest_in_context(word, "", config)
+    }
+
+    #[inline]
+    fn suggest(self: Arc<Self>, word: &str) -> Vec<Suggestion> {
+        self.suggest_with_config(word, &SpellerConfig::default())
+    }
+
+    /// Analyses from every member that recognises `word`, each tagged with its
+    /// source, cheapest first.
+    fn analyze_input_with_config(
+        self: Arc<Self>,
+        word: &str,
+        config: &SpellerConfig,
+    ) -> Vec<Suggestion> {
+        let mut out: Vec<Suggestion> = self
+            .members
+            .iter()
+            .flat_map(|m| {
+                m.speller
+                    .clone()
+                    .analyze_input_with_config(word, config)
+                    .into_iter()
+                    .map(|s| s.with_source(m.id.clone()))
+            })
+            .collect();
+        out.sort();
+        out
+    }
+
+    #[inline]
+    fn analyze_input(self: Arc<Self>, word: &str) -> Vec<Suggestion> {
+        self.analyze_input_with_config(word, &SpellerConfig::default())
+    }
+
+    /// Analyses from every member that recognises `word`, cheapest first.
+    fn analyze_with_config(self: Arc<Self>, word: &str, config: &SpellerConfig) -> Vec<Analysis> {
+        let mut out: Vec<Analysis> = self
+            .members
+            .iter()
+            .flat_map(|m| m.speller.clone().analyze_with_config(word, config))
+            .collect();
+        out.sort_by_key(|a| a.weight);
+        out
+    }
+
+    /// The lexicon weight from the first member that accepts `word`.
+    fn get_lexicon_weight_with_config(
+        self: Arc<Self>,
+        word: &str,
+        config: &SpellerConfig,
+    ) -> Weight {
+        self.members
+            .iter()
+            .find(|m| m.speller.clone().is_correct_with_config(word, config))
+            .map(|m| {
+                m.speller
+                    .clone()
+                    .get_lexicon_weight_with_config(word, config)
+            })
+            .unwrap_or(Weight(0.0))
+    }
+
+    fn analyze_output_with_config(
+        self: Arc<Self>,
+        word: &str,
+        config: &SpellerConfig,
+    ) -> Vec<Suggestion> {
+        self.analyze_output_in_context(word, "", config)
+    }
+
+    #[inline]
+    fn analyze_output(self: Arc<Self>, word: &str) -> Vec<Suggestion> {
+        self.analyze_output_with_config(word, &SpellerConfig::default())
+    }
+
+    fn analyze_suggest_with_config(
+        self: Arc<Self>,
+        word: &str,
+        config: &SpellerConfig,
+    ) -> Vec<Suggestion> {
+        self.ranked_suggestions(word, "", config, |speller, word, config| {
+            speller.analyze_suggest_with_config(word, config)
+        })
+    }
+
+    #[inline]
+    fn analyze_suggest(self: Arc<Self>, word: &str) -> Vec<Suggestion> {
+        self.analyze_suggest_with_config(word, &SpellerConfig::default())
+    }
+
+    /// Forms generated by every member, in member order.
+    fn generate_with_config(
+        self: Arc<Self>,
+        lemma: &str,
+        config: &GeneratorConfig,
+    ) -> Vec<GenerationResult> {
+        self.members
+            .iter()
+            .flat_map(|m| m.speller.clone().generate_with_config(lemma, config))
+            .collect()
+    }
+
+    #[inline]
+    fn generate(self: Arc<Self>, lemma: &str) -> Vec<GenerationResult> {
+        self.generate_with_config(lemma, &GeneratorConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speller::testing::ListSpeller;
+
+    fn multi() -> Arc<MultiSpeller> {
+        MultiSpeller::from_spellers(vec![
+            (
+                "se".into(),
+                Arc::new(ListSpeller {
+                    words: &[("mun", 0.0), ("lean", 0.0), ("dál", 0.0)],
+                    fix: Some(("sámegiella", 9.0)),
+                }),
+            ),
+            (
+                "nb".into(),
+                Arc::new(ListSpeller {
+                    words: &[("jeg", 0.0), ("er", 0.0), ("her", 0.0)],
+                    fix: Some(("norsk", 4.0)),
+                }),
+            ),
+        ])
+    }
+
+    fn top(suggs: &[Suggestion]) -> (&str, Option<&str>) {
+        (suggs[0].value(), suggs[0].source())
+    }
+
+    #[test]
+    fn a_word_any_member_accepts_is_correct() {
+        let multi = multi();
+        assert!(multi.clone().is_correct("mun"));
+        assert!(multi.clone().is_correct("jeg"));
+        assert!(!multi.clone().is_correct("xyz"));
+        assert_eq!(multi.accepted_by("her", &SpellerConfig::default()), ["nb"]);
+    }
+
+    // The surrounding text is Sámi, so the Sámi correction wins even though
+    // the Norwegian one is cheaper.
+    #[test]
+    fn context_picks_the_language() {
+        let config = SpellerConfig::default();
+        let suggs = multi().suggest_in_context("xyz", "mun lean dál", &config);
+        assert_eq!(top(&suggs), ("sámegiella", Some("se")));
+
+        let suggs = multi().suggest_in_context("xyz", "jeg er her", &config);
+        assert_eq!(top(&suggs), ("norsk", Some("nb")));
+    }
+
+    // With no context to go on, the cheapest correction decides.
+    #[test]
+    fn without_context_the_cheapest_correction_wins() {
+        assert_eq!(top(&multi().suggest("xyz")), ("norsk", Some("nb")));
+    }
+
+    // A suggestion that did not come through a `MultiSpeller` has no source,
+    // and none appears on the wire.
+    #[test]
+    fn source_is_not_serialized_when_absent() {
+        let plain = Suggestion::new("cat".into(), Weight(1.0), None);
+        let json = serde_json::to_string(&plain).unwrap();
+        assert!(!json.contains("source"), "{json}");
+
+        let tagged = plain.with_source("se".into());
+        let json = serde_json::to_string(&tagged).unwrap();
+        assert!(json.contains(r#""source":"se""#), "{json}");
+    }
+}
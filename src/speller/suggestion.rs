@@ -36,6 +36,10 @@ pub struct Suggestion {
     /// inert rather than wrong.
     #[serde(skip, default = "no_lexicon_weight")]
     pub(crate) lexicon_weight: Weight,
+    /// identifier of the speller that produced this suggestion, when it came
+    /// from a [`MultiSpeller`](super::multi::MultiSpeller); `None` otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SmolStr>,
 }
 
 /// Serde default for the skipped [`Suggestion::lexicon_weight`].
@@ -67,6 +71,7 @@ impl Suggestion {
             completed,
             weight_details: None,
             lexicon_weight: Weight::ZERO,
+            source: None,
         }
     }
 
@@ -83,6 +88,7 @@ impl Suggestion {
             completed,
             weight_details: Some(details),
             lexicon_weight: Weight::ZERO,
+            source: None,
         }
     }
 
@@ -93,6 +99,13 @@ impl Suggestion {
         self
     }
 
+    /// Record which speller of a [`MultiSpeller`](super::multi::MultiSpeller)
+    /// produced this suggestion.
+    pub(crate) fn with_source(mut self, source: SmolStr) -> Suggestion {
+        self.source = Some(source);
+        self
+    }
+
     /// gets the suggested word-form
     pub fn value(&self) -> &str {
         &self.value
@@ -112,6 +125,11 @@ impl Suggestion {
     pub fn weight_details(&self) -> Option<&WeightDetails> {
         self.weight_details.as_ref()
     }
+
+    /// gets the identifier of the speller this suggestion came from, if any
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
 }
 
 impl PartialOrd for Suggestion {
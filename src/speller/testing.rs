@@ -0,0 +1,91 @@
+//! A speller over a word list, for tests of what is built on [`Speller`].
+use std::sync::Arc;
+
+use super::suggestion::Suggestion;
+use super::{Speller, SpellerConfig};
+use crate::types::Weight;
+
+/// A speller over a fixed word list, each word with its lexicon weight, whose
+/// only correction for any word is `fix` at its cost, if given.
+pub(crate) struct ListSpeller {
+    pub(crate) words: &'static [(&'static str, f32)],
+    pub(crate) fix: Option<(&'static str, f32)>,
+}
+
+impl ListSpeller {
+    fn weight(&self, word: &str) -> Option<Weight> {
+        self.words
+            .iter()
+            .find(|(w, _)| *w == word)
+            .map(|(_, x)| Weight(*x))
+    }
+}
+
+impl Speller for ListSpeller {
+    fn is_correct(self: Arc<Self>, word: &str) -> bool {
+        self.weight(word).is_some()
+    }
+
+    fn is_correct_with_config(self: Arc<Self>, word: &str, _config: &SpellerConfig) -> bool {
+        self.is_correct(word)
+    }
+
+    fn get_lexicon_weight_with_config(
+        self: Arc<Self>,
+        word: &str,
+        _config: &SpellerConfig,
+    ) -> Weight {
+        self.weight(word).unwrap_or(Weight::ZERO)
+    }
+
+    fn suggest(self: Arc<Self>, _word: &str) -> Vec<Suggestion> {
+        self.fix
+            .iter()
+            .map(|(fix, cost)| Suggestion::new((*fix).into(), Weight(*cost), None))
+            .collect()
+    }
+
+    fn suggest_with_config(
+        self: Arc<Self>,
+        word: &str,
+        _config: &SpellerConfig,
+    ) -> Vec<Suggestion> {
+        self.suggest(word)
+    }
+
+    fn analyze_input(self: Arc<Self>, _word: &str) -> Vec<Suggestion> {
+        vec![]
+    }
+
+    fn analyze_input_with_config(
+        self: Arc<Self>,
+        _word: &str,
+        _config: &SpellerConfig,
+    ) -> Vec<Suggestion> {
+        vec![]
+    }
+
+    fn analyze_output(self: Arc<Self>, word: &str) -> Vec<Suggestion> {
+        self.suggest(word)
+    }
+
+    fn analyze_output_with_config(
+        self: Arc<Self>,
+        word: &str,
+        _config: &SpellerConfig,
+    ) -> Vec<Suggestion> {
+        self.suggest(word)
+    }
+
+    fn analyze_suggest(self: Arc<Self>, word: &str) -> Vec<Suggestion> {
+        self.suggest(word)
+    }
+
+    fn analyze_suggest_with_config(
+        self: Arc<Self>,
+        word: &str,
+        _config: &SpellerConfig,
+    ) -> Vec<Suggestion> {
+        self.suggest(word)
+    }
+}
@@ -6,6 +6,7 @@ use std::sync::Arc;
 
 use lifeguard::{Pool, Recycled};
 
+use super::analysis::{Analysis, CompoundPart};
 use super::subset::{MutatorSubsets, SubsetStats};
 use super::{HfstSpeller, OutputMode, SpellerConfig};
 use crate::speller::suggestion::{Suggestion, WeightDetails};
@@ -1068,26 +1069,72 @@ where
 
     pub(crate) fn analyze(&self) -> Vec<Suggestion> {
         tracing::trace!("Beginning analyze");
+        let alphabet = self.speller.lexicon().alphabet();
+        let mut lookups = HashMap::new();
+        for (symbols, weight) in self.lexicon_paths() {
+            let string = alphabet.string_from_symbols(&symbols);
+            let entry = lookups.entry(string).or_insert(weight);
+            if *entry > weight {
+                *entry = weight;
+            }
+        }
+        self.generate_sorted_suggestions_basic(&lookups)
+    }
+
+    /// The analyses of the input, split into lemma and tags, cheapest first.
+    pub(crate) fn analyses(&self) -> Vec<Analysis> {
+        tracing::trace!("Beginning analyses");
+        let alphabet = self.speller.lexicon().alphabet();
+        // Paths differing only in epsilons and flags give the same analysis,
+        // which is given once, at the best weight of its paths.
+        let mut best: HashMap<(SmolStr, Vec<SmolStr>, Vec<CompoundPart>), Weight> = HashMap::new();
+        for (symbols, weight) in self.lexicon_paths() {
+            let analysis = Analysis::from_symbols(alphabet, &symbols, weight);
+            let key = (analysis.lemma, analysis.tags, analysis.compound_parts);
+            let entry = best.entry(key).or_insert(weight);
+            if *entry > weight {
+                *entry = weight;
+            }
+        }
+        let mut analyses: Vec<Analysis> = best
+            .into_iter()
+            .map(|((lemma, tags, compound_parts), weight)| Analysis {
+                lemma,
+                tags,
+                weight,
+                compound_parts,
+            })
+            .collect();
+        analyses.sort_by(|a, b| {
+            a.weight
+                .cmp(&b.weight)
+                .then_with(|| a.to_string().cmp(&b.to_string()))
+        });
+
+        if let Some(n) = self.config.n_best {
+            analyses.truncate(n);
+        }
+        analyses
+    }
+
+    /// Every path through the lexicon reading the whole input, with the best
+    /// weight it is reached at (including the final weight).
+    fn lexicon_paths(&self) -> HashMap<Vec<SymbolNumber>, Weight> {
         let pool = Pool::with_size_and_max(0, 0);
         let mut nodes = speller_start_node(&pool, self.state_size() as usize);
-        tracing::trace!("beginning analyze {:?}", self.input);
-        let mut lookups = HashMap::new();
+        tracing::trace!("beginning lexicon_paths {:?}", self.input);
+        let mut paths = HashMap::new();
         while let Some(next_node) = nodes.pop() {
             if next_node.input_state.0 as usize == self.input.len()
                 && self.speller.lexicon().is_final(next_node.lexicon_state)
             {
-                let string = self
-                    .speller
-                    .lexicon()
-                    .alphabet()
-                    .string_from_symbols(&next_node.string);
                 let weight = next_node.weight()
                     + self
                         .speller
                         .lexicon()
                         .final_weight(next_node.lexicon_state)
                         .unwrap();
-                let entry = lookups.entry(string).or_insert(weight);
+                let entry = paths.entry(next_node.string.clone()).or_insert(weight);
                 if *entry > weight {
                     *entry = weight;
                 }
@@ -1095,7 +1142,7 @@ where
             self.lexicon_epsilons(&pool, Weight::INFINITE, &next_node, &mut nodes);
             self.lexicon_consume(&pool, Weight::INFINITE, &next_node, &mut nodes);
         }
-        self.generate_sorted_suggestions_basic(&lookups)
+        paths
     }
 
     fn generate_sorted_suggestions_basic(
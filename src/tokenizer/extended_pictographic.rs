@@ -0,0 +1,52 @@
+//! Unicode `Extended_Pictographic` Character Property.
+//!
+//! Word boundary rule WB3c keeps emoji ZWJ sequences together by this
+//! property since Unicode 11, in place of the `E_Base` and `Glue_After_Zwj`
+//! word break values.
+//!
+//! ## References
+//!
+//! * <https://www.unicode.org/reports/tr51/#Emoji_Properties>
+//! * <https://www.unicode.org/reports/tr29/#WB3c>
+
+use unic_char_property::char_property;
+use unic_char_range::chars;
+
+char_property! {
+    /// Represents values of the Unicode character property
+    /// [`Extended_Pictographic`](https://www.unicode.org/reports/tr51/#Emoji_Properties).
+    ///
+    /// The value is `true` for emoji and for the pictographic symbols
+    /// reserved for future emoji.
+    pub struct ExtendedPictographic(bool) {
+        abbr => "ExtPict";
+        long => "Extended_Pictographic";
+        human => "Extended Pictographic";
+
+        data_table_path => "tables/extended_pictographic.rsv";
+    }
+
+    /// The value is `true` for emoji and for the pictographic symbols
+    /// reserved for future emoji.
+    pub fn is_extended_pictographic(char) -> bool;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_extended_pictographic;
+
+    #[test]
+    fn test_values() {
+        assert!(!is_extended_pictographic('a'));
+        assert!(!is_extended_pictographic('#'));
+        assert!(is_extended_pictographic('\u{00A9}'));
+        assert!(is_extended_pictographic('\u{2764}'));
+        assert!(is_extended_pictographic('\u{1F600}'));
+        assert!(is_extended_pictographic('\u{1F6D1}'));
+        // Skin tone modifiers are Extend, regional indicators are not pictographs.
+        assert!(!is_extended_pictographic('\u{1F3FB}'));
+        assert!(!is_extended_pictographic('\u{1F1E6}'));
+        // Reserved for future emoji.
+        assert!(is_extended_pictographic('\u{1FFFD}'));
+    }
+}
@@ -0,0 +1,802 @@
+//! Tokenizing marked-up text: HTML, XML and Markdown.
+//!
+//! Running [`Tokenize::word_indices`](super::Tokenize::word_indices) over a
+//! Markdown or HTML file hands the speller tag names, attribute values, code
+//! and URLs as if they were words. The tokenizers here first find the prose in
+//! the source — the text a reader would see — and tokenize only that, with
+//! offsets into the original source so results can be mapped straight back.
+//!
+//! The markup is recognised with a light scanner rather than a full parser:
+//! it knows tags, comments, entities, code and link syntax well enough to step
+//! over them, and treats anything it does not understand as prose.
+use std::ops::Range;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use unic_ucd_common::alphanumeric::is_alphanumeric;
+
+use super::token::url_len;
+use super::word::WordBoundIndices;
+
+/// The kind of markup a text is written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Markup {
+    /// no markup: the whole text is prose
+    #[default]
+    Plain,
+    /// HTML; the contents of `code`, `pre`, `script` and similar elements are
+    /// not prose
+    Html,
+    /// XML; every element's text content is prose
+    Xml,
+    /// Markdown (CommonMark), including inline HTML
+    Markdown,
+}
+
+impl Markup {
+    /// Guess the markup of a file from its extension, falling back to
+    /// [`Markup::Plain`].
+    pub fn from_path(path: &Path) -> Markup {
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match ext.as_str() {
+            "md" | "markdown" | "mdown" | "mkd" => Markup::Markdown,
+            "html" | "htm" | "xhtml" => Markup::Html,
+            "xml" | "docbook" | "dbk" | "svg" => Markup::Xml,
+            _ => Markup::Plain,
+        }
+    }
+}
+
+/// Error parsing a [`Markup`] from a string.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown markup '{0}'. Expected one of: plain, html, xml, markdown")]
+pub struct ParseMarkupError(pub String);
+
+impl std::str::FromStr for Markup {
+    type Err = ParseMarkupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" | "text" | "txt" => Ok(Markup::Plain),
+            "html" | "htm" => Ok(Markup::Html),
+            "xml" => Ok(Markup::Xml),
+            "markdown" | "md" => Ok(Markup::Markdown),
+            _ => Err(ParseMarkupError(s.to_string())),
+        }
+    }
+}
+
+/// What separates a [`ProseSegment`] from the prose before it.
+///
+/// Ordered from weakest to strongest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Boundary {
+    /// only markup that renders as nothing, such as a tag or an emphasis
+    /// marker; the words on either side read as adjacent
+    Inline,
+    /// something visible that is not prose, such as inline code, a URL or an
+    /// entity
+    Content,
+    /// the start of the text or of a new block: a paragraph, heading, list
+    /// item or similar
+    Block,
+}
+
+/// A stretch of prose in a marked-up text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProseSegment {
+    /// byte range of the prose in the source
+    pub span: Range<usize>,
+    /// what comes between this segment and the previous one
+    pub boundary: Boundary,
+}
+
+/// Find the prose in `text`, in order.
+///
+/// Plain text is a single segment. Segments never overlap and never contain
+/// markup, but whitespace between two blocks may be left out.
+pub fn prose_segments(text: &str, markup: Markup) -> Vec<ProseSegment> {
+    let mut segments = Segments::new();
+    match markup {
+        Markup::Plain => segments.prose(0..text.len()),
+        Markup::Html => scan_html(text, true, &mut segments),
+        Markup::Xml => scan_html(text, false, &mut segments),
+        Markup::Markdown => scan_markdown(text, &mut segments),
+    }
+    segments.finish()
+}
+
+/// External iterator for the word boundaries within the prose of a marked-up
+/// text, with byte offsets into the whole text.
+#[derive(Clone, Debug)]
+pub struct MarkupWordBoundIndices<'a> {
+    text: &'a str,
+    segments: std::vec::IntoIter<ProseSegment>,
+    current: Option<(usize, WordBoundIndices<'a>)>,
+}
+
+impl<'a> MarkupWordBoundIndices<'a> {
+    /// Create new iterator over the word boundaries in the prose of `text`.
+    pub fn new(text: &'a str, markup: Markup) -> MarkupWordBoundIndices<'a> {
+        MarkupWordBoundIndices {
+            text,
+            segments: prose_segments(text, markup).into_iter(),
+            current: None,
+        }
+    }
+}
+
+impl<'a> Iterator for MarkupWordBoundIndices<'a> {
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<(usize, &'a str)> {
+        loop {
+            if let Some((offset, iter)) = &mut self.current
+                && let Some((index, token)) = iter.next()
+            {
+                return Some((*offset + index, token));
+            }
+            let segment = self.segments.next()?;
+            self.current = Some((
+                segment.span.start,
+                WordBoundIndices::new(&self.text[segment.span]),
+            ));
+        }
+    }
+}
+
+/// Iterator over the words in the prose of a marked-up text, filtering out
+/// non-alphanumeric tokens like [`WordIndices`](super::WordIndices) does.
+#[derive(Clone, Debug)]
+pub struct MarkupWordIndices<'a> {
+    iter: MarkupWordBoundIndices<'a>,
+}
+
+impl<'a> MarkupWordIndices<'a> {
+    /// Create new iterator over the words in the prose of `text`.
+    pub fn new(text: &'a str, markup: Markup) -> MarkupWordIndices<'a> {
+        MarkupWordIndices {
+            iter: MarkupWordBoundIndices::new(text, markup),
+        }
+    }
+}
+
+impl<'a> Iterator for MarkupWordIndices<'a> {
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .by_ref()
+            .find(|(_, token)| token.chars().any(is_alphanumeric))
+    }
+}
+
+/// Collects prose ranges into segments, merging adjacent ones.
+struct Segments {
+    out: Vec<ProseSegment>,
+    current: Option<ProseSegment>,
+    pending: Boundary,
+}
+
+impl Segments {
+    fn new() -> Segments {
+        Segments {
+            out: vec![],
+            current: None,
+            pending: Boundary::Block,
+        }
+    }
+
+    fn prose(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        if let Some(current) = &mut self.current
+            && current.span.end == range.start
+        {
+            current.span.end = range.end;
+            return;
+        }
+        self.out.extend(self.current.take());
+        self.current = Some(ProseSegment {
+            span: range,
+            boundary: self.pending,
+        });
+        self.pending = Boundary::Inline;
+    }
+
+    /// Note that something other than prose comes next.
+    fn skip(&mut self, boundary: Boundary) {
+        match self.current.take() {
+            Some(segment) => {
+                self.out.push(segment);
+                self.pending = boundary;
+            }
+            None => self.pending = self.pending.max(boundary),
+        }
+    }
+
+    fn finish(mut self) -> Vec<ProseSegment> {
+        self.out.extend(self.current.take());
+        self.out
+    }
+}
+
+/// HTML elements whose content is not prose at all.
+const HTML_RAW: &[&str] = &[
+    "script", "style", "pre", "textarea", "math", "svg", "template",
+];
+
+/// HTML elements whose content is code or other non-prose shown inline.
+const HTML_CODE: &[&str] = &["code", "kbd", "samp", "var", "tt"];
+
+/// HTML elements that start or end a block.
+const HTML_BLOCK: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "body",
+    "br",
+    "caption",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "option",
+    "p",
+    "section",
+    "summary",
+    "table",
+    "td",
+    "th",
+    "title",
+    "tr",
+    "ul",
+];
+
+fn scan_html(text: &str, html: bool, segments: &mut Segments) {
+    let bytes = text.as_bytes();
+    let mut prose_start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let skipped = match bytes[i] {
+            b'<' => markup_at(text, i, html),
+            b'&' => entity_at(text, i).map(|end| (end, Boundary::Content)),
+            b'h' | b'f' | b'w' => url_at(text, i).map(|end| (end, Boundary::Content)),
+            _ => None,
+        };
+        match skipped {
+            Some((end, boundary)) => {
+                segments.prose(prose_start..i);
+                segments.skip(boundary);
+                i = end;
+                prose_start = end;
+            }
+            None => i += 1,
+        }
+    }
+    segments.prose(prose_start..text.len());
+}
+
+/// The end of the tag, comment or other markup starting with the `<` at
+/// `start`, and how strongly it separates the prose around it.
+fn markup_at(text: &str, start: usize, html: bool) -> Option<(usize, Boundary)> {
+    let rest = &text[start..];
+    let until = |pattern: &str, from: usize| {
+        rest[from..]
+            .find(pattern)
+            .map_or(text.len(), |i| start + from + i + pattern.len())
+    };
+
+    if rest.starts_with("<!--") {
+        return Some((until("-->", 4), Boundary::Inline));
+    }
+    if rest.starts_with("<![CDATA[") {
+        return Some((until("]]>", 9), Boundary::Content));
+    }
+    if rest.starts_with("<!") || rest.starts_with("<?") {
+        return Some((until(">", 2), Boundary::Block));
+    }
+
+    let closing = rest.as_bytes().get(1) == Some(&b'/');
+    let name_start = if closing { 2 } else { 1 };
+    if !rest[name_start..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name_len = rest[name_start..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, ':' | '-' | '_' | '.')))
+        .unwrap_or(rest.len() - name_start);
+    let name = rest[name_start..name_start + name_len].to_ascii_lowercase();
+    let end = start + tag_end(&rest[name_start + name_len..])? + name_start + name_len;
+
+    if !html {
+        return Some((end, Boundary::Inline));
+    }
+    let self_closing = text[..end].ends_with("/>");
+    let raw = HTML_RAW.contains(&name.as_str());
+    if !closing && !self_closing && (raw || HTML_CODE.contains(&name.as_str())) {
+        let close = format!("</{name}");
+        let content_end = text[end..]
+            .to_ascii_lowercase()
+            .find(&close)
+            .map_or(text.len(), |i| {
+                let close_start = end + i;
+                tag_end(&text[close_start..]).map_or(text.len(), |j| close_start + j)
+            });
+        let boundary = if raw {
+            Boundary::Block
+        } else {
+            Boundary::Content
+        };
+        return Some((content_end, boundary));
+    }
+    let boundary = if HTML_BLOCK.contains(&name.as_str()) {
+        Boundary::Block
+    } else {
+        Boundary::Inline
+    };
+    Some((end, boundary))
+}
+
+/// The length of `s` up to and including the `>` closing a tag, skipping over
+/// quoted attribute values.
+fn tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '>') => return Some(i + 1),
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The end of the character reference (`&amp;`, `&#228;`) at `start`.
+fn entity_at(text: &str, start: usize) -> Option<usize> {
+    let rest = &text[start + 1..];
+    let body_len = if let Some(hex) = rest.strip_prefix("#x").or(rest.strip_prefix("#X")) {
+        2 + hex
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(hex.len())
+    } else if let Some(dec) = rest.strip_prefix('#') {
+        1 + dec.find(|c: char| !c.is_ascii_digit()).unwrap_or(dec.len())
+    } else if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        rest.find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len())
+    } else {
+        return None;
+    };
+    (body_len > 1 && body_len <= 32 && rest[body_len..].starts_with(';'))
+        .then_some(start + 1 + body_len + 1)
+}
+
+/// The end of the bare URL starting at `start`, if one does. Punctuation at
+/// the end is left to the prose, since a URL at the end of a sentence is
+/// followed by its full stop.
+fn url_at(text: &str, start: usize) -> Option<usize> {
+    if text[..start]
+        .chars()
+        .next_back()
+        .is_some_and(is_alphanumeric)
+    {
+        return None;
+    }
+    url_len(&text[start..]).map(|len| start + len)
+}
+
+fn scan_markdown(text: &str, segments: &mut Segments) {
+    let mut pos = front_matter_end(text);
+    if pos > 0 {
+        segments.skip(Boundary::Block);
+    }
+
+    let mut fence: Option<(char, usize)> = None;
+    let mut indented_code = false;
+    let mut in_list = false;
+    let mut prev_blank = true;
+    let mut prev_heading = false;
+
+    while pos < text.len() {
+        let line_end = text[pos..].find('\n').map_or(text.len(), |i| pos + i + 1);
+        let line = text[pos..line_end].trim_end_matches(['\n', '\r']);
+        let indent = line
+            .chars()
+            .take_while(|c| matches!(c, ' ' | '\t'))
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum::<usize>();
+        let trimmed = line.trim_start_matches([' ', '\t']);
+
+        if let Some((fence_char, fence_len)) = fence {
+            let run = trimmed.chars().take_while(|&c| c == fence_char).count();
+            if indent <= 3 && run >= fence_len && trimmed[run..].trim().is_empty() {
+                fence = None;
+            }
+            pos = line_end;
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            segments.skip(Boundary::Block);
+            prev_blank = true;
+            pos = line_end;
+            continue;
+        }
+
+        if indented_code && indent >= 4 {
+            pos = line_end;
+            continue;
+        }
+        indented_code = false;
+
+        if indent <= 3
+            && let Some(fence_char) = ['`', '~'].into_iter().find(|&c| trimmed.starts_with(c))
+        {
+            let run = trimmed.chars().take_while(|&c| c == fence_char).count();
+            if run >= 3 {
+                segments.skip(Boundary::Block);
+                fence = Some((fence_char, run));
+                prev_blank = false;
+                pos = line_end;
+                continue;
+            }
+        }
+
+        if indent >= 4 && prev_blank && !in_list {
+            segments.skip(Boundary::Block);
+            indented_code = true;
+            pos = line_end;
+            continue;
+        }
+
+        if indent <= 3 && is_link_reference_definition(trimmed) {
+            segments.skip(Boundary::Block);
+            prev_blank = false;
+            pos = line_end;
+            continue;
+        }
+
+        if prev_blank && indent == 0 {
+            in_list = false;
+        }
+
+        // Block markers: blockquotes, list items and headings.
+        let mut content = pos + (line.len() - trimmed.len());
+        let mut new_block = prev_blank || prev_heading;
+        let mut heading = false;
+        loop {
+            let rest = &text[content..line_end];
+            if let Some(after) = rest.strip_prefix('>') {
+                content += 1 + (after.starts_with(' ') as usize);
+            } else if let Some(len) = list_marker_len(rest) {
+                content += len;
+                new_block = true;
+                in_list = true;
+            } else if let Some(len) = heading_marker_len(rest) {
+                content += len;
+                new_block = true;
+                heading = true;
+                break;
+            } else {
+                break;
+            }
+        }
+        if new_block {
+            segments.skip(Boundary::Block);
+        } else if content > pos + (line.len() - trimmed.len()) {
+            segments.skip(Boundary::Inline);
+        }
+
+        pos = scan_markdown_inline(text, content, line_end, segments);
+        prev_blank = false;
+        prev_heading = heading;
+    }
+}
+
+/// Skip inline markup between `start` and `end`, returning where scanning
+/// should continue: `end`, or later if an HTML element ran past it.
+fn scan_markdown_inline(text: &str, start: usize, end: usize, segments: &mut Segments) -> usize {
+    let bytes = text.as_bytes();
+    let mut prose_start = start;
+    let mut i = start;
+    while i < end {
+        let skipped = match bytes[i] {
+            b'\\' if bytes.get(i + 1).is_some_and(u8::is_ascii_punctuation) => {
+                Some((i + 1, Boundary::Inline))
+            }
+            b'`' => code_span_at(&text[..end], i).map(|e| (e, Boundary::Content)),
+            b'<' => autolink_at(&text[..end], i)
+                .map(|e| (e, Boundary::Content))
+                .or_else(|| markup_at(text, i, true)),
+            b'&' => entity_at(&text[..end], i).map(|e| (e, Boundary::Content)),
+            b']' => link_target_at(&text[..end], i + 1).map(|e| (e, Boundary::Inline)),
+            b'_' if is_emphasis_underscore(text, i) => Some((i + 1, Boundary::Inline)),
+            b'h' | b'f' | b'w' => url_at(&text[..end], i).map(|e| (e, Boundary::Content)),
+            _ => None,
+        };
+        match skipped {
+            Some((skip_end, boundary)) => {
+                // The closing bracket of a link text is prose; its target is not.
+                let prose_end = if bytes[i] == b']' { i + 1 } else { i };
+                segments.prose(prose_start..prose_end);
+                segments.skip(boundary);
+                if skip_end > end {
+                    return skip_end;
+                }
+                i = skip_end;
+                prose_start = skip_end;
+            }
+            None if bytes[i] == b'`' => {
+                // An unmatched run of backticks is literal text.
+                i += bytes[i..].iter().take_while(|&&b| b == b'`').count();
+            }
+            None => i += 1,
+        }
+    }
+    segments.prose(prose_start..end);
+    end
+}
+
+/// The end of a YAML or TOML front matter block at the start of `text`.
+fn front_matter_end(text: &str) -> usize {
+    for delimiter in ["---", "+++"] {
+        let Some(rest) = text.strip_prefix(delimiter) else {
+            continue;
+        };
+        if !rest.starts_with(['\n', '\r']) {
+            continue;
+        }
+        let mut pos = delimiter.len();
+        while pos < text.len() {
+            let line_end = text[pos..].find('\n').map_or(text.len(), |i| pos + i + 1);
+            let line = text[pos..line_end].trim_end();
+            if pos > delimiter.len() && (line == delimiter || (delimiter == "---" && line == "..."))
+            {
+                return line_end;
+            }
+            pos = line_end;
+        }
+    }
+    0
+}
+
+/// `[label]: destination`
+fn is_link_reference_definition(line: &str) -> bool {
+    line.strip_prefix('[')
+        .and_then(|rest| rest.find("]:").map(|i| &rest[..i]))
+        .is_some_and(|label| !label.is_empty() && !label.contains(['[', ']']))
+}
+
+/// The length of the list item marker at the start of `line`, with the
+/// space after it.
+fn list_marker_len(line: &str) -> Option<usize> {
+    let marker = if line.starts_with(['-', '*', '+']) {
+        1
+    } else {
+        let digits = line.chars().take_while(char::is_ascii_digit).count();
+        if digits == 0 || digits > 9 || !line[digits..].starts_with(['.', ')']) {
+            return None;
+        }
+        digits + 1
+    };
+    match line[marker..].chars().next() {
+        Some(' ' | '\t') => Some(marker + 1),
+        Some('\n' | '\r') | None => Some(marker),
+        Some(_) => None,
+    }
+}
+
+/// The length of the ATX heading marker at the start of `line`, with the
+/// space after it.
+fn heading_marker_len(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    match line[level..].chars().next() {
+        Some(' ' | '\t') => Some(level + 1),
+        Some('\n' | '\r') | None => Some(level),
+        Some(_) => None,
+    }
+}
+
+/// The end of the code span opening with the backticks at `start`.
+fn code_span_at(text: &str, start: usize) -> Option<usize> {
+    let run = text[start..].bytes().take_while(|&b| b == b'`').count();
+    let mut i = start + run;
+    while let Some(offset) = text[i..].find('`') {
+        let close = i + offset;
+        let len = text[close..].bytes().take_while(|&b| b == b'`').count();
+        if len == run {
+            return Some(close + len);
+        }
+        i = close + len;
+    }
+    None
+}
+
+/// The end of the `<scheme:...>` or `<user@host>` autolink at `start`.
+fn autolink_at(text: &str, start: usize) -> Option<usize> {
+    let len = text[start + 1..].find(['>', '<', ' ', '\t', '\n'])?;
+    let inner = &text[start + 1..start + 1 + len];
+    let is_link = inner.contains("://") || inner.starts_with("mailto:") || inner.contains('@');
+    (is_link && text[start + 1 + len..].starts_with('>')).then_some(start + len + 2)
+}
+
+/// The end of the link destination or reference label starting at `start`,
+/// right after the `]` of a link text: `(url "title")` or `[label]`.
+fn link_target_at(text: &str, start: usize) -> Option<usize> {
+    let (open, close) = match text[start..].chars().next()? {
+        '(' => ('(', ')'),
+        '[' => ('[', ']'),
+        _ => return None,
+    };
+    let mut depth = 0;
+    for (i, c) in text[start..].char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(start + i + 1);
+            }
+        }
+    }
+    None
+}
+
+/// Whether the `_` at `i` marks emphasis rather than joining a word, as in
+/// `snake_case`.
+fn is_emphasis_underscore(text: &str, i: usize) -> bool {
+    let before = text[..i].chars().next_back().is_some_and(is_alphanumeric);
+    let after = text[i + 1..].chars().next().is_some_and(is_alphanumeric);
+    !(before && after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str, markup: Markup) -> Vec<(usize, &str)> {
+        MarkupWordIndices::new(text, markup).collect()
+    }
+
+    fn word_list(text: &str, markup: Markup) -> Vec<&str> {
+        words(text, markup).into_iter().map(|(_, w)| w).collect()
+    }
+
+    #[test]
+    fn plain_text_is_all_prose() {
+        let text = "see <b>this</b> `code`";
+        assert_eq!(
+            prose_segments(text, Markup::Plain),
+            [ProseSegment {
+                span: 0..text.len(),
+                boundary: Boundary::Block
+            }]
+        );
+    }
+
+    #[test]
+    fn html_skips_tags_attributes_and_code() {
+        let text = r#"<p title="not prose">Hello <a href="x.html">wörld</a>!</p><pre>let x</pre><p>Use <code>grep</code> &amp; more</p>"#;
+        assert_eq!(
+            word_list(text, Markup::Html),
+            ["Hello", "wörld", "Use", "more"]
+        );
+        // Offsets point into the source.
+        for (index, word) in words(text, Markup::Html) {
+            assert_eq!(&text[index..index + word.len()], word);
+        }
+    }
+
+    #[test]
+    fn html_boundaries() {
+        let segments = prose_segments(
+            "<p>One <i>two</i></p><p>three <code>x</code> four",
+            Markup::Html,
+        );
+        let boundaries: Vec<_> = segments.iter().map(|s| s.boundary).collect();
+        assert_eq!(
+            boundaries,
+            [
+                Boundary::Block,
+                Boundary::Inline,
+                Boundary::Block,
+                Boundary::Content
+            ]
+        );
+    }
+
+    #[test]
+    fn xml_keeps_all_element_text() {
+        let text = "<?xml version=\"1.0\"?>\n<doc><code>Text</code> <!-- note --> here</doc>";
+        assert_eq!(word_list(text, Markup::Xml), ["Text", "here"]);
+    }
+
+    #[test]
+    fn markdown_skips_code_and_link_targets() {
+        let text = "# Title\n\nSome `inline code` and [a link](https://example.com \"title\").\n\n```rust\nfn main() {}\n```\n\n    indented code\n\nSee <https://divvun.no> or www.divvun.no, _emphasis_ and snake_case.\n";
+        assert_eq!(
+            word_list(text, Markup::Markdown),
+            [
+                "Title",
+                "Some",
+                "and",
+                "a",
+                "link",
+                "See",
+                "or",
+                "emphasis",
+                "and",
+                "snake_case"
+            ]
+        );
+    }
+
+    #[test]
+    fn markdown_blocks() {
+        let text = "---\ntitle: Front\n---\nFirst line\ncontinues\n\n- item one\n- item two\n\n> quoted\n> more\n";
+        let segments = prose_segments(text, Markup::Markdown);
+        let found: Vec<_> = segments
+            .iter()
+            .map(|s| (text[s.span.clone()].trim_end(), s.boundary))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("First line\ncontinues", Boundary::Block),
+                ("item one", Boundary::Block),
+                ("item two", Boundary::Block),
+                ("quoted", Boundary::Block),
+                ("more", Boundary::Inline),
+            ]
+        );
+    }
+
+    #[test]
+    fn markdown_inline_html_and_references() {
+        let text =
+            "A <span class=\"x\">word</span> with a [ref][id].\n\n[id]: https://example.com\n";
+        assert_eq!(
+            word_list(text, Markup::Markdown),
+            ["A", "word", "with", "a", "ref"]
+        );
+    }
+
+    #[test]
+    fn markup_from_path_and_str() {
+        assert_eq!(Markup::from_path(Path::new("README.md")), Markup::Markdown);
+        assert_eq!(Markup::from_path(Path::new("a/index.HTML")), Markup::Html);
+        assert_eq!(Markup::from_path(Path::new("notes.txt")), Markup::Plain);
+        assert_eq!("markdown".parse(), Ok(Markup::Markdown));
+        assert!("latex".parse::<Markup>().is_err());
+    }
+}
@@ -1,9 +1,21 @@
 //! Tokenizer splits strings into words and punctuations.
+use markup::{Markup, MarkupWordBoundIndices, MarkupWordIndices};
+use offsets::{OffsetConverter, OffsetUnit, UnitIndices};
+use sentence::SentenceBoundIndices;
+use smol_str::SmolStr;
 use std::borrow::Cow;
+use token::TokenIndices;
 use unic_ucd_common::alphanumeric::is_alphanumeric;
 use word::{WordBoundIndices, Words};
 
 pub(crate) mod case_handling;
+mod extended_pictographic;
+pub mod markup;
+pub mod offsets;
+pub mod rules;
+pub mod sentence;
+pub(crate) mod sentence_break;
+pub mod token;
 pub mod word;
 mod word_break;
 
@@ -11,6 +23,7 @@ mod word_break;
 ///
 /// Returns tuples of (byte_offset, word_str) for each word containing at least
 /// one alphanumeric character.
+#[derive(Clone, Debug)]
 pub struct WordIndices<'a> {
     iter: WordBoundIndices<'a>,
 }
@@ -45,6 +58,39 @@ pub trait Tokenize {
 
     /// Get words using a custom alphabet.
     fn words_with_alphabet(&self, alphabet: Vec<char>) -> Words<'_>;
+
+    /// Get an iterator over the tokens of the string, each labelled with its
+    /// kind (see [`token`]).
+    fn token_indices(&self) -> TokenIndices<'_>;
+
+    /// Get classified tokens, where any of the given abbreviations followed by
+    /// a full stop is an abbreviation token.
+    fn token_indices_with_abbreviations(&self, abbreviations: Vec<SmolStr>) -> TokenIndices<'_>;
+
+    /// Get an iterator over word boundaries with indices counted in `unit`.
+    fn word_bound_indices_in(&self, unit: OffsetUnit) -> UnitIndices<'_, WordBoundIndices<'_>>;
+
+    /// Get an iterator over words with indices counted in `unit`
+    /// (alphanumeric words only).
+    fn word_indices_in(&self, unit: OffsetUnit) -> UnitIndices<'_, WordIndices<'_>>;
+
+    /// Get an iterator over sentence boundaries with byte indices.
+    fn sentence_bound_indices(&self) -> SentenceBoundIndices<'_>;
+
+    /// Get sentence boundaries, where a full stop after any of the given
+    /// abbreviations does not end a sentence (see [`sentence::abbreviations`]).
+    fn sentence_bound_indices_with_abbreviations(
+        &self,
+        abbreviations: Vec<SmolStr>,
+    ) -> SentenceBoundIndices<'_>;
+
+    /// Get word boundaries within the prose of marked-up text, with byte
+    /// indices into the whole text (see [`markup`]).
+    fn markup_word_bound_indices(&self, markup: Markup) -> MarkupWordBoundIndices<'_>;
+
+    /// Get the words in the prose of marked-up text, with byte indices into
+    /// the whole text (alphanumeric words only).
+    fn markup_word_indices(&self, markup: Markup) -> MarkupWordIndices<'_>;
 }
 
 impl Tokenize for str {
@@ -65,6 +111,41 @@ impl Tokenize for str {
     fn words_with_alphabet(&self, alphabet: Vec<char>) -> Words<'_> {
         Words::new_with_alphabet(self, |s| s.chars().any(|ch| ch.is_alphanumeric()), alphabet)
     }
+
+    fn token_indices(&self) -> TokenIndices<'_> {
+        TokenIndices::new(self)
+    }
+
+    fn token_indices_with_abbreviations(&self, abbreviations: Vec<SmolStr>) -> TokenIndices<'_> {
+        TokenIndices::new_with_abbreviations(self, abbreviations)
+    }
+
+    fn word_bound_indices_in(&self, unit: OffsetUnit) -> UnitIndices<'_, WordBoundIndices<'_>> {
+        UnitIndices::new(self, self.word_bound_indices(), unit)
+    }
+
+    fn word_indices_in(&self, unit: OffsetUnit) -> UnitIndices<'_, WordIndices<'_>> {
+        UnitIndices::new(self, self.word_indices(), unit)
+    }
+
+    fn sentence_bound_indices(&self) -> SentenceBoundIndices<'_> {
+        SentenceBoundIndices::new(self)
+    }
+
+    fn sentence_bound_indices_with_abbreviations(
+        &self,
+        abbreviations: Vec<SmolStr>,
+    ) -> SentenceBoundIndices<'_> {
+        SentenceBoundIndices::new_with_abbreviations(self, abbreviations)
+    }
+
+    fn markup_word_bound_indices(&self, markup: Markup) -> MarkupWordBoundIndices<'_> {
+        MarkupWordBoundIndices::new(self, markup)
+    }
+
+    fn markup_word_indices(&self, markup: Markup) -> MarkupWordIndices<'_> {
+        MarkupWordIndices::new(self, markup)
+    }
 }
 
 /// A word with its byte offset in the original string.
@@ -78,6 +159,10 @@ pub struct IndexedWord {
 /// Context information for a word, including surrounding words.
 ///
 /// Useful for context-sensitive spell-checking and analysis.
+///
+/// The offsets of the current word and the words before it count from the
+/// start of the first half of the text; those of the words after it count
+/// from the start of the second half.
 #[derive(Debug, Clone)]
 pub struct WordContext<'a> {
     /// The current word (byte_offset, text)
@@ -151,10 +236,53 @@ pub fn cursor_context<'a>(first_half: &'a str, second_half: &'a str) -> WordCont
     }
 }
 
+/// Like [`cursor_context`], with the offsets in the returned context counted
+/// in `unit` rather than in bytes.
+pub fn cursor_context_in<'a>(
+    first_half: &'a str,
+    second_half: &'a str,
+    unit: OffsetUnit,
+) -> WordContext<'a> {
+    let context = cursor_context(first_half, second_half);
+
+    let mut first = OffsetConverter::new(first_half, unit);
+    let mut second = OffsetConverter::new(second_half, unit);
+
+    let (index, current) = context.current;
+    let index = if index >= first_half.len() {
+        unit.len_of(first_half) + second.convert(index - first_half.len())
+    } else {
+        first.convert(index)
+    };
+    let mut first = |word: Option<(usize, &'a str)>| word.map(|(i, w)| (first.convert(i), w));
+    let second_before = first(context.second_before);
+    let first_before = first(context.first_before);
+    let mut second = |word: Option<(usize, &'a str)>| word.map(|(i, w)| (second.convert(i), w));
+
+    WordContext {
+        current: (index, current),
+        first_before,
+        second_before,
+        first_after: second(context.first_after),
+        second_after: second(context.second_after),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn cursor_context_in_utf16() {
+        let context = cursor_context_in("😄 sámi wo", "rld 😄 next", OffsetUnit::Utf16);
+        assert_eq!(context.current, (8, Cow::Owned("world".to_string())));
+        assert_eq!(context.first_before, Some((3, "sámi")));
+        assert_eq!(context.first_after, Some((7, "next")));
+
+        let context = cursor_context_in("😄 ", "sámi", OffsetUnit::CodePoint);
+        assert_eq!(context.current, (2, Cow::Borrowed("sámi")));
+    }
+
     #[test]
     fn rev() {
         let msg = "this is life";
@@ -0,0 +1,141 @@
+//! Offsets in units other than bytes.
+//!
+//! The tokenizer works on `&str` and reports byte offsets. Platforms whose
+//! strings are UTF-16 — Java and Android, Windows, JavaScript — count in
+//! UTF-16 code units instead, and some consumers count code points. The
+//! adapters here convert as the tokens go by, without a second pass over the
+//! text.
+
+/// The unit an offset into a string is counted in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OffsetUnit {
+    /// UTF-8 bytes, as used by `str` indexing
+    #[default]
+    Byte,
+    /// UTF-16 code units, as used by Java, C#, JavaScript and Windows strings
+    Utf16,
+    /// Unicode scalar values, as used by Python strings
+    CodePoint,
+}
+
+impl OffsetUnit {
+    /// The length of `s` in this unit.
+    pub fn len_of(self, s: &str) -> usize {
+        match self {
+            OffsetUnit::Byte => s.len(),
+            OffsetUnit::Utf16 => s.chars().map(char::len_utf16).sum(),
+            OffsetUnit::CodePoint => s.chars().count(),
+        }
+    }
+}
+
+/// Converts byte offsets into one string to another [`OffsetUnit`].
+///
+/// Conversion is incremental: converting offsets in ascending order costs
+/// one pass over the text in total. Going backwards starts over from the
+/// beginning.
+#[derive(Clone, Debug)]
+pub struct OffsetConverter<'a> {
+    text: &'a str,
+    unit: OffsetUnit,
+    byte: usize,
+    converted: usize,
+}
+
+impl<'a> OffsetConverter<'a> {
+    /// Create a converter for offsets into `text`.
+    pub fn new(text: &'a str, unit: OffsetUnit) -> OffsetConverter<'a> {
+        OffsetConverter {
+            text,
+            unit,
+            byte: 0,
+            converted: 0,
+        }
+    }
+
+    /// Convert the byte offset `byte`, which must lie on a char boundary of
+    /// the text.
+    pub fn convert(&mut self, byte: usize) -> usize {
+        if self.unit == OffsetUnit::Byte {
+            return byte;
+        }
+        if byte < self.byte {
+            self.byte = 0;
+            self.converted = 0;
+        }
+        self.converted += self.unit.len_of(&self.text[self.byte..byte]);
+        self.byte = byte;
+        self.converted
+    }
+}
+
+/// Iterator adapter turning the byte offsets of a tokenizer iterator over
+/// one string into offsets in another [`OffsetUnit`].
+#[derive(Clone, Debug)]
+pub struct UnitIndices<'a, I> {
+    iter: I,
+    converter: OffsetConverter<'a>,
+}
+
+impl<'a, I> UnitIndices<'a, I>
+where
+    I: Iterator<Item = (usize, &'a str)>,
+{
+    /// Wrap `iter`, which yields byte offsets into `text`.
+    pub fn new(text: &'a str, iter: I, unit: OffsetUnit) -> UnitIndices<'a, I> {
+        UnitIndices {
+            iter,
+            converter: OffsetConverter::new(text, unit),
+        }
+    }
+}
+
+impl<'a, I> Iterator for UnitIndices<'a, I>
+where
+    I: Iterator<Item = (usize, &'a str)>,
+{
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<(usize, &'a str)> {
+        let (index, token) = self.iter.next()?;
+        Some((self.converter.convert(index), token))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::Tokenize;
+
+    #[test]
+    fn units() {
+        let text = "a😄é b";
+        assert_eq!(OffsetUnit::Byte.len_of(text), 9);
+        assert_eq!(OffsetUnit::Utf16.len_of(text), 6);
+        assert_eq!(OffsetUnit::CodePoint.len_of(text), 5);
+    }
+
+    #[test]
+    fn converter_goes_back_and_forth() {
+        let text = "😄😄x";
+        let mut converter = OffsetConverter::new(text, OffsetUnit::Utf16);
+        assert_eq!(converter.convert(8), 4);
+        assert_eq!(converter.convert(4), 2);
+        assert_eq!(converter.convert(9), 5);
+    }
+
+    #[test]
+    fn word_indices_in_units() {
+        let text = "Čáhppes 😄 bussá";
+        let utf16: Vec<_> = text.word_indices_in(OffsetUnit::Utf16).collect();
+        assert_eq!(utf16, [(0, "Čáhppes"), (11, "bussá")]);
+        let chars: Vec<_> = text.word_indices_in(OffsetUnit::CodePoint).collect();
+        assert_eq!(chars, [(0, "Čáhppes"), (10, "bussá")]);
+        let bytes: Vec<_> = text.word_indices_in(OffsetUnit::Byte).collect();
+        assert_eq!(bytes, text.word_indices().collect::<Vec<_>>());
+    }
+}
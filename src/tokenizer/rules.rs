@@ -0,0 +1,182 @@
+//! Per-language tokenizer rules.
+//!
+//! The default word boundaries are the same for every language, and get some
+//! languages wrong: a letter Unicode files as a symbol ends the word, and so
+//! does punctuation a language uses inside its words. A speller archive can
+//! declare [`TokenizerRules`] for its language in its metadata, and a
+//! [`Tokenizer`] applies them, together with the language's abbreviations.
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+
+use super::WordIndices;
+use super::offsets::{OffsetUnit, UnitIndices};
+use super::sentence::{SentenceBoundIndices, abbreviations};
+use super::token::TokenIndices;
+use super::word::WordBoundIndices;
+
+/// The hyphens [`Hyphens::Join`] joins words with.
+const HYPHENS: [char; 3] = ['-', '\u{2010}', '\u{2011}'];
+
+/// How a hyphen between two words is tokenized.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Hyphens {
+    /// the hyphen is a token of its own: "e-post" is "e", "-" and "post"
+    #[default]
+    Split,
+    /// a hyphen between letters is part of the word: "e-post" is one word
+    Join,
+}
+
+/// How a language's words are written, as far as the tokenizer is concerned.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct TokenizerRules {
+    /// characters that are letters in this language, whatever their Unicode
+    /// properties say
+    pub word_chars: String,
+    /// characters that join the letters on both sides of them into one word,
+    /// as an apostrophe does, but are not part of a word otherwise
+    pub word_internal: String,
+    /// how hyphens between words are handled
+    pub hyphens: Hyphens,
+}
+
+/// A tokenizer following the rules of one language.
+///
+/// [`SpellerArchive::tokenizer`](crate::archive::SpellerArchive::tokenizer)
+/// gives one configured from the archive's metadata.
+#[derive(Clone, Debug, Default)]
+pub struct Tokenizer {
+    word_chars: Vec<char>,
+    word_internal: Vec<char>,
+    abbreviations: Vec<SmolStr>,
+}
+
+impl Tokenizer {
+    /// Create a tokenizer following `rules`, which knows no abbreviations.
+    pub fn new(rules: &TokenizerRules) -> Tokenizer {
+        let mut word_internal: Vec<char> = rules.word_internal.chars().collect();
+        if rules.hyphens == Hyphens::Join {
+            word_internal.extend(HYPHENS);
+        }
+        Tokenizer {
+            word_chars: rules.word_chars.chars().collect(),
+            word_internal,
+            abbreviations: vec![],
+        }
+    }
+
+    /// Create a tokenizer following `rules`, which knows the abbreviations of
+    /// `language`, a BCP 47 tag (see [`abbreviations`]).
+    pub fn for_language(language: &str, rules: &TokenizerRules) -> Tokenizer {
+        Tokenizer {
+            abbreviations: abbreviations(language),
+            ..Tokenizer::new(rules)
+        }
+    }
+
+    /// The abbreviations that do not end a sentence.
+    pub fn abbreviations(&self) -> &[SmolStr] {
+        &self.abbreviations
+    }
+
+    /// Get an iterator over the word boundaries of `text` with byte indices.
+    pub fn word_bound_indices<'a>(&self, text: &'a str) -> WordBoundIndices<'a> {
+        WordBoundIndices::new_with_word_chars(
+            text,
+            self.word_chars.clone(),
+            self.word_internal.clone(),
+        )
+    }
+
+    /// Get an iterator over the words of `text` with byte indices
+    /// (alphanumeric words only).
+    pub fn word_indices<'a>(&self, text: &'a str) -> WordIndices<'a> {
+        WordIndices {
+            iter: self.word_bound_indices(text),
+        }
+    }
+
+    /// Get an iterator over the words of `text` with indices counted in
+    /// `unit` (alphanumeric words only).
+    pub fn word_indices_in<'a>(
+        &self,
+        text: &'a str,
+        unit: OffsetUnit,
+    ) -> UnitIndices<'a, WordIndices<'a>> {
+        UnitIndices::new(text, self.word_indices(text), unit)
+    }
+
+    /// Get an iterator over the classified tokens of `text`.
+    pub fn token_indices<'a>(&self, text: &'a str) -> TokenIndices<'a> {
+        TokenIndices::from_word_bounds(
+            text,
+            self.word_bound_indices(text),
+            self.abbreviations.clone(),
+        )
+    }
+
+    /// Get an iterator over the sentence boundaries of `text` with byte
+    /// indices.
+    pub fn sentence_bound_indices<'a>(&self, text: &'a str) -> SentenceBoundIndices<'a> {
+        SentenceBoundIndices::new_with_abbreviations(text, self.abbreviations.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::Tokenize;
+
+    fn words<'a>(tokenizer: &Tokenizer, text: &'a str) -> Vec<&'a str> {
+        tokenizer.word_indices(text).map(|(_, w)| w).collect()
+    }
+
+    #[test]
+    fn default_rules_tokenize_as_unicode_does() {
+        let text = "Sámi e-post, EU:n ja`n 50%";
+        let tokenizer = Tokenizer::new(&TokenizerRules::default());
+        let expected: Vec<_> = text.word_indices().map(|(_, w)| w).collect();
+        assert_eq!(words(&tokenizer, text), expected);
+    }
+
+    #[test]
+    fn word_chars_and_word_internal() {
+        let rules = TokenizerRules {
+            word_chars: "%".into(),
+            word_internal: "`".into(),
+            ..TokenizerRules::default()
+        };
+        let tokenizer = Tokenizer::new(&rules);
+        assert_eq!(words(&tokenizer, "ja`n 50% `x"), ["ja`n", "50%", "x"]);
+        let default = Tokenizer::new(&TokenizerRules::default());
+        assert_eq!(words(&default, "ja`n 50% `x"), ["ja", "n", "50", "x"]);
+    }
+
+    #[test]
+    fn hyphens() {
+        let text = "e-post - nord- og sør-samisk";
+        let split = Tokenizer::new(&TokenizerRules::default());
+        assert_eq!(
+            words(&split, text),
+            ["e", "post", "nord", "og", "sør", "samisk"]
+        );
+        let join = Tokenizer::new(&TokenizerRules {
+            hyphens: Hyphens::Join,
+            ..TokenizerRules::default()
+        });
+        assert_eq!(words(&join, text), ["e-post", "nord", "og", "sør-samisk"]);
+    }
+
+    #[test]
+    fn language_abbreviations() {
+        let tokenizer = Tokenizer::for_language("nb-NO", &TokenizerRules::default());
+        let sentences: Vec<_> = tokenizer
+            .sentence_bound_indices("Ta med f.eks. Ola. Han kan.")
+            .map(|(_, s)| s)
+            .collect();
+        assert_eq!(sentences, ["Ta med f.eks. Ola. ", "Han kan."]);
+    }
+}
@@ -0,0 +1,382 @@
+//! Unicode Sentences of a string.
+//!
+//! Implements the default sentence boundary rules of UAX #29, with one
+//! tailoring: a full stop right after a known abbreviation ("Dr.", "f.eks.")
+//! does not end the sentence. Abbreviations are per language; see
+//! [`abbreviations`].
+//!
+//! ## References
+//!
+//! * <https://www.unicode.org/reports/tr29/#Sentence_Boundaries>
+
+use smol_str::SmolStr;
+
+use super::case_handling::lower_case;
+use super::sentence_break::SentenceBreak as SB;
+
+/// External iterator for a string's
+/// [sentence boundaries](https://www.unicode.org/reports/tr29/#Sentence_Boundaries).
+///
+/// Every part of the string belongs to exactly one sentence: the whitespace
+/// after a sentence's terminal punctuation, and any paragraph separator, are
+/// part of the sentence they follow.
+#[derive(Clone, Debug)]
+pub struct SentenceBounds<'a> {
+    string: &'a str,
+    abbreviations: Vec<SmolStr>,
+    /// Whether a full stop and a space end a sentence before a lower-case
+    /// word too; see [`SentenceBoundIndices::lower_case_starts`].
+    lower_case_starts: bool,
+}
+
+/// External iterator for sentence boundaries and byte offsets.
+#[derive(Clone, Debug)]
+pub struct SentenceBoundIndices<'a> {
+    start_offset: usize,
+    iter: SentenceBounds<'a>,
+}
+
+impl<'a> SentenceBoundIndices<'a> {
+    /// Create new iterator for *sentence boundaries and their indices*.
+    #[inline]
+    pub fn new(s: &str) -> SentenceBoundIndices<'_> {
+        SentenceBoundIndices {
+            start_offset: s.as_ptr() as usize,
+            iter: SentenceBounds::new(s),
+        }
+    }
+
+    /// Create new iterator for *sentence boundaries and their indices*, where
+    /// a full stop after any of `abbreviations` does not end a sentence.
+    #[inline]
+    pub fn new_with_abbreviations(
+        s: &'a str,
+        abbreviations: Vec<SmolStr>,
+    ) -> SentenceBoundIndices<'a> {
+        SentenceBoundIndices {
+            start_offset: s.as_ptr() as usize,
+            iter: SentenceBounds::new_with_abbreviations(s, abbreviations),
+        }
+    }
+
+    /// Let a full stop followed by a space end a sentence before a lower-case
+    /// word, where UAX #29 (SB8) takes it for an abbreviation. Only the known
+    /// abbreviations then keep the sentence going, which is what finding
+    /// sentences that start in lower case needs; "file.txt" stays whole.
+    #[inline]
+    pub(crate) fn lower_case_starts(mut self) -> SentenceBoundIndices<'a> {
+        self.iter.lower_case_starts = true;
+        self
+    }
+
+    #[inline]
+    /// View the underlying data (the part yet to be iterated) as a slice of the original string.
+    pub fn as_str(&self) -> &'a str {
+        self.iter.as_str()
+    }
+}
+
+impl<'a> Iterator for SentenceBoundIndices<'a> {
+    type Item = (usize, &'a str);
+
+    #[inline]
+    fn next(&mut self) -> Option<(usize, &'a str)> {
+        self.iter
+            .next()
+            .map(|s| (s.as_ptr() as usize - self.start_offset, s))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a> Iterator for SentenceBounds<'a> {
+    type Item = &'a str;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let slen = self.string.len();
+        (std::cmp::min(slen, 1), Some(slen))
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.string.is_empty() {
+            return None;
+        }
+
+        let end = self.next_boundary();
+        let (sentence, rest) = self.string.split_at(end);
+        self.string = rest;
+        Some(sentence)
+    }
+}
+
+impl<'a> SentenceBounds<'a> {
+    /// Create new iterator for *sentence boundaries*.
+    #[inline]
+    pub fn new(s: &'a str) -> SentenceBounds<'a> {
+        SentenceBounds {
+            string: s,
+            abbreviations: Vec::new(),
+            lower_case_starts: false,
+        }
+    }
+
+    /// Create new iterator for *sentence boundaries*, where a full stop after
+    /// any of `abbreviations` does not end a sentence. Abbreviations are
+    /// matched without regard to case and without their final full stop, so
+    /// `"f.eks"` covers "f.eks." and "F.eks.".
+    #[inline]
+    pub fn new_with_abbreviations(s: &'a str, abbreviations: Vec<SmolStr>) -> SentenceBounds<'a> {
+        SentenceBounds {
+            string: s,
+            abbreviations: abbreviations.iter().map(|a| lower_case(a)).collect(),
+            lower_case_starts: false,
+        }
+    }
+
+    #[inline]
+    /// View the underlying data (the part yet to be iterated) as a slice of the original string.
+    pub fn as_str(&self) -> &'a str {
+        self.string
+    }
+
+    /// Byte offset of the end of the first sentence of `self.string`.
+    fn next_boundary(&self) -> usize {
+        let s = self.string;
+        // The category of the last character that was not Extend or Format
+        // (SB5), for SB7.
+        let mut prev: Option<SB> = None;
+
+        for (idx, ch) in s.char_indices() {
+            let cat = SB::of(ch);
+            let next = idx + ch.len_utf8();
+            match cat {
+                // SB3, SB4
+                SB::CR => {
+                    return if s[next..].starts_with('\n') {
+                        next + 1
+                    } else {
+                        next
+                    };
+                }
+                SB::LF | SB::Sep => return next,
+                // SB5
+                SB::Extend | SB::Format if prev.is_some() => continue,
+                SB::ATerm | SB::STerm => {
+                    if let Some(end) = self.terminator_end(idx, cat, prev) {
+                        return end;
+                    }
+                }
+                _ => {}
+            }
+            prev = Some(cat);
+        }
+
+        s.len()
+    }
+
+    /// Where the sentence ends if the terminator `term` at byte `idx` ends it
+    /// — past any closing punctuation, spaces and one paragraph separator that
+    /// follow — or `None` if the rules say the sentence goes on.
+    fn terminator_end(&self, idx: usize, term: SB, prev: Option<SB>) -> Option<usize> {
+        let s = self.string;
+        let end = idx + s[idx..].chars().next().map_or(0, char::len_utf8);
+
+        // SB9, SB10: SATerm Close* Sp* stays together.
+        let mut closes = 0;
+        let mut spaces = 0;
+        let mut chars = s[end..].char_indices().peekable();
+        let mut after = None;
+        while let Some(&(offset, ch)) = chars.peek() {
+            match SB::of(ch) {
+                SB::Close if spaces == 0 => closes += 1,
+                SB::Sp => spaces += 1,
+                SB::Extend | SB::Format => {}
+                cat => {
+                    after = Some((end + offset, ch, cat));
+                    break;
+                }
+            }
+            chars.next();
+        }
+        let Some((after_idx, after_ch, after_cat)) = after else {
+            return Some(s.len());
+        };
+
+        match after_cat {
+            // SB9, SB10, SB11: a paragraph separator belongs to the sentence.
+            SB::CR => {
+                let next = after_idx + 1;
+                return Some(if s[next..].starts_with('\n') {
+                    next + 1
+                } else {
+                    next
+                });
+            }
+            SB::LF | SB::Sep => return Some(after_idx + after_ch.len_utf8()),
+            // SB8a
+            SB::SContinue | SB::ATerm | SB::STerm => return None,
+            _ => {}
+        }
+
+        if term == SB::ATerm {
+            let adjacent = closes == 0 && spaces == 0;
+            // SB6
+            if adjacent && after_cat == SB::Numeric {
+                return None;
+            }
+            // SB7
+            if adjacent
+                && matches!(prev, Some(SB::Upper) | Some(SB::Lower))
+                && after_cat == SB::Upper
+            {
+                return None;
+            }
+            // SB8
+            let decider = s[after_idx..].chars().map(SB::of).find(|cat| {
+                matches!(
+                    cat,
+                    SB::OLetter
+                        | SB::Upper
+                        | SB::Lower
+                        | SB::Sep
+                        | SB::CR
+                        | SB::LF
+                        | SB::ATerm
+                        | SB::STerm
+                )
+            });
+            if decider == Some(SB::Lower) && !(self.lower_case_starts && spaces > 0) {
+                return None;
+            }
+            if self.is_abbreviation(&s[..idx]) {
+                return None;
+            }
+        }
+
+        // SB11
+        Some(after_idx)
+    }
+
+    /// Whether the word ending `before` — the text up to a full stop — is one
+    /// of the abbreviations.
+    fn is_abbreviation(&self, before: &str) -> bool {
+        if self.abbreviations.is_empty() {
+            return false;
+        }
+        let start = before
+            .char_indices()
+            .rev()
+            .find(|(_, ch)| ch.is_whitespace())
+            .map_or(0, |(i, ch)| i + ch.len_utf8());
+        let word = before[start..].trim_start_matches(|ch: char| !ch.is_alphanumeric());
+        !word.is_empty() && self.abbreviations.contains(&lower_case(word))
+    }
+}
+
+/// Common abbreviations for `language`, a BCP 47 tag or bare ISO 639 code,
+/// written without their final full stop.
+///
+/// Only the primary language subtag is looked at. Languages without a list
+/// get none, which leaves the default UAX #29 behaviour.
+pub fn abbreviations(language: &str) -> Vec<SmolStr> {
+    let primary = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let list: &[&str] = match primary.as_str() {
+        "en" | "eng" => &[
+            "mr", "mrs", "ms", "dr", "prof", "st", "jr", "sr", "vs", "etc", "e.g", "i.e", "no",
+            "fig", "approx",
+        ],
+        "nb" | "nn" | "no" | "nob" | "nno" | "nor" => &[
+            "bl.a", "ca", "dvs", "evt", "f.eks", "jf", "kl", "mht", "mv", "nr", "osv", "pga", "s",
+            "stk",
+        ],
+        "sv" | "swe" => &[
+            "bl.a", "ca", "dvs", "el", "jfr", "kl", "m.m", "nr", "osv", "resp", "s", "s.k", "t.ex",
+        ],
+        "fi" | "fin" => &[
+            "esim", "jne", "ks", "mm", "n", "ns", "nro", "s", "tms", "v", "yms",
+        ],
+        "se" | "sme" | "smj" | "sma" => &["bl.a", "jna", "nr", "omd", "s", "st"],
+        _ => &[],
+    };
+    list.iter().map(SmolStr::new).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentences(s: &str) -> Vec<&str> {
+        SentenceBounds::new(s).collect()
+    }
+
+    fn sentences_in<'a>(s: &'a str, language: &str) -> Vec<&'a str> {
+        SentenceBounds::new_with_abbreviations(s, abbreviations(language)).collect()
+    }
+
+    #[test]
+    fn splits_after_terminators_and_trailing_space() {
+        assert_eq!(
+            sentences("Hello world. How are you? Fine!"),
+            ["Hello world. ", "How are you? ", "Fine!"]
+        );
+    }
+
+    #[test]
+    fn paragraph_separators_end_sentences() {
+        assert_eq!(
+            sentences("One line\nAnother.\r\nThird"),
+            ["One line\n", "Another.\r\n", "Third"]
+        );
+    }
+
+    #[test]
+    fn closing_punctuation_stays_with_the_sentence() {
+        assert_eq!(
+            sentences("He said “Stop.” Then (left.) Gone."),
+            ["He said “Stop.” ", "Then (left.) ", "Gone."]
+        );
+    }
+
+    // SB6, SB7, SB8, SB8a.
+    #[test]
+    fn full_stops_that_do_not_end_sentences() {
+        assert_eq!(sentences("Pi is 3.14 exactly."), ["Pi is 3.14 exactly."]);
+        assert_eq!(sentences("The U.S.A. is big."), ["The U.S.A. is big."]);
+        assert_eq!(sentences("See e.g. this one."), ["See e.g. this one."]);
+        assert_eq!(sentences("Wait... what, no?!"), ["Wait... what, no?!"]);
+    }
+
+    #[test]
+    fn abbreviations_are_per_language() {
+        let text = "Ask Dr. Smith. He knows.";
+        assert_eq!(sentences(text), ["Ask Dr. ", "Smith. ", "He knows."]);
+        assert_eq!(
+            sentences_in(text, "en-GB"),
+            ["Ask Dr. Smith. ", "He knows."]
+        );
+        assert_eq!(
+            sentences_in("Ta med f.eks. Ola. Han kan.", "nb"),
+            ["Ta med f.eks. Ola. ", "Han kan."]
+        );
+        assert_eq!(
+            sentences_in("Ta med f.eks. Ola. Han kan.", "se"),
+            ["Ta med f.eks. ", "Ola. ", "Han kan."]
+        );
+    }
+
+    #[test]
+    fn indices_cover_the_whole_string() {
+        let text = "Go. Now!  Stop?";
+        let indices: Vec<_> = SentenceBoundIndices::new(text).collect();
+        assert_eq!(indices, [(0, "Go. "), (4, "Now!  "), (10, "Stop?")]);
+        assert_eq!(sentences(""), Vec::<&str>::new());
+    }
+}
@@ -0,0 +1,410 @@
+//! Tokens labelled with what they are.
+//!
+//! [`WordBoundIndices`] splits text at the UAX #29 word boundaries, which cut
+//! URLs, e-mail addresses and hashtags into pieces and tell a number from a
+//! word only by looking at the characters. [`TokenIndices`] puts those pieces
+//! back together and labels every token with a [`TokenKind`], so a
+//! spell-checking front end can pass only the words to the speller.
+use std::fmt;
+use std::iter::Peekable;
+
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use unic_ucd_common::alphanumeric::is_alphanumeric;
+
+use super::case_handling::lower_case;
+use super::extended_pictographic::is_extended_pictographic;
+use super::word::WordBoundIndices;
+use super::word_break::WordBreak as WB;
+
+/// What a token is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum TokenKind {
+    /// a word, possibly with digits in it ("sámi", "4G")
+    Word,
+    /// a number, possibly with separators ("42", "3.14", "1,000")
+    Number,
+    /// a web address ("https://divvun.no/", "www.divvun.no")
+    Url,
+    /// an e-mail address
+    Email,
+    /// a `#` followed by a word
+    Hashtag,
+    /// an `@` followed by a name
+    Mention,
+    /// one emoji, including its modifiers and joined sequences
+    Emoji,
+    /// punctuation and other symbols
+    Punctuation,
+    /// spaces and line breaks
+    Whitespace,
+    /// a word with its abbreviating full stop ("e.g.", "Dr.")
+    Abbreviation,
+}
+
+impl TokenKind {
+    /// Whether tokens of this kind should be spell-checked.
+    pub fn is_word(self) -> bool {
+        matches!(self, TokenKind::Word | TokenKind::Abbreviation)
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            TokenKind::Word => "word",
+            TokenKind::Number => "number",
+            TokenKind::Url => "url",
+            TokenKind::Email => "email",
+            TokenKind::Hashtag => "hashtag",
+            TokenKind::Mention => "mention",
+            TokenKind::Emoji => "emoji",
+            TokenKind::Punctuation => "punctuation",
+            TokenKind::Whitespace => "whitespace",
+            TokenKind::Abbreviation => "abbreviation",
+        }
+    }
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// A classified token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// byte offset of the token in the string
+    pub index: usize,
+    /// the token text
+    pub text: &'a str,
+    /// what the token is
+    pub kind: TokenKind,
+}
+
+/// External iterator for the classified tokens of a string.
+///
+/// Every byte of the string belongs to exactly one token.
+#[derive(Clone, Debug)]
+pub struct TokenIndices<'a> {
+    string: &'a str,
+    iter: Peekable<WordBoundIndices<'a>>,
+    abbreviations: Vec<SmolStr>,
+}
+
+impl<'a> TokenIndices<'a> {
+    /// Create new iterator over the classified tokens of `s`.
+    ///
+    /// A word followed by a full stop is an abbreviation only if it has full
+    /// stops inside it already, as "e.g." and "U.S.A." do.
+    pub fn new(s: &'a str) -> TokenIndices<'a> {
+        TokenIndices::new_with_abbreviations(s, vec![])
+    }
+
+    /// Create new iterator over the classified tokens of `s`, where any of
+    /// `abbreviations` followed by a full stop is also an abbreviation.
+    /// Abbreviations are matched without regard to case and without their
+    /// final full stop, as with
+    /// [`sentence::abbreviations`](super::sentence::abbreviations).
+    pub fn new_with_abbreviations(s: &'a str, abbreviations: Vec<SmolStr>) -> TokenIndices<'a> {
+        TokenIndices::from_word_bounds(s, WordBoundIndices::new(s), abbreviations)
+    }
+
+    /// Create new iterator over the classified tokens of `s`, putting them
+    /// together from the word boundaries `iter` finds in it.
+    pub(super) fn from_word_bounds(
+        s: &'a str,
+        iter: WordBoundIndices<'a>,
+        abbreviations: Vec<SmolStr>,
+    ) -> TokenIndices<'a> {
+        TokenIndices {
+            string: s,
+            iter: iter.peekable(),
+            abbreviations: abbreviations.iter().map(|a| lower_case(a)).collect(),
+        }
+    }
+
+    /// The end of the token starting at `index` if it is one of the kinds
+    /// made of several word-boundary pieces.
+    fn compound_at(&self, index: usize, piece: &str) -> Option<(usize, TokenKind)> {
+        let rest = &self.string[index..];
+        if self.string[..index]
+            .chars()
+            .next_back()
+            .is_some_and(is_alphanumeric)
+        {
+            return None;
+        }
+
+        if let Some(len) = url_len(rest) {
+            return Some((index + len, TokenKind::Url));
+        }
+        if let Some(len) = email_len(rest) {
+            return Some((index + len, TokenKind::Email));
+        }
+        for (sigil, kind) in [('#', TokenKind::Hashtag), ('@', TokenKind::Mention)] {
+            if let Some(name) = rest.strip_prefix(sigil) {
+                let len = name
+                    .find(|c: char| !(is_alphanumeric(c) || c == '_'))
+                    .unwrap_or(name.len());
+                if name[..len].chars().any(char::is_alphabetic) {
+                    return Some((index + 1 + len, kind));
+                }
+            }
+        }
+
+        let is_abbreviation = rest[piece.len()..].starts_with('.')
+            && piece.chars().any(char::is_alphabetic)
+            && (piece.contains('.') || self.abbreviations.contains(&lower_case(piece)));
+        is_abbreviation.then_some((index + piece.len() + 1, TokenKind::Abbreviation))
+    }
+}
+
+impl<'a> Iterator for TokenIndices<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let (index, piece) = self.iter.next()?;
+
+        let Some((mut end, kind)) = self.compound_at(index, piece) else {
+            return Some(Token {
+                index,
+                text: piece,
+                kind: classify(piece),
+            });
+        };
+
+        // Take in every piece the compound token overlaps.
+        end = end.max(index + piece.len());
+        while let Some(&(next, next_piece)) = self.iter.peek() {
+            if next >= end {
+                break;
+            }
+            end = end.max(next + next_piece.len());
+            self.iter.next();
+        }
+
+        Some(Token {
+            index,
+            text: &self.string[index..end],
+            kind,
+        })
+    }
+}
+
+/// Classify a single word-boundary token, as produced by
+/// [`Tokenize::word_bound_indices`](super::Tokenize::word_bound_indices).
+///
+/// Without the text around it, a token can only be a word, number, emoji,
+/// punctuation or whitespace.
+pub fn classify(token: &str) -> TokenKind {
+    if token.chars().all(char::is_whitespace) {
+        return TokenKind::Whitespace;
+    }
+    if token.chars().any(char::is_alphabetic) {
+        return TokenKind::Word;
+    }
+    if token.chars().any(|c| WB::of(c) == WB::Numeric) {
+        return TokenKind::Number;
+    }
+    if token.chars().any(is_emoji) {
+        return TokenKind::Emoji;
+    }
+    if token.chars().any(is_alphanumeric) {
+        return TokenKind::Word;
+    }
+    TokenKind::Punctuation
+}
+
+/// Characters that are emoji rather than symbols: pictographs, and the
+/// regional indicators flags are spelled with.
+fn is_emoji(c: char) -> bool {
+    is_extended_pictographic(c) || WB::of(c) == WB::RegionalIndicator
+}
+
+/// The length of the URL at the start of `s`, if one starts there.
+/// Punctuation at the end is not part of it, since a URL at the end of a
+/// sentence is followed by its full stop.
+///
+/// The scheme or `www.` must be followed by a host, and a host after `www.`
+/// by a dot of its own, so that "www." or "http://" alone is no URL.
+pub(super) fn url_len(s: &str) -> Option<usize> {
+    let lower = s.chars().take(8).collect::<String>().to_ascii_lowercase();
+    let scheme = ["http://", "https://", "ftp://", "www."]
+        .into_iter()
+        .find(|scheme| lower.starts_with(scheme))?;
+    let len = s
+        .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"'))
+        .unwrap_or(s.len());
+    let mut url = s[..len].trim_end_matches(['.', ',', ';', ':', '!', '?', ']', '\'']);
+    // A closing parenthesis belongs to the URL only if it opened one.
+    while url.ends_with(')') && url.matches(')').count() > url.matches('(').count() {
+        url = url[..url.len() - 1].trim_end_matches(['.', ',', ';', ':', '!', '?', ']', '\'']);
+    }
+    // Trimming the end may have cut into the scheme itself.
+    let host = url
+        .get(scheme.len()..)
+        .unwrap_or_default()
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    if !host.chars().any(is_alphanumeric) || (scheme == "www." && !host.contains('.')) {
+        return None;
+    }
+    Some(url.len())
+}
+
+/// The length of the e-mail address at the start of `s`, if one starts there.
+fn email_len(s: &str) -> Option<usize> {
+    let is_local = |c: char| is_alphanumeric(c) || matches!(c, '.' | '_' | '%' | '+' | '-');
+    let at = s.find(|c: char| !is_local(c))?;
+    if at == 0 || s.starts_with('.') || !s[at..].starts_with('@') {
+        return None;
+    }
+    let domain = &s[at + 1..];
+    let len = domain
+        .find(|c: char| !(is_alphanumeric(c) || matches!(c, '.' | '-')))
+        .unwrap_or(domain.len());
+    let domain = domain[..len].trim_end_matches(['.', '-']);
+    let tld = domain.rsplit('.').next().unwrap_or_default();
+    let valid = domain.contains('.')
+        && !domain.starts_with(['.', '-'])
+        && !domain.contains("..")
+        && tld.chars().count() >= 2
+        && tld.chars().all(char::is_alphabetic);
+    valid.then_some(at + 1 + domain.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(s: &str) -> Vec<(&str, TokenKind)> {
+        TokenIndices::new(s)
+            .filter(|t| t.kind != TokenKind::Whitespace)
+            .map(|t| (t.text, t.kind))
+            .collect()
+    }
+
+    #[test]
+    fn words_numbers_and_punctuation() {
+        use TokenKind::*;
+        assert_eq!(
+            tokens("Sámi 4G costs 1,000.50 kr!"),
+            [
+                ("Sámi", Word),
+                ("4G", Word),
+                ("costs", Word),
+                ("1,000.50", Number),
+                ("kr", Word),
+                ("!", Punctuation)
+            ]
+        );
+    }
+
+    #[test]
+    fn urls_and_emails() {
+        use TokenKind::*;
+        assert_eq!(
+            tokens("See https://divvun.no/a_b?x=1. Mail me@divvun.no, (www.example.com)"),
+            [
+                ("See", Word),
+                ("https://divvun.no/a_b?x=1", Url),
+                (".", Punctuation),
+                ("Mail", Word),
+                ("me@divvun.no", Email),
+                (",", Punctuation),
+                ("(", Punctuation),
+                ("www.example.com", Url),
+                (")", Punctuation)
+            ]
+        );
+    }
+
+    // A scheme or "www." without a host after it is no URL.
+    #[test]
+    fn urls_need_a_host() {
+        assert_eq!(url_len("http://divvun.no"), Some(16));
+        assert_eq!(url_len("www.divvun.no."), Some(13));
+        assert_eq!(url_len("http://"), None);
+        assert_eq!(url_len("https:///path"), None);
+        assert_eq!(url_len("ftp://."), None);
+        assert_eq!(url_len("www."), None);
+        assert_eq!(url_len("www.."), None);
+        assert_eq!(url_len("www.divvun"), None);
+        assert_eq!(url_len("www./divvun.no"), None);
+
+        use TokenKind::*;
+        assert_eq!(
+            tokens("www. http://"),
+            [
+                ("www", Word),
+                (".", Punctuation),
+                ("http", Word),
+                (":", Punctuation),
+                ("/", Punctuation),
+                ("/", Punctuation)
+            ]
+        );
+    }
+
+    #[test]
+    fn hashtags_mentions_and_emoji() {
+        use TokenKind::*;
+        assert_eq!(
+            tokens("#sámegiella @divvun_no #1 😄👍🏽"),
+            [
+                ("#sámegiella", Hashtag),
+                ("@divvun_no", Mention),
+                ("#", Punctuation),
+                ("1", Number),
+                ("😄", Emoji),
+                ("👍🏽", Emoji)
+            ]
+        );
+    }
+
+    #[test]
+    fn abbreviations() {
+        use TokenKind::*;
+        assert_eq!(
+            tokens("e.g. Dr. Smith."),
+            [
+                ("e.g.", Abbreviation),
+                ("Dr", Word),
+                (".", Punctuation),
+                ("Smith", Word),
+                (".", Punctuation)
+            ]
+        );
+        let with_list: Vec<_> =
+            TokenIndices::new_with_abbreviations("Dr. Smith.", vec!["dr".into()])
+                .map(|t| (t.text, t.kind))
+                .collect();
+        assert_eq!(
+            with_list,
+            [
+                ("Dr.", Abbreviation),
+                (" ", Whitespace),
+                ("Smith", Word),
+                (".", Punctuation)
+            ]
+        );
+    }
+
+    #[test]
+    fn tokens_cover_the_string() {
+        let text = "Mail: a.b@c.no — see #tag, https://x.org/(y) 😄.";
+        let mut end = 0;
+        for token in TokenIndices::new(text) {
+            assert_eq!(token.index, end);
+            assert_eq!(
+                &text[token.index..token.index + token.text.len()],
+                token.text
+            );
+            end += token.text.len();
+        }
+        assert_eq!(end, text.len());
+    }
+}
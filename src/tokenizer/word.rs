@@ -18,6 +18,7 @@
 use std::cmp;
 use std::iter::Filter;
 
+use super::extended_pictographic::is_extended_pictographic;
 use super::word_break::WordBreak as WB;
 
 /// An iterator over the substrings of a string which, after splitting the string on [word
@@ -71,6 +72,7 @@ pub struct WordBounds<'a> {
     cat: Option<WB>,
     catb: Option<WB>,
     alphabet: Option<Vec<char>>,
+    word_internal: Vec<char>,
 }
 
 /// External iterator for word boundaries and byte offsets.
@@ -99,6 +101,21 @@ impl<'a> WordBoundIndices<'a> {
         }
     }
 
+    /// Create new iterator for *word boundaries and their indices*, treating
+    /// the symbols in `alphabet` as letters and those in `word_internal` as
+    /// joining letters on both sides into one word.
+    #[inline]
+    pub fn new_with_word_chars(
+        s: &'a str,
+        alphabet: Vec<char>,
+        word_internal: Vec<char>,
+    ) -> WordBoundIndices<'a> {
+        WordBoundIndices {
+            start_offset: s.as_ptr() as usize,
+            iter: WordBounds::new_with_word_chars(s, alphabet, word_internal),
+        }
+    }
+
     #[inline]
     /// View the underlying data (the part yet to be iterated) as a slice of the original string.
     ///
@@ -154,6 +171,7 @@ enum WordBoundsState {
     ExtendNumLet,
     Regional(RegionalState),
     FormatExtend(FormatExtendType),
+    WSegSpace,
     Zwj,
     Emoji,
 }
@@ -203,24 +221,18 @@ impl<'a> Iterator for WordBounds<'a> {
         let mut cat = WB::Other;
         let mut savecat = WB::Other;
 
-        // Whether or not the previous category was ZWJ
-        // ZWJs get collapsed, so this handles precedence of WB3c over WB4
+        // Whether or not the previous category was ZWJ, or any of the
+        // categories WB4 ignores. These get collapsed, so this handles
+        // precedence of WB3c and WB3d over WB4
         let mut prev_zwj;
+        let mut prev_ignored;
         for (curr, ch) in self.string.char_indices() {
             idx = curr;
             prev_zwj = cat == WB::ZWJ;
+            prev_ignored = matches!(cat, WB::Extend | WB::Format | WB::ZWJ);
             // if there's a category cached, grab it
             cat = match self.cat {
-                None => match &self.alphabet {
-                    None => WB::of(ch),
-                    Some(alphabet) => {
-                        if alphabet.contains(&ch) {
-                            WB::ALetter
-                        } else {
-                            WB::of(ch)
-                        }
-                    }
-                },
+                None => self.category(ch),
                 _ => self.cat.take().unwrap(),
             };
             take_cat = true;
@@ -245,25 +257,24 @@ impl<'a> Iterator for WordBounds<'a> {
             // but you can still be in a Zwj state if you started with Zwj
             //
             // This means that Zwj + Extend will collapse into Zwj, which is wrong,
-            // since Extend has a boundary with following EBG/GAZ chars but ZWJ doesn't,
-            // and that rule (WB3c) has higher priority
-            //
-            // Additionally, Emoji_Base+ZWJ+(EBG/GAZ) will collapse into Emoji_Base+EBG/GAZ
-            // which won't have a boundary even though EB+ZWJ+GAZ should have a boundary.
+            // since Extend has a boundary with following Extended_Pictographic
+            // chars but ZWJ doesn't, and that rule (WB3c) has higher priority
             //
             // Thus, we separately keep track of whether or not the last character
             // was a ZWJ. This is an additional bit of state tracked outside of the
             // state enum; the state enum represents the last non-zwj state encountered.
             // When prev_zwj is true, for the purposes of WB3c, we are in the Zwj state,
             // however we are in the previous state for the purposes of all other rules.
-            if prev_zwj {
-                match cat {
-                    WB::GlueAfterZwj => continue,
-                    WB::EBaseGAZ => {
-                        state = Emoji;
+            if prev_zwj && is_extended_pictographic(ch) {
+                match state {
+                    // a few pictographs are ALetter and can complete rule WB7
+                    FormatExtend(RequireLetter) if cat == WB::ALetter => {}
+                    // the ZWJ belongs to the punctuation, rewind to it
+                    FormatExtend(RequireLetter | RequireHLetter | RequireNumeric) => break,
+                    _ => {
+                        state = if cat == WB::ALetter { Letter } else { Emoji };
                         continue;
                     }
-                    _ => (),
                 }
             }
             // Don't use `continue` in this match without updating `cat`
@@ -284,7 +295,7 @@ impl<'a> Iterator for WordBounds<'a> {
                     WB::RegionalIndicator => Regional(RegionalState::Half), // rule WB13c
                     WB::LF | WB::Newline => break,    // rule WB3a
                     WB::ZWJ => Zwj,                   // rule WB3c
-                    WB::EBase | WB::EBaseGAZ => Emoji, // rule WB14
+                    WB::WSegSpace => WSegSpace,       // rule WB3d
                     _ => {
                         if let Some(ncat) = self.get_next_cat(idx) {
                             // rule WB4
@@ -299,11 +310,18 @@ impl<'a> Iterator for WordBounds<'a> {
                 },
                 Zwj => {
                     // We already handle WB3c above. At this point,
-                    // the current category is not GAZ or EBG,
+                    // the current character is not Extended_Pictographic,
                     // or the previous character was not actually a ZWJ
                     take_curr = false;
                     break;
                 }
+                WSegSpace => match cat {
+                    WB::WSegSpace if !prev_ignored => WSegSpace, // rule WB3d
+                    _ => {
+                        take_curr = false;
+                        break;
+                    }
+                },
                 Letter | HLetter => match cat {
                     WB::ALetter => Letter,            // rule WB5
                     WB::HebrewLetter => HLetter,      // rule WB5
@@ -378,14 +396,12 @@ impl<'a> Iterator for WordBounds<'a> {
                 Regional(_) => {
                     unreachable!("RegionalState::Unknown should not occur on forward iteration")
                 }
-                Emoji => match cat {
-                    // rule WB14
-                    WB::EModifier => state,
-                    _ => {
-                        take_curr = false;
-                        break;
-                    }
-                },
+                Emoji => {
+                    // We already handle WB3c above. If we get here,
+                    // the emoji sequence is over
+                    take_curr = false;
+                    break;
+                }
                 FormatExtend(t) => match t {
                     // handle FormatExtends depending on what type
                     RequireNumeric if cat == WB::Numeric => Numeric, // rule WB11
@@ -453,16 +469,7 @@ impl<'a> DoubleEndedIterator for WordBounds<'a> {
 
             // if there's a category cached, grab it
             cat = match self.catb {
-                None => match &self.alphabet {
-                    None => WB::of(ch),
-                    Some(alphabet) => {
-                        if alphabet.contains(&ch) {
-                            WB::ALetter
-                        } else {
-                            WB::of(ch)
-                        }
-                    }
-                },
+                None => self.category(ch),
                 _ => self.catb.take().unwrap(),
             };
             take_cat = true;
@@ -473,6 +480,9 @@ impl<'a> DoubleEndedIterator for WordBounds<'a> {
             //     Hebrew Letter immediately before it.
             // (2) Format and Extend char handling takes some gymnastics.
 
+            // Whether the character is right before the previous one, with no
+            // Format or Extend chars between them, for rule WB3d
+            let mut adjacent = true;
             if cat == WB::Extend || cat == WB::Format || (cat == WB::ZWJ && state != Zwj) {
                 // WB3c has more priority so we should not
                 // fold in that case
@@ -493,25 +503,32 @@ impl<'a> DoubleEndedIterator for WordBounds<'a> {
                 state = savestate;
                 previdx = saveidx;
                 take_cat = false;
+                adjacent = false;
             }
 
             // Don't use `continue` in this match without updating `catb`
             state = match state {
                 Start | FormatExtend(AcceptAny) => match cat {
-                    WB::ALetter => Letter,            // rule WB5, WB7, WB10, WB13b
-                    WB::HebrewLetter => HLetter,      // rule WB5, WB7, WB7c, WB10, WB13b
-                    WB::Numeric => Numeric,           // rule WB8, WB9, WB11, WB13b
-                    WB::Katakana => Katakana,         // rule WB13, WB13b
+                    // rule WB3c
+                    _ if cat != WB::ALetter
+                        && is_extended_pictographic(ch)
+                        && self.get_prev_cat(idx) == Some(WB::ZWJ) =>
+                    {
+                        Zwj
+                    }
+                    WB::ALetter => Letter,       // rule WB5, WB7, WB10, WB13b
+                    WB::HebrewLetter => HLetter, // rule WB5, WB7, WB7c, WB10, WB13b
+                    WB::Numeric => Numeric,      // rule WB8, WB9, WB11, WB13b
+                    WB::Katakana => Katakana,    // rule WB13, WB13b
                     WB::ExtendNumLet => ExtendNumLet, // rule WB13a
                     WB::RegionalIndicator => Regional(RegionalState::Unknown), // rule WB13c
-                    WB::GlueAfterZwj | WB::EBaseGAZ => Zwj, // rule WB3c
+                    WB::WSegSpace => WSegSpace,  // rule WB3d
                     // rule WB4:
                     WB::Extend | WB::Format | WB::ZWJ => FormatExtend(AcceptAny),
                     WB::SingleQuote => {
                         saveidx = idx;
                         FormatExtend(AcceptQLetter) // rule WB7a
                     }
-                    WB::EModifier => Emoji, // rule WB14
                     WB::CR | WB::LF | WB::Newline => {
                         if state == Start {
                             if cat == WB::LF {
@@ -535,6 +552,13 @@ impl<'a> DoubleEndedIterator for WordBounds<'a> {
                         break;
                     }
                 },
+                WSegSpace => match cat {
+                    WB::WSegSpace if adjacent => WSegSpace, // rule WB3d
+                    _ => {
+                        take_curr = false;
+                        break;
+                    }
+                },
                 Letter | HLetter => match cat {
                     WB::ALetter => Letter,            // rule WB5
                     WB::HebrewLetter => HLetter,      // rule WB5
@@ -615,14 +639,7 @@ impl<'a> DoubleEndedIterator for WordBounds<'a> {
                         break;
                     }
                 },
-                Emoji => match cat {
-                    // rule WB14
-                    WB::EBase | WB::EBaseGAZ => Zwj,
-                    _ => {
-                        take_curr = false;
-                        break;
-                    }
-                },
+                Emoji => unreachable!("Emoji state should not occur on backward iteration"),
                 FormatExtend(t) => match t {
                     RequireNumeric if cat == WB::Numeric => Numeric, // rule WB12
                     RequireLetter if cat == WB::ALetter => Letter,   // rule WB6
@@ -655,6 +672,19 @@ impl<'a> DoubleEndedIterator for WordBounds<'a> {
             if take_cat { Some(cat) } else { None }
         };
 
+        // rule WB3c: the few pictographs that are also letters are handled by
+        // the letter rules above, which can leave them split from a ZWJ before
+        // them. Going forwards gets that right, so ask the forward rules.
+        if self.get_prev_cat(idx) == Some(WB::ZWJ)
+            && self.string[idx..]
+                .chars()
+                .next()
+                .is_some_and(is_extended_pictographic)
+        {
+            idx = self.last_forward_start();
+            self.catb = None;
+        }
+
         let retstr = &self.string[idx..];
         self.string = &self.string[..idx];
 
@@ -671,6 +701,7 @@ impl<'a> WordBounds<'a> {
             cat: None,
             catb: None,
             alphabet: None,
+            word_internal: vec![],
         }
     }
 
@@ -682,6 +713,25 @@ impl<'a> WordBounds<'a> {
             cat: None,
             catb: None,
             alphabet: Some(alphabet),
+            word_internal: vec![],
+        }
+    }
+
+    /// Create new iterator for *word boundries* with an alphabet, and with
+    /// the symbols in `word_internal` joining letters on both sides into one
+    /// word, as an apostrophe does.
+    #[inline]
+    pub fn new_with_word_chars(
+        s: &'a str,
+        alphabet: Vec<char>,
+        word_internal: Vec<char>,
+    ) -> WordBounds<'a> {
+        WordBounds {
+            string: s,
+            cat: None,
+            catb: None,
+            alphabet: Some(alphabet),
+            word_internal,
         }
     }
 
@@ -703,6 +753,35 @@ impl<'a> WordBounds<'a> {
         self.string
     }
 
+    /// Byte offset of the last word boundary, found by iterating forwards.
+    fn last_forward_start(&self) -> usize {
+        let forward = WordBounds {
+            string: self.string,
+            cat: None,
+            catb: None,
+            alphabet: self.alphabet.clone(),
+            word_internal: self.word_internal.clone(),
+        };
+        let mut start = 0;
+        let mut end = 0;
+        for word in forward {
+            start = end;
+            end += word.len();
+        }
+        start
+    }
+
+    /// The word break category of `ch`, after the alphabet and word-internal
+    /// symbols have had their say.
+    #[inline]
+    fn category(&self, ch: char) -> WB {
+        match &self.alphabet {
+            Some(alphabet) if alphabet.contains(&ch) => WB::ALetter,
+            _ if self.word_internal.contains(&ch) => WB::MidLetter,
+            _ => WB::of(ch),
+        }
+    }
+
     #[inline]
     fn get_next_cat(&self, idx: usize) -> Option<WB> {
         let nidx = idx + self.string[idx..].chars().next().unwrap().len_utf8();
@@ -734,11 +813,51 @@ mod tests {
         assert_eq!(
             WordBounds::new("The quick (\"brown\")  fox").collect::<Vec<&str>>(),
             &[
-                "The", " ", "quick", " ", "(", "\"", "brown", "\"", ")", " ", " ", "fox"
+                "The", " ", "quick", " ", "(", "\"", "brown", "\"", ")", "  ", "fox"
             ]
         );
     }
 
+    fn bounds_both_ways(s: &str) -> Vec<&str> {
+        let forward = WordBounds::new(s).collect::<Vec<&str>>();
+        let mut backward = WordBounds::new(s).rev().collect::<Vec<&str>>();
+        backward.reverse();
+        assert_eq!(forward, backward, "{s:?}");
+        forward
+    }
+
+    #[test]
+    fn test_emoji_sequences() {
+        // rule WB3c
+        assert_eq!(
+            bounds_both_ways("👨\u{200d}👩\u{200d}👧 hi"),
+            &["👨\u{200d}👩\u{200d}👧", " ", "hi"]
+        );
+        assert_eq!(bounds_both_ways("👍🏽!"), &["👍🏽", "!"]);
+        assert_eq!(bounds_both_ways("a\u{200d}😀b"), &["a\u{200d}😀", "b"]);
+        assert_eq!(bounds_both_ways("a.\u{200d}😀"), &["a", ".\u{200d}😀"]);
+        assert_eq!(bounds_both_ways("😀\u{200d}ℹb"), &["😀\u{200d}ℹb"]);
+        assert_eq!(bounds_both_ways("b.\u{200d}ℹ"), &["b.\u{200d}ℹ"]);
+        assert_eq!(bounds_both_ways(";.\u{200d}ℹ"), &[";", ".\u{200d}ℹ"]);
+        assert_eq!(
+            bounds_both_ways("😀\u{301}\u{200d}😀"),
+            &["😀\u{301}\u{200d}😀"]
+        );
+        assert_eq!(
+            bounds_both_ways("😀\u{200d}\u{301}😀"),
+            &["😀\u{200d}\u{301}", "😀"]
+        );
+    }
+
+    #[test]
+    fn test_whitespace_runs() {
+        // rule WB3d
+        assert_eq!(bounds_both_ways("a   b"), &["a", "   ", "b"]);
+        assert_eq!(bounds_both_ways(" \u{3000}\t "), &[" \u{3000}", "\t", " "]);
+        assert_eq!(bounds_both_ways(" \u{301} "), &[" \u{301}", " "]);
+        assert_eq!(bounds_both_ways(" \u{200d}😀"), &[" \u{200d}😀"]);
+    }
+
     #[test]
     fn test_words() {
         assert_eq!(
@@ -10,6 +10,9 @@
 
 //! Unicode `Word_Break` Character Property.
 //!
+//! The table is generated by `support/gen-break-tables.py` from the Unicode
+//! 16.0.0 character database.
+//!
 //! ## References
 //!
 //! * <https://www.unicode.org/reports/tr44/#Word_Break>
@@ -261,45 +264,16 @@ char_property! {
             human => "Extend Numeric/Letter",
         }
 
-        // Emoji
-
-        /// Emoji characters listed as `Emoji_Modifier_Base=Yes` in `emoji-data.txt`, which do not
-        /// occur after ZWJ in `emoji-zwj-sequences.txt`.
-        ///
-        /// See <https://www.unicode.org/reports/tr51/>.
-        EBase {
-            abbr => EB,
-            long => E_Base,
-            human => "Emoji Base",
-        }
-
-        /// Emoji characters listed as `Emoji_Modifer=Yes` in `emoji-data.txt`.
-        ///
-        /// See <https://www.unicode.org/reports/tr51/>.
-        EModifier {
-            abbr => EM,
-            long => E_Modifier,
-            human => "Emoji Modifier",
-        }
-
-        /// Emoji characters that do not break from a previous ZWJ in a defined emoji ZWJ sequence,
-        /// and are not listed as `Emoji_Modifier_Base=Yes` in `emoji-data.txt`.
-        ///
-        /// See <https://www.unicode.org/reports/tr51/>.
-        GlueAfterZwj {
-            abbr => GAZ,
-            long => Glue_After_Zwj,
-            human => "Glue After ZWJ",
-        }
-
-        /// Emoji characters listed as `Emoji_Modifer_Base=Yes` in `emoji_data.txt`, and also occur
-        /// after ZWJ in `emoji-zwj-sequences.txt`.
-        ///
-        /// See <https://www.unicode.org/reports/tr51/>.
-        EBaseGAZ {
-            abbr => EBG,
-            long => E_Base_GAZ,
-            human => "Emoji Base and Glue After ZWJ",
+        /// ```text
+        /// General_Category = Space_Separator (Zs), except
+        /// U+00A0 NO-BREAK SPACE (NBSP)
+        /// U+2007 FIGURE SPACE
+        /// U+202F NARROW NO-BREAK SPACE (NNBSP)
+        /// ```
+        WSegSpace {
+            abbr => WSegSpace,
+            long => WSegSpace,
+            human => "White Space",
         }
 
         /// All other characters
@@ -377,8 +351,8 @@ mod tests {
         assert_eq!(WB::of('\u{05D1}'), WB::HebrewLetter);
         assert_eq!(WB::of('\u{05FF}'), WB::Other);
 
-        // Arabic
-        assert_eq!(WB::of('\u{0600}'), WB::Format);
+        // Arabic, whose number signs are numbers since Unicode 15.1
+        assert_eq!(WB::of('\u{0600}'), WB::Numeric);
         assert_eq!(WB::of('\u{0627}'), WB::ALetter);
         assert_eq!(WB::of('\u{07BF}'), WB::Other);
 
@@ -386,10 +360,10 @@ mod tests {
         assert_eq!(WB::of('\u{07C0}'), WB::Numeric);
         assert_eq!(WB::of('\u{085F}'), WB::Other);
         assert_eq!(WB::of('\u{0860}'), WB::ALetter);
-        assert_eq!(WB::of('\u{0870}'), WB::Other);
-        assert_eq!(WB::of('\u{089F}'), WB::Other);
+        assert_eq!(WB::of('\u{0870}'), WB::ALetter);
+        assert_eq!(WB::of('\u{089F}'), WB::Extend);
         assert_eq!(WB::of('\u{08A0}'), WB::ALetter);
-        assert_eq!(WB::of('\u{089F}'), WB::Other);
+        assert_eq!(WB::of('\u{089F}'), WB::Extend);
         assert_eq!(WB::of('\u{08FF}'), WB::Extend);
 
         // Default ET
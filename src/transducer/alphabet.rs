@@ -1,6 +1,7 @@
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
+use unic_segment::Graphemes;
 
 use crate::transducer::Transducer;
 use crate::types::{OperationsMap, SymbolNumber};
@@ -50,6 +51,18 @@ impl TransducerAlphabet {
         self.operations.contains_key(&symbol)
     }
 
+    /// Whether `symbol` is a multicharacter symbol, such as a morphological
+    /// tag, rather than one character of a word form. Epsilon and flag
+    /// diacritics are neither.
+    pub fn is_multichar(&self, symbol: SymbolNumber) -> bool {
+        if symbol == SymbolNumber::ZERO || self.is_flag(symbol) {
+            return false;
+        }
+        self.key_table
+            .get(symbol.0 as usize)
+            .is_some_and(|key| Graphemes::new(key).nth(1).is_some())
+    }
+
     #[inline(always)]
     pub fn add_symbol(&mut self, string: &str) {
         self.string_to_symbol.insert(
@@ -0,0 +1,215 @@
+//! The AT&T tabular text format for transducers.
+//!
+//! This is the format `hfst-fst2txt` writes and `hfst-txt2fst` reads: one
+//! line per arc, with the source and target state, input and output symbol
+//! and optionally a weight, and one per final state, with the state and
+//! optionally its final weight. Fields are separated by tabs, as `write`
+//! writes them, or spaces:
+//!
+//! ```text
+//! 0 1 c c 0
+//! 1 2 a a 0
+//! 2 3 t t 0
+//! 3 4 @0@ +N 0
+//! 4 0
+//! ```
+//!
+//! The start state is the source state of the first line. Epsilon is written
+//! `@0@` or `@_EPSILON_SYMBOL_@`, a space `@_SPACE_@` and a tab `@_TAB_@`.
+//! Flag diacritics and wildcards are written as in an HFST alphabet, such as
+//! `@P.CASE.NOM@` or `@_IDENTITY_SYMBOL_@`.
+//!
+//! [`parse`] reads the text into a transducer held in memory, which works
+//! anywhere a transducer read from a file does, and [`write`] writes any
+//! transducer back out.
+use std::borrow::Cow;
+use std::io::Write;
+
+use hashbrown::HashMap;
+
+use super::builder::TransducerBuilder;
+use super::thfst::MemoryThfstTransducer;
+use super::{Transducer, TransducerError, graph};
+use crate::types::{SymbolNumber, Weight};
+
+/// Error reading AT&T text.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum AttError {
+    /// A line is neither an arc nor a final state.
+    #[error("line {line}: {detail}")]
+    Syntax {
+        /// the line, counting from 1
+        line: usize,
+        /// human-readable explanation
+        detail: Cow<'static, str>,
+    },
+
+    /// The transducer could not be built, such as for a malformed flag
+    /// diacritic.
+    #[error("failed to build transducer")]
+    Build(#[source] TransducerError),
+}
+
+/// Read the transducer written in `text`.
+pub fn parse(text: &str) -> Result<MemoryThfstTransducer, AttError> {
+    let mut builder = TransducerBuilder::new();
+    let mut states: HashMap<u32, usize> = HashMap::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let syntax = |detail: Cow<'static, str>| AttError::Syntax {
+            line: line_number,
+            detail,
+        };
+        let fields: Vec<&str> = line
+            .split([' ', '\t'])
+            .filter(|field| !field.is_empty())
+            .collect();
+        if fields.is_empty() {
+            continue;
+        }
+        if fields == ["--"] {
+            return Err(syntax("only one transducer can be read".into()));
+        }
+
+        let mut state = |field: &str| -> Result<usize, AttError> {
+            let number: u32 = field
+                .parse()
+                .map_err(|_| syntax(format!("'{field}' is not a state number").into()))?;
+            // The first state named is the start state, which the builder
+            // already has.
+            if states.is_empty() {
+                states.insert(number, 0);
+                return Ok(0);
+            }
+            Ok(*states.entry(number).or_insert_with(|| builder.add_state()))
+        };
+        let weight = |field: Option<&&str>| -> Result<Weight, AttError> {
+            match field {
+                Some(field) => field
+                    .parse()
+                    .map(Weight)
+                    .map_err(|_| syntax(format!("'{field}' is not a weight").into())),
+                None => Ok(Weight::ZERO),
+            }
+        };
+
+        match fields.len() {
+            1 | 2 => {
+                let from = state(fields[0])?;
+                let weight = weight(fields.get(1))?;
+                builder.set_final(from, weight);
+            }
+            4 | 5 => {
+                let from = state(fields[0])?;
+                let to = state(fields[1])?;
+                let weight = weight(fields.get(4))?;
+                let input = builder.symbol(&unescape(fields[2]));
+                let output = builder.symbol(&unescape(fields[3]));
+                builder.add_arc(from, to, input, output, weight);
+            }
+            n => {
+                return Err(syntax(
+                    format!(
+                        "expected 1 or 2 fields for a final state, or 4 or 5 for an arc, found {n}"
+                    )
+                    .into(),
+                ));
+            }
+        }
+    }
+
+    builder.build().map_err(AttError::Build)
+}
+
+/// Write `transducer` as AT&T text, the states reachable from its start
+/// state numbered from 0 breadth-first.
+pub fn write<T: Transducer, W: Write>(transducer: &T, writer: &mut W) -> std::io::Result<()> {
+    let states = graph::states(transducer);
+    let numbers: HashMap<_, _> = states.iter().enumerate().map(|(i, s)| (*s, i)).collect();
+    let key_table = transducer.alphabet().key_table();
+    let symbol = |sym: SymbolNumber| -> Cow<'_, str> {
+        if sym == SymbolNumber::ZERO {
+            return "@0@".into();
+        }
+        match key_table.get(sym.0 as usize).map(|key| key.as_str()) {
+            None | Some("") => "@0@".into(),
+            Some(" ") => "@_SPACE_@".into(),
+            Some("\t") => "@_TAB_@".into(),
+            Some(key) => key.into(),
+        }
+    };
+
+    for (number, &state) in states.iter().enumerate() {
+        for edge in graph::edges(transducer, state) {
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}\t{}",
+                number,
+                numbers[&edge.target],
+                symbol(edge.input),
+                symbol(edge.output),
+                edge.weight.0
+            )?;
+        }
+        if transducer.is_final(state) {
+            let weight = transducer.final_weight(state).unwrap_or(Weight::ZERO);
+            writeln!(writer, "{}\t{}", number, weight.0)?;
+        }
+    }
+    Ok(())
+}
+
+/// [`write`] to a string.
+pub fn to_string<T: Transducer>(transducer: &T) -> String {
+    let mut buf = Vec::new();
+    write(transducer, &mut buf).expect("writing to a Vec cannot fail");
+    String::from_utf8(buf).expect("symbols are UTF-8")
+}
+
+fn unescape(field: &str) -> Cow<'_, str> {
+    match field {
+        "@0@" | "@_EPSILON_SYMBOL_@" => "@_EPSILON_SYMBOL_@".into(),
+        "@_SPACE_@" => " ".into(),
+        "@_TAB_@" => "\t".into(),
+        field => field.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(text: &str) -> AttError {
+        match parse(text) {
+            Ok(_) => panic!("parsed {text:?}"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = error("0\t1\ta\n1\n");
+        assert!(matches!(err, AttError::Syntax { line: 1, .. }), "{err}");
+        let err = error("0\t1\ta\ta\n1\tx\n");
+        assert!(matches!(err, AttError::Syntax { line: 2, .. }), "{err}");
+        let err = error("0\t1\ta\ta\n1\n--\n0\n");
+        assert!(matches!(err, AttError::Syntax { line: 3, .. }), "{err}");
+        let err = error("0\t1\t@X.F.V@\t@X.F.V@\n1\n");
+        assert!(matches!(err, AttError::Build(_)), "{err}");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "0\t1\ta\tb\t0.5\n1\t2\t@_SPACE_@\t@0@\t0\n1\t1\n2\t0\n";
+        let transducer = parse(text).unwrap();
+        assert_eq!(to_string(&transducer), text);
+    }
+
+    #[test]
+    fn test_start_state_is_first_named() {
+        let transducer = parse("5 3 a a\n3\n").unwrap();
+        assert_eq!(to_string(&transducer), "0\t1\ta\ta\t0\n1\t0\n");
+    }
+}
@@ -0,0 +1,333 @@
+//! Building a transducer in memory from its states and arcs.
+//!
+//! The tables of the HFST optimised-lookup format, which THFST shares, find
+//! a state's arcs for a symbol by indexing: a state is an offset into the
+//! index table, and the entry at `offset + 1 + symbol` points to its arcs
+//! reading `symbol`, or belongs to some other state. [`TransducerBuilder`]
+//! packs the states' entries into one index table first-fit, so that no two
+//! states claim the same entry, and lays out their arcs behind it.
+//!
+//! The built transducer is held in memory and works anywhere one read from a
+//! file does. To keep it, write it out through [`ConvertFile`]:
+//!
+//! ```
+//! use divvun_fst::transducer::builder::TransducerBuilder;
+//! use divvun_fst::transducer::convert::ConvertFile;
+//! use divvun_fst::transducer::hfst::HfstTransducer;
+//! use divvun_fst::transducer::thfst::MmapThfstTransducer;
+//! use divvun_fst::types::Weight;
+//!
+//! let mut builder = TransducerBuilder::new();
+//! let mut state = 0;
+//! for c in ["c", "a", "t"] {
+//!     let sym = builder.symbol(c);
+//!     let next = builder.add_state();
+//!     builder.add_arc(state, next, sym, sym, Weight::ZERO);
+//!     state = next;
+//! }
+//! builder.set_final(state, Weight::ZERO);
+//! let transducer = builder.build().unwrap();
+//!
+//! # let dir = tempfile::tempdir().unwrap();
+//! # let path = &dir.path().join("lexicon");
+//! // lexicon.thfst/, a directory of tables
+//! MmapThfstTransducer::convert_file(&transducer, path).unwrap();
+//! // lexicon.hfst, for the HFST tools
+//! HfstTransducer::convert_file(&transducer, path).unwrap();
+//! ```
+//!
+//! [`ConvertFile`]: super::convert::ConvertFile
+use std::path::Path;
+
+use hashbrown::HashMap;
+use smol_str::SmolStr;
+
+use super::TransducerError;
+use super::alphabet::TransducerAlphabet;
+use super::hfst::alphabet::TransducerAlphabetParser;
+use super::thfst::MemoryThfstTransducer;
+use super::thfst::index_table::IndexTable;
+use super::thfst::transition_table::TransitionTable;
+use crate::constants::TARGET_TABLE;
+use crate::types::{FlagDiacriticOperator, SymbolNumber, Weight};
+
+const EPSILON: &str = "@_EPSILON_SYMBOL_@";
+
+/// An arc from one state of a [`TransducerBuilder`] to another.
+#[derive(Debug, Clone)]
+struct BuilderArc {
+    input: SymbolNumber,
+    output: SymbolNumber,
+    target: usize,
+    weight: Weight,
+}
+
+#[derive(Debug, Clone, Default)]
+struct BuilderState {
+    final_weight: Option<Weight>,
+    arcs: Vec<BuilderArc>,
+}
+
+/// A transducer under construction: its symbols, states and arcs.
+///
+/// States are numbered from 0, the start state, in the order they are added.
+/// The methods taking a state or symbol panic if it wasn't added to this
+/// builder.
+#[derive(Debug, Clone)]
+pub struct TransducerBuilder {
+    symbols: Vec<SmolStr>,
+    symbol_numbers: HashMap<SmolStr, SymbolNumber>,
+    states: Vec<BuilderState>,
+}
+
+impl Default for TransducerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransducerBuilder {
+    /// A builder with only epsilon, symbol 0, and a start state that isn't
+    /// final.
+    pub fn new() -> Self {
+        let mut builder = TransducerBuilder {
+            symbols: vec![],
+            symbol_numbers: HashMap::new(),
+            states: vec![BuilderState::default()],
+        };
+        builder.symbol(EPSILON);
+        builder
+    }
+
+    /// The number of `key`, added to the alphabet if it isn't there yet.
+    ///
+    /// Keys are written as in an HFST alphabet: a flag diacritic as
+    /// `@P.FEATURE.VALUE@`, a wildcard as `@_IDENTITY_SYMBOL_@` or
+    /// `@_UNKNOWN_SYMBOL_@`.
+    pub fn symbol(&mut self, key: &str) -> SymbolNumber {
+        if let Some(&sym) = self.symbol_numbers.get(key) {
+            return sym;
+        }
+        let sym = SymbolNumber(self.symbols.len() as u16);
+        self.symbols.push(key.into());
+        self.symbol_numbers.insert(key.into(), sym);
+        sym
+    }
+
+    /// The number of a flag diacritic, such as `@P.CASE.NOM@`, added to the
+    /// alphabet if it isn't there yet.
+    ///
+    /// Arcs reading it are taken only when the flag's operation succeeds; see
+    /// [`FlagDiacriticOperator`].
+    pub fn flag(
+        &mut self,
+        operator: FlagDiacriticOperator,
+        feature: &str,
+        value: &str,
+    ) -> SymbolNumber {
+        let key = if value.is_empty() {
+            format!("@{operator}.{feature}@")
+        } else {
+            format!("@{operator}.{feature}.{value}@")
+        };
+        self.symbol(&key)
+    }
+
+    /// The number of states added so far, counting the start state.
+    pub fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Add a state that isn't final, and return its number.
+    pub fn add_state(&mut self) -> usize {
+        self.states.push(BuilderState::default());
+        self.states.len() - 1
+    }
+
+    /// Add an arc from `from` to `to`.
+    pub fn add_arc(
+        &mut self,
+        from: usize,
+        to: usize,
+        input: SymbolNumber,
+        output: SymbolNumber,
+        weight: Weight,
+    ) {
+        assert!(to < self.states.len(), "no state {to}");
+        assert!(
+            (input.0 as usize) < self.symbols.len() && (output.0 as usize) < self.symbols.len(),
+            "no such symbol"
+        );
+        self.states[from].arcs.push(BuilderArc {
+            input,
+            output,
+            target: to,
+            weight,
+        });
+    }
+
+    /// Make `state` final, at `weight`.
+    pub fn set_final(&mut self, state: usize, weight: Weight) {
+        self.states[state].final_weight = Some(weight);
+    }
+
+    /// Pack the states and arcs into tables.
+    ///
+    /// Fails if there are more symbols or arcs than the tables can number, or
+    /// a flag diacritic is malformed.
+    pub fn build(&self) -> Result<MemoryThfstTransducer, TransducerError> {
+        let path = Path::new("<memory>");
+        let too_large = |detail: &'static str| TransducerError::CorruptTables {
+            path: path.to_path_buf(),
+            detail: detail.into(),
+        };
+        if self.symbols.len() >= SymbolNumber::MAX.0 as usize {
+            return Err(TransducerError::AlphabetMalformed {
+                path: path.to_path_buf(),
+                detail: "too many symbols".into(),
+            });
+        }
+        let alphabet = self.alphabet(path)?;
+
+        // An arc's slot is its input symbol's, but epsilons and flag
+        // diacritics share slot 0, where the search looks for arcs reading
+        // nothing.
+        let slot = |sym: SymbolNumber| {
+            if alphabet.is_flag(sym) {
+                0
+            } else {
+                sym.0 as usize
+            }
+        };
+
+        let arcs: Vec<Vec<&BuilderArc>> = self
+            .states
+            .iter()
+            .map(|state| {
+                let mut arcs: Vec<_> = state.arcs.iter().collect();
+                arcs.sort_by_key(|arc| slot(arc.input));
+                arcs
+            })
+            .collect();
+
+        // Place each state at the first offset where its finality entry and
+        // its slots are all free. The start state comes first, at 0.
+        let mut used: Vec<bool> = vec![];
+        let mut first_free = 0;
+        let mut offsets = Vec::with_capacity(self.states.len());
+        for state_arcs in &arcs {
+            let mut slots: Vec<usize> = state_arcs.iter().map(|arc| slot(arc.input)).collect();
+            slots.dedup();
+            while used.get(first_free).copied().unwrap_or(false) {
+                first_free += 1;
+            }
+            let mut offset = first_free;
+            while used.get(offset).copied().unwrap_or(false)
+                || slots
+                    .iter()
+                    .any(|s| used.get(offset + 1 + s).copied().unwrap_or(false))
+            {
+                offset += 1;
+            }
+            let end = offset + 1 + slots.last().map_or(0, |s| s + 1);
+            if used.len() < end {
+                used.resize(end, false);
+            }
+            used[offset] = true;
+            for s in &slots {
+                used[offset + 1 + s] = true;
+            }
+            offsets.push(offset);
+        }
+        if used.len() >= TARGET_TABLE.0 as usize {
+            return Err(too_large("index table too large"));
+        }
+
+        // Lay out each state's arcs in slot order, ended by a record that
+        // reads no symbol, and point its slots at them.
+        let mut index = vec![(u16::MAX, u32::MAX); used.len()];
+        let mut transitions = Vec::new();
+        for ((state, state_arcs), &offset) in self.states.iter().zip(&arcs).zip(&offsets) {
+            if let Some(weight) = state.final_weight {
+                index[offset].1 = weight.0.to_bits();
+            }
+            if state_arcs.is_empty() {
+                continue;
+            }
+            for arc in state_arcs {
+                let s = slot(arc.input);
+                let entry = &mut index[offset + 1 + s];
+                if entry.0 == u16::MAX {
+                    let position = u32::try_from(transitions.len())
+                        .ok()
+                        .filter(|p| *p < TARGET_TABLE.0)
+                        .ok_or_else(|| too_large("transition table too large"))?;
+                    *entry = (s as u16, TARGET_TABLE.0 + position);
+                }
+                transitions.push((
+                    arc.input.0,
+                    arc.output.0,
+                    offsets[arc.target] as u32,
+                    arc.weight,
+                ));
+            }
+            transitions.push((u16::MAX, u16::MAX, u32::MAX, Weight::ZERO));
+        }
+
+        let mut index_bytes = Vec::with_capacity(index.len() * 8);
+        for (input, target) in &index {
+            index_bytes.extend_from_slice(&input.to_le_bytes());
+            index_bytes.extend_from_slice(&0u16.to_le_bytes());
+            index_bytes.extend_from_slice(&target.to_le_bytes());
+        }
+        let mut transition_bytes = Vec::with_capacity(transitions.len() * 12);
+        for (input, output, target, weight) in &transitions {
+            transition_bytes.extend_from_slice(&input.to_le_bytes());
+            transition_bytes.extend_from_slice(&output.to_le_bytes());
+            transition_bytes.extend_from_slice(&target.to_le_bytes());
+            transition_bytes.extend_from_slice(&weight.0.to_bits().to_le_bytes());
+        }
+
+        let index_len = index_bytes.len();
+        let transition_len = transition_bytes.len();
+        Ok(MemoryThfstTransducer::from_parts(
+            IndexTable::new(index_bytes, index_len),
+            TransitionTable::new(transition_bytes, transition_len),
+            alphabet,
+        ))
+    }
+
+    /// The alphabet of the symbols, parsed as an HFST alphabet is.
+    fn alphabet(&self, path: &Path) -> Result<TransducerAlphabet, TransducerError> {
+        let mut buf = Vec::new();
+        for key in &self.symbols {
+            buf.extend_from_slice(key.as_bytes());
+            buf.push(0);
+        }
+        TransducerAlphabetParser::parse(&buf, SymbolNumber(self.symbols.len() as u16), path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_states_share_index_space() {
+        let mut builder = TransducerBuilder::new();
+        let mut state = 0;
+        for key in ["a", "b", "c", "d", "e", "f"] {
+            let sym = builder.symbol(key);
+            let next = builder.add_state();
+            builder.add_arc(state, next, sym, sym, Weight::ZERO);
+            state = next;
+        }
+        builder.set_final(state, Weight::ZERO);
+        let transducer = builder.build().unwrap();
+
+        // Unpacked, each of the 7 states would take an entry for its
+        // finality and one for each of the 7 symbols.
+        assert!(transducer.index_table.len().0 < 7 * 8);
+        assert_eq!(transducer.transition_table.len().0, 6 * 2);
+    }
+}
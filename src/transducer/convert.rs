@@ -6,6 +6,7 @@ use byteorder::{LittleEndian, WriteBytesExt};
 
 use super::hfst;
 use super::thfst;
+use super::{IndexTableTrait, TransitionTableTrait, graph};
 use crate::transducer::Transducer;
 use crate::types::{SymbolNumber, TransitionTableIndex};
 
@@ -19,40 +20,143 @@ pub trait ConvertFrom<T> {
 
 impl ConvertFile<hfst::HfstTransducer> for thfst::MmapThfstTransducer {
     fn convert_file(transducer: &hfst::HfstTransducer, path: &Path) -> Result<(), std::io::Error> {
-        let thfst_path = path.with_extension("thfst");
-        std::fs::create_dir_all(&thfst_path)?;
+        write_thfst(
+            &transducer.index_table,
+            &transducer.transition_table,
+            transducer,
+            path,
+        )
+    }
+}
 
-        let transition_path = thfst_path.join("transition");
-        let index_path = thfst_path.join("index");
-        let alphabet_path = thfst_path.join("alphabet");
+/// Write a THFST transducer, such as one built in memory, to a THFST
+/// directory.
+impl<I, T> ConvertFile<thfst::ThfstTransducer<I, T>> for thfst::MmapThfstTransducer
+where
+    I: IndexTableTrait,
+    T: TransitionTableTrait,
+{
+    fn convert_file(
+        transducer: &thfst::ThfstTransducer<I, T>,
+        path: &Path,
+    ) -> Result<(), std::io::Error> {
+        write_thfst(
+            &transducer.index_table,
+            &transducer.transition_table,
+            transducer,
+            path,
+        )
+    }
+}
 
-        let mut writer = BufWriter::new(File::create(transition_path)?);
-        thfst::transition_table::TransitionTable::<memmap2::Mmap>::convert_from(
+/// Write a THFST transducer, such as one built in memory, to an HFST
+/// optimised-lookup file.
+impl<I, T> ConvertFile<thfst::ThfstTransducer<I, T>> for hfst::HfstTransducer
+where
+    I: IndexTableTrait,
+    T: TransitionTableTrait,
+{
+    fn convert_file(
+        transducer: &thfst::ThfstTransducer<I, T>,
+        path: &Path,
+    ) -> Result<(), std::io::Error> {
+        let mut writer = BufWriter::new(File::create(path.with_extension("hfst"))?);
+        write_hfst_header(transducer, &mut writer)?;
+        hfst::index_table::MappedIndexTable::convert_from(&transducer.index_table, &mut writer)?;
+        hfst::transition_table::MappedTransitionTable::convert_from(
             &transducer.transition_table,
             &mut writer,
         )?;
+        writer.flush()
+    }
+}
 
-        let mut writer = BufWriter::new(File::create(index_path)?);
-        thfst::index_table::IndexTable::<memmap2::Mmap>::convert_from(
-            &transducer.index_table,
-            &mut writer,
-        )?;
+fn write_thfst<I, T, X>(
+    index_table: &I,
+    transition_table: &T,
+    transducer: &X,
+    path: &Path,
+) -> Result<(), std::io::Error>
+where
+    I: IndexTableTrait,
+    T: TransitionTableTrait,
+    X: Transducer,
+{
+    let thfst_path = path.with_extension("thfst");
+    std::fs::create_dir_all(&thfst_path)?;
 
-        let writer = BufWriter::new(File::create(alphabet_path)?);
-        serde_json::to_writer_pretty(writer, transducer.alphabet())?;
+    let transition_path = thfst_path.join("transition");
+    let index_path = thfst_path.join("index");
+    let alphabet_path = thfst_path.join("alphabet");
 
-        Ok(())
-    }
+    let mut writer = BufWriter::new(File::create(transition_path)?);
+    thfst::transition_table::TransitionTable::<memmap2::Mmap>::convert_from(
+        transition_table,
+        &mut writer,
+    )?;
+    writer.flush()?;
+
+    let mut writer = BufWriter::new(File::create(index_path)?);
+    thfst::index_table::IndexTable::<memmap2::Mmap>::convert_from(index_table, &mut writer)?;
+    writer.flush()?;
+
+    let writer = BufWriter::new(File::create(alphabet_path)?);
+    serde_json::to_writer_pretty(writer, transducer.alphabet())?;
+
+    Ok(())
 }
 
-impl ConvertFrom<hfst::index_table::MappedIndexTable>
-    for thfst::index_table::IndexTable<memmap2::Mmap>
+/// The HFST header and alphabet, which the tables follow.
+fn write_hfst_header<I, T, W>(
+    transducer: &thfst::ThfstTransducer<I, T>,
+    writer: &mut W,
+) -> Result<(), std::io::Error>
+where
+    I: IndexTableTrait,
+    T: TransitionTableTrait,
+    W: Write,
 {
-    fn convert_from<W: Write>(
-        table: &hfst::index_table::MappedIndexTable,
-        writer: &mut W,
-    ) -> Result<(), std::io::Error> {
-        for index in 0..table.size.0 {
+    const PROPERTIES: &[u8] = b"version\x003.3\x00type\x00HFST_OLW\x00";
+
+    let states = graph::states(transducer);
+    let transitions: usize = states
+        .iter()
+        .map(|state| graph::edges(transducer, *state).len())
+        .sum();
+    let key_table = transducer.alphabet().key_table();
+
+    writer.write_all(b"HFST\0")?;
+    writer.write_u16::<LittleEndian>(PROPERTIES.len() as u16)?;
+    writer.write_all(b"\0")?;
+    writer.write_all(PROPERTIES)?;
+
+    writer.write_u16::<LittleEndian>(transducer.alphabet().initial_symbol_count().0)?;
+    writer.write_u16::<LittleEndian>(key_table.len() as u16)?;
+    writer.write_u32::<LittleEndian>(transducer.index_table.len().0)?;
+    writer.write_u32::<LittleEndian>(transducer.transition_table.len().0)?;
+    writer.write_u32::<LittleEndian>(states.len() as u32)?;
+    writer.write_u32::<LittleEndian>(transitions as u32)?;
+    // Only the first property, weighted, is claimed.
+    writer.write_u32::<LittleEndian>(1)?;
+    for _ in 1..9 {
+        writer.write_u32::<LittleEndian>(0)?;
+    }
+
+    for (i, key) in key_table.iter().enumerate() {
+        if i == 0 {
+            writer.write_all(b"@_EPSILON_SYMBOL_@")?;
+        } else {
+            writer.write_all(key.as_bytes())?;
+        }
+        writer.write_all(b"\0")?;
+    }
+
+    Ok(())
+}
+
+impl<I: IndexTableTrait> ConvertFrom<I> for thfst::index_table::IndexTable<memmap2::Mmap> {
+    fn convert_from<W: Write>(table: &I, writer: &mut W) -> Result<(), std::io::Error> {
+        for index in 0..table.len().0 {
             let input_symbol = table
                 .input_symbol(TransitionTableIndex(index))
                 .unwrap_or(SymbolNumber::MAX);
@@ -69,14 +173,11 @@ impl ConvertFrom<hfst::index_table::MappedIndexTable>
     }
 }
 
-impl ConvertFrom<hfst::transition_table::MappedTransitionTable>
+impl<T: TransitionTableTrait> ConvertFrom<T>
     for thfst::transition_table::TransitionTable<memmap2::Mmap>
 {
-    fn convert_from<W: Write>(
-        table: &hfst::transition_table::MappedTransitionTable,
-        writer: &mut W,
-    ) -> Result<(), std::io::Error> {
-        for index in 0..table.size.0 {
+    fn convert_from<W: Write>(table: &T, writer: &mut W) -> Result<(), std::io::Error> {
+        for index in 0..table.len().0 {
             let index = TransitionTableIndex(index);
             let input_symbol = table.input_symbol(index).unwrap_or(SymbolNumber::MAX);
             let output_symbol = table.output_symbol(index).unwrap_or(SymbolNumber::MAX);
@@ -94,3 +195,28 @@ impl ConvertFrom<hfst::transition_table::MappedTransitionTable>
         Ok(())
     }
 }
+
+/// HFST index records are THFST's without the padding.
+impl<I: IndexTableTrait> ConvertFrom<I> for hfst::index_table::MappedIndexTable {
+    fn convert_from<W: Write>(table: &I, writer: &mut W) -> Result<(), std::io::Error> {
+        for index in 0..table.len().0 {
+            let input_symbol = table
+                .input_symbol(TransitionTableIndex(index))
+                .unwrap_or(SymbolNumber::MAX);
+            let targetish = table
+                .target(TransitionTableIndex(index))
+                .unwrap_or(TransitionTableIndex::MAX);
+
+            writer.write_u16::<LittleEndian>(input_symbol.0)?;
+            writer.write_u32::<LittleEndian>(targetish.0)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: TransitionTableTrait> ConvertFrom<T> for hfst::transition_table::MappedTransitionTable {
+    fn convert_from<W: Write>(table: &T, writer: &mut W) -> Result<(), std::io::Error> {
+        thfst::transition_table::TransitionTable::<memmap2::Mmap>::convert_from(table, writer)
+    }
+}
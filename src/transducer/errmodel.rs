@@ -0,0 +1,266 @@
+//! Error models built without the HFST tools.
+//!
+//! An error model maps what was typed to what may have been meant, each
+//! change weighted by how unlikely it is. [`EditDistance`] builds the
+//! simplest useful one: any few letters substituted, left out, typed in
+//! excess or swapped with the next, over the letters of a lexicon. Known
+//! mistakes, such as a spelling by sound, can be added as [`Replacement`]s.
+use smol_str::SmolStr;
+use unic_segment::Graphemes;
+
+use super::TransducerError;
+use super::builder::TransducerBuilder;
+use super::thfst::MemoryThfstTransducer;
+use crate::types::Weight;
+
+/// An edit distance error model: up to `max_edits` changes to a word, each
+/// at its weight.
+///
+/// The default weights, 5 per edit, make one edit cost about as much as a
+/// word being 150 times rarer in a lexicon weighted by frequency, as
+/// [`WordList::acceptor`] weights it.
+///
+/// [`WordList::acceptor`]: super::wordlist::WordList::acceptor
+#[derive(Debug, Clone)]
+pub struct EditDistance {
+    /// Maximum number of edits to a word.
+    pub max_edits: usize,
+    /// Weight of a letter typed in place of another.
+    pub substitution: Weight,
+    /// Weight of a letter left out, which the correction inserts.
+    pub insertion: Weight,
+    /// Weight of a letter typed in excess, which the correction deletes.
+    pub deletion: Weight,
+    /// Weight of two adjacent letters typed the wrong way round.
+    pub transposition: Weight,
+}
+
+impl Default for EditDistance {
+    fn default() -> Self {
+        Self {
+            max_edits: 2,
+            substitution: Weight(5.0),
+            insertion: Weight(5.0),
+            deletion: Weight(5.0),
+            transposition: Weight(5.0),
+        }
+    }
+}
+
+/// A known mistake: `from`, as typed, corrected to `to`, at `weight`. It
+/// counts as one edit. Both are read a grapheme cluster at a time, as the
+/// speller reads its input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replacement {
+    /// What was typed.
+    pub from: SmolStr,
+    /// What was meant.
+    pub to: SmolStr,
+    /// The weight of the correction.
+    pub weight: Weight,
+}
+
+impl EditDistance {
+    /// Build the error model over `letters`, such as a
+    /// [`WordList::alphabet`]. Letters outside them are not corrected.
+    ///
+    /// [`WordList::alphabet`]: super::wordlist::WordList::alphabet
+    pub fn error_model<S: AsRef<str>>(
+        &self,
+        letters: &[S],
+    ) -> Result<MemoryThfstTransducer, TransducerError> {
+        self.error_model_with(letters, letters, &[])
+    }
+
+    /// Build the error model passing `letters` through unchanged, editing
+    /// only those among `edited`, and making the `replacements` too.
+    pub fn error_model_with<S: AsRef<str>, E: AsRef<str>>(
+        &self,
+        letters: &[S],
+        edited: &[E],
+        replacements: &[Replacement],
+    ) -> Result<MemoryThfstTransducer, TransducerError> {
+        let mut builder = TransducerBuilder::new();
+        let epsilon = builder.symbol("@_EPSILON_SYMBOL_@");
+        let mut symbols = |mut letters: Vec<&str>| {
+            letters.retain(|letter| !letter.is_empty());
+            letters.sort_unstable();
+            letters.dedup();
+            letters
+                .into_iter()
+                .map(|letter| builder.symbol(letter))
+                .collect::<Vec<_>>()
+        };
+        let letters = symbols(letters.iter().map(AsRef::as_ref).collect());
+        let edited = symbols(edited.iter().map(AsRef::as_ref).collect());
+        let replacements: Vec<_> = replacements
+            .iter()
+            .map(|r| {
+                let from: Vec<_> = Graphemes::new(&r.from)
+                    .map(|letter| builder.symbol(letter))
+                    .collect();
+                let to: Vec<_> = Graphemes::new(&r.to)
+                    .map(|letter| builder.symbol(letter))
+                    .collect();
+                (from, to, r.weight)
+            })
+            .filter(|(from, to, _)| from != to)
+            .collect();
+
+        // State k has had k edits, and every one is final.
+        let levels: Vec<usize> = (0..=self.max_edits)
+            .map(|k| if k == 0 { 0 } else { builder.add_state() })
+            .collect();
+        for (k, &level) in levels.iter().enumerate() {
+            builder.set_final(level, Weight::ZERO);
+            for &x in &letters {
+                builder.add_arc(level, level, x, x, Weight::ZERO);
+            }
+            let Some(&next) = levels.get(k + 1) else {
+                continue;
+            };
+
+            for &x in &edited {
+                for &y in edited.iter().filter(|y| **y != x) {
+                    builder.add_arc(level, next, x, y, self.substitution);
+                }
+                builder.add_arc(level, next, epsilon, x, self.insertion);
+                builder.add_arc(level, next, x, epsilon, self.deletion);
+
+                // x then y, corrected to y then x: x is held back until y
+                // has been written.
+                let held = builder.add_state();
+                let written = builder.add_state();
+                builder.add_arc(level, held, x, epsilon, self.transposition);
+                for &y in edited.iter().filter(|y| **y != x) {
+                    builder.add_arc(held, written, y, y, Weight::ZERO);
+                }
+                builder.add_arc(written, next, epsilon, x, Weight::ZERO);
+            }
+
+            // Letter for letter as far as both go, then the rest of the
+            // longer side against nothing.
+            for (from, to, weight) in &replacements {
+                let len = from.len().max(to.len());
+                let mut state = level;
+                for i in 0..len {
+                    let target = if i + 1 == len {
+                        next
+                    } else {
+                        builder.add_state()
+                    };
+                    let input = from.get(i).copied().unwrap_or(epsilon);
+                    let output = to.get(i).copied().unwrap_or(epsilon);
+                    let weight = if i == 0 { *weight } else { Weight::ZERO };
+                    builder.add_arc(state, target, input, output, weight);
+                    state = target;
+                }
+            }
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transducer::lookup::lookup;
+
+    fn corrections(model: &MemoryThfstTransducer, input: &str) -> Vec<(String, f32)> {
+        let mut found: Vec<_> = lookup(model, input)
+            .into_iter()
+            .map(|(output, weight)| (output.to_string(), weight.0))
+            .collect();
+        found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        found.dedup();
+        found
+    }
+
+    #[test]
+    fn test_single_edits() {
+        let model = EditDistance {
+            max_edits: 1,
+            ..EditDistance::default()
+        }
+        .error_model(&["a", "b"])
+        .unwrap();
+        let found = corrections(&model, "ab");
+        let expected = [
+            ("a", 5.0),
+            ("aa", 5.0),
+            ("aab", 5.0),
+            ("ab", 0.0),
+            ("aba", 5.0),
+            ("abb", 5.0),
+            ("b", 5.0),
+            ("ba", 5.0),
+            ("bab", 5.0),
+            ("bb", 5.0),
+        ];
+        assert_eq!(
+            found,
+            expected.map(|(s, w)| (s.to_string(), w)),
+            "{found:?}"
+        );
+    }
+
+    #[test]
+    fn test_replacements() {
+        let model = EditDistance {
+            max_edits: 1,
+            ..EditDistance::default()
+        }
+        .error_model_with(
+            &["f", "o", "n", "e", "p", "h"],
+            &["e"],
+            &[Replacement {
+                from: "f".into(),
+                to: "ph".into(),
+                weight: Weight(1.0),
+            }],
+        )
+        .unwrap();
+        let found = corrections(&model, "fon");
+        assert_eq!(
+            found,
+            [
+                ("efon".to_string(), 5.0),
+                ("feon".to_string(), 5.0),
+                ("foen".to_string(), 5.0),
+                ("fon".to_string(), 0.0),
+                ("fone".to_string(), 5.0),
+                ("phon".to_string(), 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replacements_of_combining_marks() {
+        let model = EditDistance {
+            max_edits: 1,
+            ..EditDistance::default()
+        }
+        .error_model_with(
+            &["a", "a\u{301}"],
+            &[] as &[&str],
+            &[Replacement {
+                from: "a\u{301}".into(),
+                to: "a".into(),
+                weight: Weight(1.0),
+            }],
+        )
+        .unwrap();
+        assert_eq!(
+            corrections(&model, "a\u{301}"),
+            [("a".to_string(), 1.0), ("a\u{301}".to_string(), 0.0)]
+        );
+    }
+
+    #[test]
+    fn test_edits_are_limited() {
+        let model = EditDistance::default().error_model(&["a", "b"]).unwrap();
+        let found = corrections(&model, "a");
+        assert!(found.contains(&("bb".to_string(), 10.0)));
+        assert!(!found.iter().any(|(s, _)| s.len() > 3));
+    }
+}
@@ -0,0 +1,271 @@
+//! A transducer's states and arcs, read through the [`Transducer`] trait.
+//!
+//! The trait answers what the search asks, the arcs of a state reading a
+//! given symbol, so listing every arc of a state means asking for each
+//! symbol in turn. Epsilon and flag diacritic arcs are found together, as the
+//! search finds them.
+//!
+//! An [`Automaton`] holds the states and arcs so found, numbered from 0, for
+//! the code rewriting a transducer, which then packs it into tables again.
+use std::collections::VecDeque;
+
+use hashbrown::HashMap;
+
+use super::builder::TransducerBuilder;
+use super::thfst::MemoryThfstTransducer;
+use super::{Transducer, TransducerError};
+use crate::constants::TARGET_TABLE;
+use crate::types::{SymbolNumber, TransitionTableIndex, Weight};
+
+/// An arc of a transducer.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Edge {
+    pub(crate) input: SymbolNumber,
+    pub(crate) output: SymbolNumber,
+    pub(crate) target: TransitionTableIndex,
+    pub(crate) weight: Weight,
+}
+
+/// The arcs leaving `state`: those reading nothing first, then by input
+/// symbol.
+pub(crate) fn edges<T: Transducer>(transducer: &T, state: TransitionTableIndex) -> Vec<Edge> {
+    let alphabet = transducer.alphabet();
+    let mut edges = Vec::new();
+    let mut push = |pos: TransitionTableIndex, input: SymbolNumber| {
+        let trans = if input == SymbolNumber::ZERO || alphabet.is_flag(input) {
+            transducer.take_epsilons_and_flags(pos)
+        } else {
+            transducer.take_non_epsilons(pos, input)
+        };
+        let Some(trans) = trans else {
+            return false;
+        };
+        if let Some(target) = trans.target() {
+            edges.push(Edge {
+                input,
+                output: trans.symbol().unwrap_or(SymbolNumber::ZERO),
+                target,
+                weight: trans.weight().unwrap_or(Weight::ZERO),
+            });
+        }
+        true
+    };
+
+    // A state kept in the transition table alone has its arcs right after
+    // it, up to the next record reading no symbol.
+    if state >= TARGET_TABLE {
+        let mut pos = state - TARGET_TABLE + TransitionTableIndex::ONE;
+        while let Some(input) = transducer.transition_input_symbol(pos) {
+            if !push(pos, input) {
+                break;
+            }
+            pos = pos.incr();
+        }
+        return edges;
+    }
+
+    if transducer.has_epsilons_or_flags(state.incr())
+        && let Some(mut pos) = transducer.next(state, SymbolNumber::ZERO)
+    {
+        while let Some(input) = transducer.transition_input_symbol(pos) {
+            // The state's arcs reading a symbol may follow.
+            if (input != SymbolNumber::ZERO && !alphabet.is_flag(input)) || !push(pos, input) {
+                break;
+            }
+            pos = pos.incr();
+        }
+    }
+
+    for sym in (1..alphabet.initial_symbol_count().0).map(SymbolNumber) {
+        if alphabet.is_flag(sym) || !transducer.has_transitions(state.incr(), Some(sym)) {
+            continue;
+        }
+        let Some(mut pos) = transducer.next(state, sym) else {
+            continue;
+        };
+        while push(pos, sym) {
+            pos = pos.incr();
+        }
+    }
+    edges
+}
+
+/// The states reachable from the start state, numbered from 0 in the order
+/// they are first reached, breadth-first.
+pub(crate) fn states<T: Transducer>(transducer: &T) -> Vec<TransitionTableIndex> {
+    let start = TransitionTableIndex(0);
+    let mut numbers = HashMap::new();
+    let mut states = vec![start];
+    let mut queue = VecDeque::from([start]);
+    numbers.insert(start, 0);
+    while let Some(state) = queue.pop_front() {
+        for edge in edges(transducer, state) {
+            if !numbers.contains_key(&edge.target) {
+                numbers.insert(edge.target, states.len());
+                states.push(edge.target);
+                queue.push_back(edge.target);
+            }
+        }
+    }
+    states
+}
+
+/// An arc of an [`Automaton`], to the state numbered `target`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Arc {
+    pub(crate) input: SymbolNumber,
+    pub(crate) output: SymbolNumber,
+    pub(crate) target: usize,
+    pub(crate) weight: f32,
+}
+
+impl Arc {
+    /// Whether taking the arc reads and writes nothing.
+    pub(crate) fn is_free(&self) -> bool {
+        self.input == SymbolNumber::ZERO && self.output == SymbolNumber::ZERO
+    }
+}
+
+/// A state of an [`Automaton`], final if it has a weight.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct State {
+    pub(crate) final_weight: Option<f32>,
+    pub(crate) arcs: Vec<Arc>,
+}
+
+/// A transducer as a list of states, 0 the start state, over `symbols`, the
+/// keys of its alphabet, for rewriting before it is packed into tables again.
+#[derive(Debug, Clone)]
+pub(crate) struct Automaton {
+    pub(crate) symbols: Vec<String>,
+    pub(crate) states: Vec<State>,
+}
+
+impl Automaton {
+    /// The states reachable from the start state of `transducer`, numbered
+    /// as [`states`] numbers them.
+    pub(crate) fn read<T: Transducer>(transducer: &T) -> Automaton {
+        let key_table = transducer.alphabet().key_table();
+        // Symbols with no key are written as epsilon, as `att::write` does.
+        let symbol = |sym: SymbolNumber| match key_table.get(sym.0 as usize) {
+            Some(key) if !key.is_empty() => sym,
+            _ => SymbolNumber::ZERO,
+        };
+
+        let numbers = states(transducer);
+        let index: HashMap<_, _> = numbers.iter().enumerate().map(|(i, s)| (*s, i)).collect();
+        let states = numbers
+            .iter()
+            .map(|&state| State {
+                final_weight: transducer
+                    .is_final(state)
+                    .then(|| transducer.final_weight(state).unwrap_or(Weight::ZERO).0),
+                arcs: edges(transducer, state)
+                    .into_iter()
+                    .map(|edge| Arc {
+                        input: symbol(edge.input),
+                        output: symbol(edge.output),
+                        target: index[&edge.target],
+                        weight: edge.weight.0,
+                    })
+                    .collect(),
+            })
+            .collect();
+        Automaton {
+            symbols: key_table.iter().map(|key| key.to_string()).collect(),
+            states,
+        }
+    }
+
+    pub(crate) fn arc_count(&self) -> usize {
+        self.states.iter().map(|state| state.arcs.len()).sum()
+    }
+
+    /// The automaton without the states no final state can be reached from,
+    /// nor those left unreachable once they are gone.
+    pub(crate) fn trim(&self) -> Automaton {
+        let mut incoming = vec![vec![]; self.states.len()];
+        for (n, state) in self.states.iter().enumerate() {
+            for arc in &state.arcs {
+                incoming[arc.target].push(n);
+            }
+        }
+        let mut useful = vec![false; self.states.len()];
+        let mut stack: Vec<usize> = (0..self.states.len())
+            .filter(|n| self.states[*n].final_weight.is_some())
+            .collect();
+        while let Some(n) = stack.pop() {
+            if !std::mem::replace(&mut useful[n], true) {
+                stack.extend(incoming[n].iter().filter(|m| !useful[**m]));
+            }
+        }
+
+        // Renumber from the start state, which stays even if it is useless.
+        let mut numbers = vec![None; self.states.len()];
+        numbers[0] = Some(0);
+        let mut order = vec![0];
+        let mut i = 0;
+        while let Some(&n) = order.get(i) {
+            for arc in &self.states[n].arcs {
+                if useful[arc.target] && numbers[arc.target].is_none() {
+                    numbers[arc.target] = Some(order.len());
+                    order.push(arc.target);
+                }
+            }
+            i += 1;
+        }
+        let states = order
+            .iter()
+            .map(|&n| State {
+                final_weight: self.states[n].final_weight,
+                arcs: self.states[n]
+                    .arcs
+                    .iter()
+                    .filter_map(|arc| {
+                        Some(Arc {
+                            target: numbers[arc.target]?,
+                            ..*arc
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+        Automaton {
+            symbols: self.symbols.clone(),
+            states,
+        }
+    }
+
+    /// Pack the automaton into tables.
+    pub(crate) fn build(&self) -> Result<MemoryThfstTransducer, TransducerError> {
+        let mut builder = TransducerBuilder::new();
+        // Every symbol is kept, used or not, since the wildcards match only
+        // the symbols the transducer doesn't know.
+        let numbers: Vec<SymbolNumber> = self
+            .symbols
+            .iter()
+            .enumerate()
+            .map(|(sym, key)| {
+                if sym == 0 || key.is_empty() {
+                    SymbolNumber::ZERO
+                } else {
+                    builder.symbol(key)
+                }
+            })
+            .collect();
+        for _ in 1..self.states.len() {
+            builder.add_state();
+        }
+        for (n, state) in self.states.iter().enumerate() {
+            if let Some(weight) = state.final_weight {
+                builder.set_final(n, Weight(weight));
+            }
+            for arc in &state.arcs {
+                let input = numbers[arc.input.0 as usize];
+                let output = numbers[arc.output.0 as usize];
+                builder.add_arc(n, arc.target, input, output, Weight(arc.weight));
+            }
+        }
+        builder.build()
+    }
+}
@@ -122,3 +122,21 @@ impl MappedIndexTable {
         self.input_symbol(i) == None && self.target(i) != None
     }
 }
+
+impl crate::transducer::IndexTableTrait for MappedIndexTable {
+    fn len(&self) -> TransitionTableIndex {
+        self.size
+    }
+
+    fn input_symbol(&self, i: TransitionTableIndex) -> Option<SymbolNumber> {
+        self.input_symbol(i)
+    }
+
+    fn target(&self, i: TransitionTableIndex) -> Option<TransitionTableIndex> {
+        self.target(i)
+    }
+
+    fn final_weight(&self, i: TransitionTableIndex) -> Option<Weight> {
+        self.final_weight(i)
+    }
+}
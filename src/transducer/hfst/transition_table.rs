@@ -135,3 +135,25 @@ impl MappedTransitionTable {
         SymbolTransition::new(self.target(i), self.output_symbol(i), self.weight(i))
     }
 }
+
+impl crate::transducer::TransitionTableTrait for MappedTransitionTable {
+    fn len(&self) -> TransitionTableIndex {
+        self.size
+    }
+
+    fn input_symbol(&self, i: TransitionTableIndex) -> Option<SymbolNumber> {
+        self.input_symbol(i)
+    }
+
+    fn output_symbol(&self, i: TransitionTableIndex) -> Option<SymbolNumber> {
+        self.output_symbol(i)
+    }
+
+    fn target(&self, i: TransitionTableIndex) -> Option<TransitionTableIndex> {
+        self.target(i)
+    }
+
+    fn weight(&self, i: TransitionTableIndex) -> Option<Weight> {
+        self.weight(i)
+    }
+}
@@ -0,0 +1,346 @@
+//! Reading Hunspell `.aff` files: flags, affix rules and suggestion tables.
+use std::borrow::Cow;
+
+use hashbrown::{HashMap, HashSet};
+use smol_str::SmolStr;
+
+use super::HunspellError;
+
+/// A flag, as written in the file: a character, two characters or a number.
+pub(crate) type Flag = SmolStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FlagType {
+    Char,
+    Long,
+    Num,
+    Utf8,
+}
+
+/// One element of an affix condition.
+#[derive(Debug, Clone, PartialEq)]
+enum Pattern {
+    Any,
+    Char(char),
+    Set { negated: bool, chars: Vec<char> },
+}
+
+impl Pattern {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Pattern::Any => true,
+            Pattern::Char(p) => *p == c,
+            Pattern::Set { negated, chars } => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+/// The letters an affix requires next to it, such as `[^aeiou]y`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Condition(Vec<Pattern>);
+
+impl Condition {
+    fn parse(text: &str) -> Result<Condition, Cow<'static, str>> {
+        let mut patterns = vec![];
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            patterns.push(match c {
+                '.' => Pattern::Any,
+                '[' => {
+                    let mut set: Vec<char> = vec![];
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some(c) => set.push(c),
+                            None => return Err(format!("unclosed '[' in '{text}'").into()),
+                        }
+                    }
+                    let negated = set.first() == Some(&'^');
+                    if negated {
+                        set.remove(0);
+                    }
+                    Pattern::Set {
+                        negated,
+                        chars: set,
+                    }
+                }
+                c => Pattern::Char(c),
+            });
+        }
+        Ok(Condition(patterns))
+    }
+
+    fn matches_start(&self, word: &str) -> bool {
+        let mut chars = word.chars();
+        self.0
+            .iter()
+            .all(|p| chars.next().is_some_and(|c| p.matches(c)))
+    }
+
+    fn matches_end(&self, word: &str) -> bool {
+        let mut chars = word.chars().rev();
+        self.0
+            .iter()
+            .rev()
+            .all(|p| chars.next().is_some_and(|c| p.matches(c)))
+    }
+}
+
+/// A prefix or suffix rule: strip `strip` from the word and add `add`, if
+/// the word meets `condition`.
+#[derive(Debug, Clone)]
+pub(crate) struct Affix {
+    strip: SmolStr,
+    add: SmolStr,
+    pub(crate) continuation: Vec<Flag>,
+    condition: Condition,
+}
+
+impl Affix {
+    pub(crate) fn apply_prefix(&self, word: &str) -> Option<String> {
+        let rest = word.strip_prefix(self.strip.as_str())?;
+        self.condition
+            .matches_start(word)
+            .then(|| format!("{}{rest}", self.add))
+    }
+
+    pub(crate) fn apply_suffix(&self, word: &str) -> Option<String> {
+        let rest = word.strip_suffix(self.strip.as_str())?;
+        self.condition
+            .matches_end(word)
+            .then(|| format!("{rest}{}", self.add))
+    }
+}
+
+/// The rules of one affix flag.
+#[derive(Debug, Clone)]
+pub(crate) struct AffixGroup {
+    pub(crate) cross_product: bool,
+    pub(crate) rules: Vec<Affix>,
+}
+
+/// What the speller needs of an `.aff` file.
+#[derive(Debug, Clone)]
+pub(crate) struct Aff {
+    pub(crate) language: Option<SmolStr>,
+    flag_type: FlagType,
+    aliases: Vec<Vec<Flag>>,
+    pub(crate) prefixes: HashMap<Flag, AffixGroup>,
+    pub(crate) suffixes: HashMap<Flag, AffixGroup>,
+    pub(crate) need_affix: Option<Flag>,
+    pub(crate) forbidden: Option<Flag>,
+    pub(crate) only_in_compound: Option<Flag>,
+    pub(crate) replacements: Vec<(SmolStr, SmolStr)>,
+    pub(crate) related: Vec<Vec<SmolStr>>,
+    pub(crate) keyboard: Vec<SmolStr>,
+    pub(crate) try_letters: SmolStr,
+}
+
+/// Hunspell's keyboard when an `.aff` file gives none.
+const DEFAULT_KEY: &str = "qwertyuiop|asdfghjkl|zxcvbnm";
+
+impl Aff {
+    pub(crate) fn parse(text: &str) -> Result<Aff, HunspellError> {
+        let mut aff = Aff {
+            language: None,
+            flag_type: FlagType::Char,
+            aliases: vec![],
+            prefixes: HashMap::new(),
+            suffixes: HashMap::new(),
+            need_affix: None,
+            forbidden: None,
+            only_in_compound: None,
+            replacements: vec![],
+            related: vec![],
+            keyboard: DEFAULT_KEY.split('|').map(SmolStr::from).collect(),
+            try_letters: SmolStr::default(),
+        };
+        // Tables start with a line giving their length, which we don't need.
+        let mut tables: HashSet<&str> = HashSet::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let error = |detail: Cow<'static, str>| HunspellError::Aff {
+                line: i + 1,
+                detail,
+            };
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let Some(&keyword) = fields.first() else {
+                continue;
+            };
+            if keyword.starts_with('#') {
+                continue;
+            }
+            let arg = |n: usize| {
+                fields
+                    .get(n)
+                    .copied()
+                    .ok_or_else(|| error(format!("{keyword} needs {n} fields").into()))
+            };
+
+            match keyword {
+                "FLAG" => {
+                    aff.flag_type = match arg(1)? {
+                        "long" => FlagType::Long,
+                        "num" => FlagType::Num,
+                        "UTF-8" => FlagType::Utf8,
+                        other => return Err(error(format!("unknown flag type '{other}'").into())),
+                    }
+                }
+                "LANG" => aff.language = Some(arg(1)?.into()),
+                "NEEDAFFIX" | "PSEUDOROOT" => aff.need_affix = Some(arg(1)?.into()),
+                "FORBIDDENWORD" => aff.forbidden = Some(arg(1)?.into()),
+                "ONLYINCOMPOUND" => aff.only_in_compound = Some(arg(1)?.into()),
+                "TRY" => aff.try_letters = arg(1)?.into(),
+                "KEY" => aff.keyboard = arg(1)?.split('|').map(SmolStr::from).collect(),
+                "AF" | "REP" | "MAP" if tables.insert(keyword) => {}
+                "AF" => {
+                    let flags = aff.parse_plain_flags(arg(1)?).map_err(error)?;
+                    aff.aliases.push(flags);
+                }
+                "REP" => {
+                    let from = arg(1)?.replace('_', " ");
+                    let to = arg(2)?.replace('_', " ");
+                    // Anchors are dropped: the replacement is made anywhere.
+                    let unanchor = |s: &str| -> SmolStr {
+                        s.trim_start_matches('^').trim_end_matches('$').into()
+                    };
+                    aff.replacements.push((unanchor(&from), unanchor(&to)));
+                }
+                "MAP" => aff.related.push(parse_map(arg(1)?)),
+                "PFX" | "SFX" => {
+                    let prefix = keyword == "PFX";
+                    let flag: Flag = arg(1)?.into();
+                    // The first line of a flag's rules says whether they
+                    // combine with the other kind.
+                    if !aff.affixes(prefix).contains_key(&flag) {
+                        let group = AffixGroup {
+                            cross_product: arg(2)? == "Y",
+                            rules: vec![],
+                        };
+                        aff.affixes(prefix).insert(flag, group);
+                        continue;
+                    }
+                    let strip = match arg(2)? {
+                        "0" => "",
+                        strip => strip,
+                    };
+                    let (add, continuation) = arg(3)?.split_once('/').unwrap_or((arg(3)?, ""));
+                    let add = if add == "0" { "" } else { add };
+                    let condition = fields.get(4).copied().unwrap_or(".");
+                    let affix = Affix {
+                        strip: strip.into(),
+                        add: add.into(),
+                        continuation: aff.parse_flags(continuation).map_err(error)?,
+                        condition: Condition::parse(condition).map_err(error)?,
+                    };
+                    if let Some(group) = aff.affixes(prefix).get_mut(&flag) {
+                        group.rules.push(affix);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(aff)
+    }
+
+    fn affixes(&mut self, prefix: bool) -> &mut HashMap<Flag, AffixGroup> {
+        if prefix {
+            &mut self.prefixes
+        } else {
+            &mut self.suffixes
+        }
+    }
+
+    /// The flags written in `text`, or those of the alias it numbers.
+    pub(crate) fn parse_flags(&self, text: &str) -> Result<Vec<Flag>, Cow<'static, str>> {
+        if text.is_empty() {
+            return Ok(vec![]);
+        }
+        if !self.aliases.is_empty() {
+            return text
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| self.aliases.get(n.wrapping_sub(1)))
+                .cloned()
+                .ok_or_else(|| format!("no flag alias '{text}'").into());
+        }
+        self.parse_plain_flags(text)
+    }
+
+    /// The flags written in `text`, ignoring aliases.
+    fn parse_plain_flags(&self, text: &str) -> Result<Vec<Flag>, Cow<'static, str>> {
+        Ok(match self.flag_type {
+            FlagType::Char | FlagType::Utf8 => text
+                .chars()
+                .map(|c| Flag::from(c.encode_utf8(&mut [0; 4])))
+                .collect(),
+            FlagType::Long => {
+                let chars: Vec<char> = text.chars().collect();
+                if !chars.len().is_multiple_of(2) {
+                    return Err(format!("odd number of characters in long flags '{text}'").into());
+                }
+                chars
+                    .chunks(2)
+                    .map(|pair| pair.iter().collect::<String>().into())
+                    .collect()
+            }
+            FlagType::Num => text
+                .split(',')
+                .map(|n| {
+                    n.parse::<u16>()
+                        .map(|n| Flag::from(n.to_string()))
+                        .map_err(|_| Cow::from(format!("'{n}' is not a numeric flag")))
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// The related letters of a `MAP` line, such as `aáà` or `(ss)ß`.
+fn parse_map(text: &str) -> Vec<SmolStr> {
+    let mut related = vec![];
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '(' {
+            related.push(chars.by_ref().take_while(|c| *c != ')').collect());
+        } else {
+            related.push(c.encode_utf8(&mut [0; 4]).into());
+        }
+    }
+    related
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conditions() {
+        let condition = Condition::parse("[^aeiou]y").unwrap();
+        assert!(condition.matches_end("fly"));
+        assert!(!condition.matches_end("play"));
+        assert!(!condition.matches_end("y"));
+        let condition = Condition::parse("re.").unwrap();
+        assert!(condition.matches_start("red"));
+        assert!(!condition.matches_start("re"));
+        assert!(Condition::parse("[ab").is_err());
+    }
+
+    #[test]
+    fn test_flags() {
+        let mut aff = Aff::parse("FLAG long\n").unwrap();
+        assert_eq!(aff.parse_flags("AaBb").unwrap(), ["Aa", "Bb"]);
+        assert!(aff.parse_flags("AaB").is_err());
+        aff.flag_type = FlagType::Num;
+        assert_eq!(aff.parse_flags("1,20").unwrap(), ["1", "20"]);
+        let aff = Aff::parse("AF 2\nAF AB\nAF C\n").unwrap();
+        assert_eq!(aff.parse_flags("2").unwrap(), ["C"]);
+        assert!(aff.parse_flags("3").is_err());
+    }
+
+    #[test]
+    fn test_map() {
+        assert_eq!(parse_map("aáà"), ["a", "á", "à"]);
+        assert_eq!(parse_map("(ss)ß"), ["ss", "ß"]);
+    }
+}
@@ -0,0 +1,380 @@
+//! Importing Hunspell dictionaries.
+//!
+//! A Hunspell dictionary is a pair of files: the `.dic` lists stems, each
+//! with the flags of the affixes it takes, and the `.aff` defines those
+//! affixes and how to suggest corrections. [`Dictionary`] reads the pair and
+//! compiles it into the two transducers of a speller:
+//!
+//! - [`Dictionary::words`] expands every stem by its prefixes and suffixes,
+//!   combining them where both allow it and following suffixes' continuation
+//!   flags one level deep, into a [`WordList`], whose acceptor weights a
+//!   word by how many ways it can be formed.
+//! - [`Dictionary::error_model`] turns `REP` replacements, `MAP` related
+//!   letters and `KEY` keyboard neighbours into known mistakes, and edits
+//!   over the `TRY` letters into an [`EditDistance`] model.
+//!
+//! Compounding is not imported, and `REP` anchors are ignored, the
+//! replacement being made anywhere in a word. Words flagged
+//! `FORBIDDENWORD` or `ONLYINCOMPOUND` are left out, as are the bare stems
+//! of those flagged `NEEDAFFIX`.
+mod aff;
+
+use std::borrow::Cow;
+
+use hashbrown::HashMap;
+use smol_str::SmolStr;
+use unic_segment::Graphemes;
+
+use self::aff::{Aff, Affix, AffixGroup, Flag};
+use super::TransducerError;
+use super::errmodel::{EditDistance, Replacement};
+use super::thfst::MemoryThfstTransducer;
+use super::wordlist::WordList;
+use crate::archive::meta::{
+    SpellerMetadata, SpellerMetadataAcceptor, SpellerMetadataErrmodel, SpellerMetadataInfo,
+};
+use crate::types::Weight;
+
+/// Error reading a Hunspell dictionary.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum HunspellError {
+    /// A line of the `.aff` file is malformed.
+    #[error(".aff line {line}: {detail}")]
+    Aff {
+        /// the line, counting from 1
+        line: usize,
+        /// human-readable explanation
+        detail: Cow<'static, str>,
+    },
+
+    /// A line of the `.dic` file is malformed.
+    #[error(".dic line {line}: {detail}")]
+    Dic {
+        /// the line, counting from 1
+        line: usize,
+        /// human-readable explanation
+        detail: Cow<'static, str>,
+    },
+
+    /// The files are in an encoding other than UTF-8 or ISO 8859-1, or not
+    /// in the one the `.aff` file names.
+    #[error("cannot read the dictionary as {encoding}")]
+    Encoding {
+        /// the encoding named by `SET`
+        encoding: SmolStr,
+    },
+
+    /// A transducer could not be built.
+    #[error("failed to build transducer")]
+    Build(#[source] TransducerError),
+}
+
+/// Weights of the corrections in [`Dictionary::error_model`], on top of
+/// those of [`EditDistance`]. A `REP`, `MAP` or `KEY` correction counts as
+/// one edit.
+#[derive(Debug, Clone)]
+pub struct ErrorModelConfig {
+    /// Edits over the `TRY` letters, or all the words' letters if there are
+    /// none.
+    pub edits: EditDistance,
+    /// Weight of a `REP` replacement.
+    pub replacement: Weight,
+    /// Weight of a letter typed for one related to it by `MAP`.
+    pub related: Weight,
+    /// Weight of a letter typed for its neighbour on the `KEY` keyboard.
+    pub keyboard: Weight,
+}
+
+impl Default for ErrorModelConfig {
+    fn default() -> Self {
+        Self {
+            edits: EditDistance::default(),
+            replacement: Weight(2.0),
+            related: Weight(2.0),
+            keyboard: Weight(3.0),
+        }
+    }
+}
+
+/// A Hunspell dictionary: its affix file and stems.
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    aff: Aff,
+    stems: Vec<(SmolStr, Vec<Flag>)>,
+}
+
+impl Dictionary {
+    /// Read a dictionary from the text of its `.dic` and `.aff` files.
+    pub fn parse(dic: &str, aff: &str) -> Result<Dictionary, HunspellError> {
+        let aff = Aff::parse(aff.trim_start_matches('\u{feff}'))?;
+        let mut stems = vec![];
+        let mut lines = dic.trim_start_matches('\u{feff}').lines().enumerate();
+
+        // The first line is the number of stems.
+        if let Some((_, first)) = lines.next()
+            && first.trim().parse::<usize>().is_err()
+        {
+            return Err(HunspellError::Dic {
+                line: 1,
+                detail: format!("'{first}' is not the number of stems").into(),
+            });
+        }
+        for (i, line) in lines {
+            // Anything after the word and its flags is morphology.
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = split_entry(entry);
+            let flags = aff
+                .parse_flags(&flags)
+                .map_err(|detail| HunspellError::Dic {
+                    line: i + 1,
+                    detail,
+                })?;
+            stems.push((word.into(), flags));
+        }
+        Ok(Dictionary { aff, stems })
+    }
+
+    /// Read a dictionary from its `.dic` and `.aff` files as stored, in the
+    /// encoding the `.aff` file names with `SET`.
+    pub fn from_bytes(dic: &[u8], aff: &[u8]) -> Result<Dictionary, HunspellError> {
+        let encoding = aff
+            .split(|b| *b == b'\n')
+            .filter_map(|line| line.strip_prefix(b"SET"))
+            .map(|rest| String::from_utf8_lossy(rest).trim().to_string())
+            .next()
+            .unwrap_or_else(|| "ISO8859-1".into());
+        let decode = |bytes: &[u8]| -> Result<String, HunspellError> {
+            match encoding.to_ascii_uppercase().replace('-', "").as_str() {
+                "UTF8" => String::from_utf8(bytes.to_vec()).map_err(|_| HunspellError::Encoding {
+                    encoding: encoding.as_str().into(),
+                }),
+                "ISO88591" => Ok(bytes.iter().map(|b| char::from(*b)).collect()),
+                _ => Err(HunspellError::Encoding {
+                    encoding: encoding.as_str().into(),
+                }),
+            }
+        };
+        Dictionary::parse(&decode(dic)?, &decode(aff)?)
+    }
+
+    /// The language the `.aff` file names with `LANG`, if any.
+    pub fn language(&self) -> Option<&str> {
+        self.aff.language.as_deref()
+    }
+
+    /// Every word the dictionary accepts, counted once for each way it is
+    /// formed.
+    pub fn words(&self) -> WordList {
+        let mut words = WordList::new();
+        for (stem, flags) in &self.stems {
+            let has = |flag: &Option<Flag>| flag.as_ref().is_some_and(|f| flags.contains(f));
+            if has(&self.aff.forbidden) || has(&self.aff.only_in_compound) {
+                continue;
+            }
+            let mut forms = vec![];
+            if !has(&self.aff.need_affix) {
+                forms.push(stem.to_string());
+            }
+
+            // Suffixed forms, and those that may also take a prefix.
+            let mut crossable = vec![];
+            for (suffix, cross_product) in affixes(&self.aff.suffixes, flags) {
+                let Some(form) = suffix.apply_suffix(stem) else {
+                    continue;
+                };
+                for (next, _) in affixes(&self.aff.suffixes, &suffix.continuation) {
+                    forms.extend(next.apply_suffix(&form));
+                }
+                if self.needs_affix(&suffix.continuation) {
+                    continue;
+                }
+                if cross_product {
+                    crossable.push(form.clone());
+                }
+                forms.push(form);
+            }
+
+            for (prefix, cross_product) in affixes(&self.aff.prefixes, flags) {
+                if !self.needs_affix(&prefix.continuation) {
+                    forms.extend(prefix.apply_prefix(stem));
+                }
+                if cross_product {
+                    for form in &crossable {
+                        forms.extend(prefix.apply_prefix(form));
+                    }
+                }
+            }
+
+            for form in forms.iter().filter(|form| !form.is_empty()) {
+                words.insert(form, 1);
+            }
+        }
+        words
+    }
+
+    /// Build the error model of the dictionary's suggestion tables. The
+    /// letters of `words`, as [`words`] gives them, pass through it
+    /// unchanged.
+    ///
+    /// [`words`]: Dictionary::words
+    pub fn error_model(
+        &self,
+        words: &WordList,
+        config: &ErrorModelConfig,
+    ) -> Result<MemoryThfstTransducer, HunspellError> {
+        let mut replacements = vec![];
+        let mut replace = |from: &str, to: &str, weight: Weight| {
+            replacements.push(Replacement {
+                from: from.into(),
+                to: to.into(),
+                weight,
+            });
+        };
+        for (from, to) in &self.aff.replacements {
+            replace(from, to, config.replacement);
+        }
+        for related in &self.aff.related {
+            for from in related {
+                for to in related.iter().filter(|to| *to != from) {
+                    replace(from, to, config.related);
+                }
+            }
+        }
+        for row in &self.aff.keyboard {
+            let keys: Vec<&str> = Graphemes::new(row).collect();
+            for pair in keys.windows(2) {
+                replace(pair[0], pair[1], config.keyboard);
+                replace(pair[1], pair[0], config.keyboard);
+            }
+        }
+
+        // Letters are grapheme clusters, as in `words`.
+        let mut letters = words.alphabet();
+        let tried: Vec<SmolStr> = Graphemes::new(&self.aff.try_letters)
+            .map(SmolStr::from)
+            .collect();
+        let edited = if tried.is_empty() {
+            letters.clone()
+        } else {
+            tried.clone()
+        };
+        letters.extend(tried);
+        config
+            .edits
+            .error_model_with(&letters, &edited, &replacements)
+            .map_err(HunspellError::Build)
+    }
+
+    /// Metadata for a speller of the dictionary in `locale`, its acceptor
+    /// and error model named as a `.bhfst` archive expects them.
+    pub fn metadata(&self, locale: &str) -> SpellerMetadata {
+        SpellerMetadata::new(
+            SpellerMetadataInfo::new(
+                locale,
+                format!("{locale} speller imported from Hunspell"),
+                format!("A speller for {locale} compiled from a Hunspell dictionary."),
+                "",
+            ),
+            SpellerMetadataAcceptor::new(
+                "acceptor.default.thfst",
+                format!("{locale} Hunspell dictionary"),
+                "The dictionary's stems expanded by its affix rules.",
+            ),
+            SpellerMetadataErrmodel::new(
+                "errmodel.default.thfst",
+                "Hunspell suggestion tables",
+                "The dictionary's REP, MAP, KEY and TRY tables as an error model.",
+            ),
+        )
+    }
+
+    fn needs_affix(&self, flags: &[Flag]) -> bool {
+        self.aff
+            .need_affix
+            .as_ref()
+            .is_some_and(|flag| flags.contains(flag))
+    }
+}
+
+/// The rules of the affixes among `flags`, each with whether it combines
+/// with the other kind.
+fn affixes<'a>(
+    groups: &'a HashMap<Flag, AffixGroup>,
+    flags: &'a [Flag],
+) -> impl Iterator<Item = (&'a Affix, bool)> + 'a {
+    flags
+        .iter()
+        .filter_map(|flag| groups.get(flag))
+        .flat_map(|group| group.rules.iter().map(|rule| (rule, group.cross_product)))
+}
+
+/// Split a `.dic` entry at the first `/` not escaped as `\/`.
+fn split_entry(entry: &str) -> (String, String) {
+    let mut word = String::new();
+    let mut chars = entry.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.as_str().starts_with('/') => {
+                word.push('/');
+                chars.next();
+            }
+            '/' => return (word, chars.collect()),
+            c => word.push(c),
+        }
+    }
+    (word, String::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_entry() {
+        assert_eq!(split_entry("cat/AB"), ("cat".into(), "AB".into()));
+        assert_eq!(split_entry("cat"), ("cat".into(), "".into()));
+        assert_eq!(split_entry("and\\/or/A"), ("and/or".into(), "A".into()));
+    }
+
+    #[test]
+    fn test_encodings() {
+        let aff = b"SET ISO8859-1\nSFX A Y 1\nSFX A 0 s .\n";
+        let dic = b"1\ncaf\xe9/A\n";
+        let words = Dictionary::from_bytes(dic, aff).unwrap().words();
+        assert!(words.weight("cafés").is_some());
+
+        let err = Dictionary::from_bytes(dic, b"SET KOI8-R\n").unwrap_err();
+        assert!(matches!(err, HunspellError::Encoding { .. }), "{err}");
+        let err = Dictionary::from_bytes(dic, b"SET UTF-8\n").unwrap_err();
+        assert!(matches!(err, HunspellError::Encoding { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_try_letters_are_graphemes() {
+        use crate::transducer::lookup::lookup;
+
+        let aff = "SET UTF-8\nTRY e\u{301}e\n";
+        let dic = "1\ncafe\u{301}\n";
+        let dictionary = Dictionary::from_bytes(dic.as_bytes(), aff.as_bytes()).unwrap();
+        let words = dictionary.words();
+        let model = dictionary
+            .error_model(&words, &ErrorModelConfig::default())
+            .unwrap();
+        let found: Vec<_> = lookup(&model, "cafe")
+            .into_iter()
+            .map(|(output, _)| output)
+            .collect();
+        assert!(
+            found.iter().any(|output| output == "cafe\u{301}"),
+            "{found:?}"
+        );
+        // The mark is no letter to try on its own, in place of the "e".
+        assert!(
+            !found.iter().any(|output| output == "caf\u{301}"),
+            "{found:?}"
+        );
+    }
+}
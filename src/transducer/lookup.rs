@@ -0,0 +1,343 @@
+//! Looking up what a transducer writes for a string on its input side.
+//!
+//! The speller reads its input through two transducers at once, and the
+//! generator reads a prefix; this is the plain case of one transducer and a
+//! whole string, as `hfst-lookup` does it, for analysers, transliterators,
+//! normalisers or any other transducer. Paths are searched best-first, so the
+//! outputs come out cheapest first.
+//!
+//! Epsilon arcs are followed without reading input, and flag diacritics are
+//! checked along the way as the speller checks them: a path breaking a flag
+//! constraint is dropped, and neither epsilons nor flags are written to the
+//! output.
+use std::collections::BinaryHeap;
+
+use hashbrown::HashSet;
+use lifeguard::{Pool, Recycled};
+use smol_str::SmolStr;
+use unic_segment::Graphemes;
+
+use super::Transducer;
+use super::symbol_transition::SymbolTransition;
+use super::tree_node::TreeNode;
+use crate::types::{FlagDiacriticOperation, InputIndex, SymbolNumber, ValueNumber, Weight};
+
+/// Recycled search nodes kept around between pops.
+const NODE_POOL_SIZE: usize = 64;
+
+/// The most arcs a path may follow in a row without reading input. Bounds
+/// epsilon cycles, which would otherwise write outputs without end.
+const MAX_FREE_MOVES: u16 = 64;
+
+/// Configuration for [`lookup_with_config`].
+#[derive(Debug, Clone)]
+pub struct LookupConfig {
+    /// Maximum number of outputs to return: the cheapest this many. `None` =
+    /// unbounded.
+    pub max_results: Option<usize>,
+    /// Maximum path weight; paths exceeding this are pruned. `None` =
+    /// unbounded.
+    pub max_weight: Option<Weight>,
+    /// Hard cap on the number of paths extended. Guarantees termination;
+    /// when it cuts the search short, the outputs found so far are returned,
+    /// still cheapest first. `None` = unbounded.
+    pub max_iterations: Option<u64>,
+}
+
+impl Default for LookupConfig {
+    fn default() -> Self {
+        Self {
+            max_results: None,
+            max_weight: None,
+            max_iterations: Some(100_000),
+        }
+    }
+}
+
+/// The outputs `transducer` writes for `input`, each once, with the weight
+/// of its cheapest path, cheapest first.
+///
+/// A character of `input` outside the transducer's alphabet is read by an
+/// `@_IDENTITY_@` or `@_UNKNOWN_@` arc, and an identity arc writes it back.
+/// Without such arcs, an input with such a character has no outputs.
+pub fn lookup<T: Transducer>(transducer: &T, input: &str) -> Vec<(SmolStr, Weight)> {
+    lookup_with_config(transducer, input, &LookupConfig::default())
+}
+
+/// [`lookup`] with config options.
+pub fn lookup_with_config<T: Transducer>(
+    transducer: &T,
+    input: &str,
+    config: &LookupConfig,
+) -> Vec<(SmolStr, Weight)> {
+    let Some((input, foreign)) = tokenise(transducer, input) else {
+        return vec![];
+    };
+
+    let pool = Pool::with_size_and_max(NODE_POOL_SIZE, NODE_POOL_SIZE);
+    let flag_size = transducer.alphabet().state_size().0 as usize;
+    let mut search = Lookup {
+        transducer,
+        input: &input,
+        max_weight: config.max_weight,
+        pool: &pool,
+        queue: BinaryHeap::new(),
+        sequence: 0,
+    };
+    search.push(
+        TreeNode::empty(&pool, vec![ValueNumber::ZERO; flag_size]),
+        0,
+        false,
+    );
+
+    let key_table = transducer.alphabet().key_table();
+    let mut seen = HashSet::new();
+    let mut results = vec![];
+    let mut iterations = 0u64;
+    while let Some(Queued {
+        weight,
+        complete,
+        node,
+        free_moves,
+        ..
+    }) = search.queue.pop()
+    {
+        if config.max_results.is_some_and(|max| results.len() >= max) {
+            break;
+        }
+
+        // Nothing still queued is cheaper, so this output's weight is final.
+        if complete {
+            let output: SmolStr = node
+                .string
+                .iter()
+                .map(|s| match key_table.get(s.0 as usize) {
+                    Some(key) => &**key,
+                    None => &*foreign[s.0 as usize - key_table.len()],
+                })
+                .collect();
+            if seen.insert(output.clone()) {
+                results.push((output, weight));
+            }
+            continue;
+        }
+
+        iterations += 1;
+        if config.max_iterations.is_some_and(|max| iterations > max) {
+            break;
+        }
+
+        search.expand(&node, free_moves);
+
+        // Queue a path that has read all the input to a final state again,
+        // as an output, at its weight with the final weight added.
+        let state = node.lexicon_state;
+        if node.input_state.0 as usize == input.len() && transducer.is_final(state) {
+            let final_weight = transducer.final_weight(state).unwrap_or(Weight::ZERO);
+            let mut node = node;
+            node.weight = node.weight + final_weight;
+            search.push(node, free_moves, true);
+        }
+    }
+
+    results
+}
+
+/// Map `input` to symbol numbers in the transducer's alphabet, and the
+/// graphemes that have none.
+///
+/// A grapheme outside the alphabet gets a number past its end, which is
+/// its index in the graphemes returned plus the alphabet's length. Returns
+/// `None` if there is such a grapheme and the transducer has no wildcards
+/// to read it with.
+pub(crate) fn tokenise<T: Transducer>(
+    transducer: &T,
+    input: &str,
+) -> Option<(Vec<SymbolNumber>, Vec<SmolStr>)> {
+    let alphabet = transducer.alphabet();
+    let has_wildcards = alphabet.identity().is_some() || alphabet.unknown().is_some();
+    let mut syms = Vec::new();
+    let mut foreign: Vec<SmolStr> = Vec::new();
+    for grapheme in Graphemes::new(input) {
+        let key = SmolStr::new(grapheme);
+        let sym = match alphabet.string_to_symbol().get(&key).copied() {
+            Some(sym) if sym < alphabet.initial_symbol_count() => sym,
+            // Symbols added to the alphabet after loading, such as the error
+            // model's, have no arcs in the transducer.
+            Some(sym) if has_wildcards => sym,
+            None if has_wildcards => {
+                let index = match foreign.iter().position(|f| *f == key) {
+                    Some(index) => index,
+                    None => {
+                        foreign.push(key);
+                        foreign.len() - 1
+                    }
+                };
+                SymbolNumber(u16::try_from(alphabet.key_table().len() + index).ok()?)
+            }
+            _ => return None,
+        };
+        syms.push(sym);
+    }
+    Some((syms, foreign))
+}
+
+/// A path waiting in the queue.
+struct Queued<'a> {
+    /// the weight of the path, with the final weight for a complete one
+    weight: Weight,
+    /// whether the path has read all the input and ends here
+    complete: bool,
+    /// the order the path was queued in, for a stable order among equals
+    sequence: u64,
+    /// arcs followed since the path last read input
+    free_moves: u16,
+    node: Recycled<'a, TreeNode>,
+}
+
+impl PartialEq for Queued<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for Queued<'_> {}
+
+impl PartialOrd for Queued<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Queued<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Reversed: the cheapest path first out of the max-heap, complete
+        // ones before open ones of the same weight, then first queued first.
+        other
+            .weight
+            .cmp(&self.weight)
+            .then_with(|| self.complete.cmp(&other.complete))
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+struct Lookup<'a, T: Transducer> {
+    transducer: &'a T,
+    input: &'a [SymbolNumber],
+    max_weight: Option<Weight>,
+    pool: &'a Pool<TreeNode>,
+    queue: BinaryHeap<Queued<'a>>,
+    sequence: u64,
+}
+
+impl<'a, T: Transducer> Lookup<'a, T> {
+    fn push(&mut self, node: Recycled<'a, TreeNode>, free_moves: u16, complete: bool) {
+        if self.max_weight.is_some_and(|max| node.weight() > max) {
+            return;
+        }
+        self.sequence += 1;
+        self.queue.push(Queued {
+            weight: node.weight(),
+            complete,
+            sequence: self.sequence,
+            free_moves,
+            node,
+        });
+    }
+
+    /// Queue every path one arc longer than `node`'s.
+    fn expand(&mut self, node: &TreeNode, free_moves: u16) {
+        let transducer = self.transducer;
+        let state = node.lexicon_state;
+
+        // Arcs reading no input: epsilons and flag diacritics.
+        if free_moves < MAX_FREE_MOVES
+            && transducer.has_epsilons_or_flags(state.incr())
+            && let Some(mut pos) = transducer.next(state, SymbolNumber::ZERO)
+        {
+            let operations = transducer.alphabet().operations();
+            while let Some(trans) = transducer.take_epsilons_and_flags(pos) {
+                let input_sym = transducer
+                    .transition_input_symbol(pos)
+                    .unwrap_or(SymbolNumber::ZERO);
+                if input_sym == SymbolNumber::ZERO {
+                    self.advance(node, free_moves, None, false, trans, None);
+                } else if let Some(op) = operations.get(&input_sym) {
+                    self.advance(node, free_moves, None, false, trans, Some(op));
+                }
+                pos = pos.incr();
+            }
+        }
+
+        // Arcs reading the next input symbol, or a wildcard for a symbol
+        // outside the alphabet.
+        let index = node.input_state.0 as usize;
+        let Some(&sym) = self.input.get(index) else {
+            return;
+        };
+        let alphabet = transducer.alphabet();
+        let inputs = if sym >= alphabet.initial_symbol_count() {
+            [alphabet.identity(), alphabet.unknown()]
+        } else {
+            [Some(sym), None]
+        };
+        for input_sym in inputs.into_iter().flatten() {
+            if transducer.has_transitions(state.incr(), Some(input_sym))
+                && let Some(mut pos) = transducer.next(state, input_sym)
+            {
+                let wildcard = input_sym != sym;
+                while let Some(trans) = transducer.take_non_epsilons(pos, input_sym) {
+                    self.advance(node, free_moves, Some(sym), wildcard, trans, None);
+                    pos = pos.incr();
+                }
+            }
+        }
+    }
+
+    /// Queue the path of `node` extended by `trans`, which reads `read` from
+    /// the input (through a wildcard if `wildcard`), or nothing.
+    fn advance(
+        &mut self,
+        node: &TreeNode,
+        free_moves: u16,
+        read: Option<SymbolNumber>,
+        wildcard: bool,
+        trans: SymbolTransition,
+        op: Option<&FlagDiacriticOperation>,
+    ) {
+        if trans.target().is_none() {
+            return;
+        }
+        let alphabet = self.transducer.alphabet();
+        let mut output_sym = trans.symbol().unwrap_or(SymbolNumber::ZERO);
+
+        // A wildcard on the output side writes the symbol its partner on the
+        // input side read. With no such symbol there is nothing to write.
+        let is_wildcard = |sym| sym == alphabet.identity() || sym == alphabet.unknown();
+        if is_wildcard(Some(output_sym)) {
+            match read {
+                Some(sym) if wildcard => output_sym = sym,
+                _ => return,
+            }
+        }
+
+        let mut next = match op {
+            Some(op) => match node.apply_operation(self.pool, op, &trans) {
+                Some(next) => next,
+                None => return,
+            },
+            None => node.apply_transition(self.pool, &trans),
+        };
+        if output_sym != SymbolNumber::ZERO && !alphabet.is_flag(output_sym) {
+            next.string.push(output_sym);
+        }
+        let free_moves = match read {
+            Some(_) => {
+                next.input_state = InputIndex(node.input_state.0 + 1);
+                0
+            }
+            None => free_moves + 1,
+        };
+        self.push(next, free_moves, false);
+    }
+}
@@ -5,10 +5,20 @@
 //! hfst-ospell library. It may contain some complex optimisations and
 //! specifics to underlying finite-state systems and lot of this is
 //! pretty hacky.
+pub mod att;
+pub mod builder;
+pub mod errmodel;
 pub mod hfst;
+pub mod hunspell;
+pub mod lookup;
+pub mod optimize;
+pub mod paths;
+pub mod rational;
 pub mod thfst;
+pub mod wordlist;
 
-mod alphabet;
+pub(crate) mod alphabet;
+pub(crate) mod graph;
 pub(crate) mod heuristic;
 pub(crate) mod symbol_transition;
 pub(crate) mod tree_node;
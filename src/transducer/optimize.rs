@@ -0,0 +1,375 @@
+//! Making a transducer smaller and faster to search, offline.
+//!
+//! Compiled error models usually ship non-deterministic, which the speller
+//! makes up for at search time with a lazy subset construction (see
+//! `speller::subset`). [`optimize`] does that work once, ahead of time:
+//!
+//! 1. It reads the transducer's states and arcs, and drops the states from
+//!    which no final state can be reached.
+//! 2. It determinises the transducer over `input:output` pairs, following
+//!    arcs that read and write nothing for free and moving each subset's
+//!    weight as early as it can go. Flag diacritics are labels like any
+//!    other: every path into a subset took the same ones, so the paths agree
+//!    on what the flags have been set to. A transducer whose determinisation
+//!    grows past [`OptimizeConfig::max_states`], as one that isn't
+//!    determinisable does for ever, is kept as it was.
+//! 3. It minimises the result, merging states whose final weights and
+//!    arcs, to states already merged, are the same. This works whether or
+//!    not the transducer was determinised, though only a deterministic one
+//!    is then as small as it can be.
+//!
+//! The optimised transducer accepts the same pairs at the same weights, its
+//! weights possibly further along their paths. Determinising an error model
+//! saves the speller its subset construction but can multiply its arcs, as
+//! every combination of edits the model can be in gets its own state; the
+//! [`OptimizeReport`] says what it cost, and minimising alone never adds
+//! any.
+use hashbrown::HashMap;
+use std::collections::BTreeMap;
+
+use super::graph::{Arc, Automaton, State};
+use super::thfst::MemoryThfstTransducer;
+use super::{Transducer, TransducerError};
+use crate::types::SymbolNumber;
+
+/// Most members a determinised state may have before determinisation is
+/// abandoned.
+const MAX_SUBSET_MEMBERS: usize = 256;
+
+/// Most steps the closure over free arcs may take for one determinised
+/// state.
+const MAX_CLOSURE_STEPS: usize = 8192;
+
+/// How hard [`optimize`] tries.
+#[derive(Debug, Clone)]
+pub struct OptimizeConfig {
+    /// Whether to determinise before minimising.
+    pub determinise: bool,
+    /// Most states the determinised transducer may have before the
+    /// transducer is only minimised instead.
+    pub max_states: usize,
+}
+
+impl Default for OptimizeConfig {
+    fn default() -> Self {
+        Self {
+            determinise: true,
+            max_states: 1 << 20,
+        }
+    }
+}
+
+/// What [`optimize`] did: the sizes of the transducer before and after,
+/// counting only states reachable from the start state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptimizeReport {
+    /// States before.
+    pub states_before: usize,
+    /// States after.
+    pub states_after: usize,
+    /// Arcs before.
+    pub arcs_before: usize,
+    /// Arcs after.
+    pub arcs_after: usize,
+    /// Whether the transducer was determinised; if not, it was only
+    /// minimised.
+    pub determinised: bool,
+}
+
+/// Determinise where possible and minimise `transducer`.
+///
+/// Fails only if the result cannot be packed into tables, which the input
+/// could be.
+pub fn optimize<T: Transducer>(
+    transducer: &T,
+    config: &OptimizeConfig,
+) -> Result<(MemoryThfstTransducer, OptimizeReport), TransducerError> {
+    let automaton = Automaton::read(transducer);
+    let states_before = automaton.states.len();
+    let arcs_before = automaton.arc_count();
+
+    let trimmed = automaton.trim();
+    let determinised = if config.determinise {
+        trimmed.determinise(config.max_states)
+    } else {
+        None
+    };
+    let is_determinised = determinised.is_some();
+    let minimised = determinised.unwrap_or(trimmed).minimise();
+
+    let report = OptimizeReport {
+        states_before,
+        states_after: minimised.states.len(),
+        arcs_before,
+        arcs_after: minimised.arc_count(),
+        determinised: is_determinised,
+    };
+    Ok((minimised.build()?, report))
+}
+
+/// The bits of a weight, with `-0.0` and `0.0` the same.
+fn bits(weight: f32) -> u32 {
+    (weight + 0.0).to_bits()
+}
+
+impl Automaton {
+    /// The states reachable from `seeds` by free arcs, each at the cheapest
+    /// weight it can be reached at, in state order. `None` if that takes too
+    /// long or reaches too many states.
+    fn closure(&self, seeds: &[(usize, f32)]) -> Option<Vec<(usize, f32)>> {
+        let mut closure: HashMap<usize, f32> = HashMap::new();
+        let mut pending = vec![];
+        for &(state, weight) in seeds {
+            if closure.get(&state).is_none_or(|best| weight < *best) {
+                closure.insert(state, weight);
+                pending.push(state);
+            }
+        }
+        let mut steps = 0;
+        while let Some(state) = pending.pop() {
+            steps += 1;
+            if steps > MAX_CLOSURE_STEPS || closure.len() > MAX_SUBSET_MEMBERS {
+                return None;
+            }
+            let weight = closure[&state];
+            for arc in self.states[state].arcs.iter().filter(|arc| arc.is_free()) {
+                let next = weight + arc.weight;
+                if closure.get(&arc.target).is_none_or(|best| next < *best) {
+                    closure.insert(arc.target, next);
+                    pending.push(arc.target);
+                }
+            }
+        }
+        let mut closure: Vec<_> = closure.into_iter().collect();
+        closure.sort_unstable_by_key(|(state, _)| *state);
+        Some(closure)
+    }
+
+    /// Determinise over `input:output` pairs, or `None` if that takes more
+    /// than `max_states` states.
+    fn determinise(&self, max_states: usize) -> Option<Automaton> {
+        type Subset = Vec<(usize, f32)>;
+        let key = |subset: &Subset| -> Vec<(usize, u32)> {
+            subset.iter().map(|(s, w)| (*s, bits(*w))).collect()
+        };
+
+        // The start subset has to cost nothing to be in, there being no
+        // initial weight to put what it does cost on.
+        let start = self.closure(&[(0, 0.0)])?;
+        if start.iter().any(|(_, weight)| *weight < 0.0) {
+            return None;
+        }
+        let mut intern: HashMap<_, usize> = HashMap::new();
+        intern.insert(key(&start), 0);
+        let mut subsets = vec![start];
+        let mut states = vec![];
+
+        while states.len() < subsets.len() {
+            let subset = subsets[states.len()].clone();
+            let final_weight = subset
+                .iter()
+                .filter_map(|(s, w)| Some(w + self.states[*s].final_weight?))
+                .min_by(f32::total_cmp);
+
+            let mut groups: BTreeMap<(SymbolNumber, SymbolNumber), Subset> = BTreeMap::new();
+            for &(s, w) in &subset {
+                for arc in self.states[s].arcs.iter().filter(|arc| !arc.is_free()) {
+                    groups
+                        .entry((arc.input, arc.output))
+                        .or_default()
+                        .push((arc.target, w + arc.weight));
+                }
+            }
+
+            let mut arcs = vec![];
+            for ((input, output), group) in groups {
+                let mut next = self.closure(&group)?;
+                let weight = next
+                    .iter()
+                    .map(|(_, w)| *w)
+                    .min_by(f32::total_cmp)
+                    .unwrap_or(0.0);
+                for (_, w) in next.iter_mut() {
+                    *w -= weight;
+                }
+                let target = *intern.entry(key(&next)).or_insert_with(|| {
+                    subsets.push(next);
+                    subsets.len() - 1
+                });
+                arcs.push(Arc {
+                    input,
+                    output,
+                    target,
+                    weight,
+                });
+            }
+            if subsets.len() > max_states {
+                return None;
+            }
+            states.push(State { final_weight, arcs });
+        }
+        Some(Automaton {
+            symbols: self.symbols.clone(),
+            states,
+        })
+    }
+
+    /// Merge the states that accept the same pairs at the same weights,
+    /// weight for weight along the way.
+    fn minimise(&self) -> Automaton {
+        // Split the states by final weight, then by their arcs into the
+        // classes of the last round, until no class splits.
+        let mut finals = HashMap::new();
+        let mut classes: Vec<usize> = self
+            .states
+            .iter()
+            .map(|state| {
+                let next = finals.len();
+                *finals.entry(state.final_weight.map(bits)).or_insert(next)
+            })
+            .collect();
+        let mut count = finals.len();
+        loop {
+            let mut signatures = HashMap::new();
+            let next: Vec<usize> = self
+                .states
+                .iter()
+                .enumerate()
+                .map(|(n, state)| {
+                    let id = signatures.len();
+                    *signatures
+                        .entry((classes[n], self.arcs(state, &classes)))
+                        .or_insert(id)
+                })
+                .collect();
+            classes = next;
+            if signatures.len() == count {
+                break;
+            }
+            count = signatures.len();
+        }
+
+        // Number the classes in the order their first states come, so the
+        // start state's is 0.
+        let mut numbers = vec![None; count];
+        let mut representatives = vec![];
+        for (n, class) in classes.iter().enumerate() {
+            if numbers[*class].is_none() {
+                numbers[*class] = Some(representatives.len());
+                representatives.push(n);
+            }
+        }
+        let classes: Vec<usize> = classes.iter().map(|c| numbers[*c].unwrap()).collect();
+        let states = representatives
+            .iter()
+            .map(|&n| State {
+                final_weight: self.states[n].final_weight,
+                arcs: self
+                    .arcs(&self.states[n], &classes)
+                    .into_iter()
+                    .map(|(input, output, weight, target)| Arc {
+                        input: SymbolNumber(input),
+                        output: SymbolNumber(output),
+                        target,
+                        weight: f32::from_bits(weight),
+                    })
+                    .collect(),
+            })
+            .collect();
+        Automaton {
+            symbols: self.symbols.clone(),
+            states,
+        }
+    }
+
+    /// The arcs of `state` to `classes`, in order and without repeats.
+    fn arcs(&self, state: &State, classes: &[usize]) -> Vec<(u16, u16, u32, usize)> {
+        let mut arcs: Vec<_> = state
+            .arcs
+            .iter()
+            .map(|arc| {
+                (
+                    arc.input.0,
+                    arc.output.0,
+                    bits(arc.weight),
+                    classes[arc.target],
+                )
+            })
+            .collect();
+        arcs.sort_unstable();
+        arcs.dedup();
+        arcs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transducer::att;
+    use crate::transducer::lookup::lookup;
+    use crate::transducer::paths::{PathsConfig, paths};
+
+    fn all<T: Transducer>(transducer: &T) -> Vec<(String, String, f32)> {
+        let mut all: Vec<_> = paths(transducer, PathsConfig::default())
+            .map(|p| (p.input.to_string(), p.output.to_string(), p.weight.0))
+            .collect();
+        all.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        all
+    }
+
+    #[test]
+    fn test_determinise_and_minimise() {
+        // cat and car on separate branches, each then ending alike, and a
+        // free arc into the second.
+        let text = "0\t1\tc\tc\t0\n0\t4\t@0@\t@0@\t0.5\n\
+                    4\t5\tc\tc\t0\n1\t2\ta\ta\t0\n2\t3\tt\tt\t0\n\
+                    5\t6\ta\ta\t0\n6\t3\tr\tr\t0\n3\t0\n";
+        let nfa = att::parse(text).unwrap();
+        let (optimised, report) = optimize(&nfa, &OptimizeConfig::default()).unwrap();
+        assert!(report.determinised);
+        assert_eq!(report.states_before, 7);
+        // c, a, then t at 0 and r at 0.5 to the same final state
+        assert_eq!(report.states_after, 4);
+        assert_eq!(report.arcs_after, 4);
+        assert_eq!(all(&optimised), all(&nfa));
+    }
+
+    #[test]
+    fn test_trim() {
+        // The arc reading b leads nowhere final.
+        let text = "0\t1\ta\ta\t0\n0\t2\tb\tb\t0\n2\t3\tc\tc\t0\n1\t0\n";
+        let transducer = att::parse(text).unwrap();
+        let (optimised, report) = optimize(&transducer, &OptimizeConfig::default()).unwrap();
+        assert_eq!((report.states_after, report.arcs_after), (2, 1));
+        assert_eq!(all(&optimised), all(&transducer));
+    }
+
+    #[test]
+    fn test_falls_back_to_minimising() {
+        // a*b and a*c, which a determinisation of at most two states can't
+        // hold, but whose two loops are alike.
+        let text = "0\t1\t@0@\t@0@\t0\n0\t2\t@0@\t@0@\t0\n\
+                    1\t1\ta\ta\t0\n2\t2\ta\ta\t0\n1\t3\tb\tb\t0\n2\t3\tc\tc\t0\n3\t0\n";
+        let nfa = att::parse(text).unwrap();
+        let same = |optimised: &MemoryThfstTransducer| {
+            for input in ["b", "c", "aab", "aaac", "a", "ba"] {
+                assert_eq!(lookup(optimised, input), lookup(&nfa, input), "{input}");
+            }
+        };
+
+        let config = OptimizeConfig {
+            max_states: 2,
+            ..OptimizeConfig::default()
+        };
+        let (optimised, report) = optimize(&nfa, &config).unwrap();
+        assert!(!report.determinised);
+        assert_eq!(report.states_after, 4);
+        same(&optimised);
+
+        // {0, 1, 2} and {1, 2} are alike too.
+        let (optimised, report) = optimize(&nfa, &OptimizeConfig::default()).unwrap();
+        assert!(report.determinised);
+        assert_eq!(report.states_after, 2);
+        same(&optimised);
+    }
+}
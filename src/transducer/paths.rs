@@ -0,0 +1,426 @@
+//! The accepting paths of a transducer: the string pairs it relates.
+//!
+//! [`paths`] walks a transducer from its start state and gives every path
+//! ending in a final state as the strings on its two sides and its weight,
+//! for dumping a lexicon's word list, sampling from it or comparing two
+//! builds. [`PathOrder`] chooses how it walks:
+//!
+//! - best-first, the default, gives paths cheapest first, so the cheapest `n`
+//!   can be had without walking the rest. Every path not yet extended is kept
+//!   until it is, which on a large lexicon is most of them;
+//! - depth-first keeps only the paths branching off the one it is on, so
+//!   memory grows with the length of a path rather than the size of the
+//!   lexicon, which is what dumping all of it needs;
+//! - random walks sample paths: from each state, a walk takes one of its
+//!   transitions, or ends if it is final, all equally likely. The same seed
+//!   gives the same sample.
+//!
+//! A lexicon with cycles, as any allowing compounds has, relates infinitely
+//! many strings. [`PathsConfig`] bounds the walk by the length and weight of
+//! a path and the number of paths given; the iterator can also simply be
+//! dropped.
+//!
+//! Flag diacritics are checked as the speller checks them, so a path they
+//! rule out is not given, and they are written to neither side. Wildcards,
+//! which stand for any character, are written as their symbols.
+use std::collections::BinaryHeap;
+
+use hashbrown::HashSet;
+use lifeguard::Pool;
+use smol_str::SmolStr;
+
+use super::Transducer;
+use super::symbol_transition::SymbolTransition;
+use super::tree_node::TreeNode;
+use crate::types::{FlagDiacriticOperation, SymbolNumber, ValueNumber, Weight};
+
+/// Configuration for [`paths`].
+#[derive(Debug, Clone)]
+pub struct PathsConfig {
+    /// Maximum number of transitions on a path.
+    pub max_depth: usize,
+    /// Maximum path weight; paths exceeding this are pruned. `None` =
+    /// unbounded.
+    pub max_weight: Option<Weight>,
+    /// Maximum number of paths to give: the first this many in `order`.
+    /// `None` = unbounded, which for [`PathOrder::Random`] is forever.
+    pub max_results: Option<usize>,
+    /// The order to walk the transducer in.
+    pub order: PathOrder,
+}
+
+impl Default for PathsConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 64,
+            max_weight: None,
+            max_results: None,
+            order: PathOrder::default(),
+        }
+    }
+}
+
+/// How [`paths`] walks a transducer; see the [module docs](self).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PathOrder {
+    /// Cheapest first, each pair of strings once, with the weight of its
+    /// cheapest path.
+    #[default]
+    Cheapest,
+    /// Depth first, taking transitions in the order they are stored, and a
+    /// path ending in a final state before any continuing it. Every path is
+    /// given, so a pair of strings with several paths is given once for each.
+    DepthFirst,
+    /// Random walks from the start state, with this seed. A walk left with
+    /// nowhere to go, in a state that is not final with no transitions
+    /// within `max_depth` and `max_weight`, is started over; paths are given
+    /// as often as a walk ends in them.
+    Random { seed: u64 },
+}
+
+/// Walks a random sample starts without one ending in a path before it gives
+/// up, taking the transducer to accept nothing within the bounds.
+const MAX_FAILED_WALKS: usize = 10_000;
+
+/// An accepting path of a transducer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcceptingPath {
+    /// The string on the input side.
+    pub input: SmolStr,
+    /// The string on the output side.
+    pub output: SmolStr,
+    /// The weight of the path, with the final weight.
+    pub weight: Weight,
+}
+
+/// Every accepting path of `transducer` within the bounds of `config`, in
+/// the order of [`PathsConfig::order`].
+pub fn paths<T: Transducer>(transducer: &T, config: PathsConfig) -> Paths<'_, T> {
+    let flag_size = transducer.alphabet().state_size().0 as usize;
+    let pool = Pool::with_size_and_max(0, 0);
+    let start = TreeNode::empty(&pool, vec![ValueNumber::ZERO; flag_size]).detach();
+    let frontier = match config.order {
+        PathOrder::Cheapest => Frontier::Cheapest(BinaryHeap::new()),
+        PathOrder::DepthFirst => Frontier::DepthFirst(vec![]),
+        PathOrder::Random { seed } => Frontier::Random {
+            start: start.clone(),
+            walk: None,
+            rng: SplitMix64(seed),
+            failures: 0,
+        },
+    };
+    let mut paths = Paths {
+        transducer,
+        config,
+        pool,
+        frontier,
+        children: vec![],
+        sequence: 0,
+        seen: HashSet::new(),
+        given: 0,
+    };
+    paths.push(start, vec![], 0, false);
+    paths.schedule();
+    paths
+}
+
+/// Iterator over the accepting paths of a transducer; see [`paths`].
+pub struct Paths<'a, T: Transducer> {
+    transducer: &'a T,
+    config: PathsConfig,
+    /// Only lends [`TreeNode`]'s transitions and flag checks their nodes;
+    /// every node is detached from it at once, to be queued.
+    pool: Pool<TreeNode>,
+    frontier: Frontier,
+    /// The paths one transition longer than the one last extended, and that
+    /// one again if it is complete, until they are scheduled.
+    children: Vec<Queued>,
+    sequence: u64,
+    /// The pairs of strings given so far, for [`PathOrder::Cheapest`] only.
+    seen: HashSet<(SmolStr, SmolStr)>,
+    given: usize,
+}
+
+/// The paths waiting to be taken up, kept as [`PathOrder`] needs them.
+enum Frontier {
+    Cheapest(BinaryHeap<Queued>),
+    /// a stack, the next path to extend on top
+    DepthFirst(Vec<Queued>),
+    Random {
+        /// the empty path every walk starts from
+        start: TreeNode,
+        /// the path the current walk is on
+        walk: Option<Queued>,
+        rng: SplitMix64,
+        /// the walks started since one last ended in a path
+        failures: usize,
+    },
+}
+
+/// The SplitMix64 generator: small, fast and plenty for choosing arcs.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number below `n`, which must not be 0.
+    fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+}
+
+/// A path waiting in the queue.
+struct Queued {
+    /// the weight of the path, with the final weight for a complete one
+    weight: Weight,
+    /// whether the path ends here, rather than being one to extend
+    complete: bool,
+    /// the order the path was queued in, for a stable order among equals
+    sequence: u64,
+    /// the number of transitions on the path
+    depth: usize,
+    /// the input side; the node's string is the output side
+    input: Vec<SymbolNumber>,
+    node: TreeNode,
+}
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for Queued {}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Reversed: the cheapest path first out of the max-heap, complete
+        // ones before open ones of the same weight, then first queued first.
+        other
+            .weight
+            .cmp(&self.weight)
+            .then_with(|| self.complete.cmp(&other.complete))
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl<T: Transducer> Iterator for Paths<'_, T> {
+    type Item = AcceptingPath;
+
+    fn next(&mut self) -> Option<AcceptingPath> {
+        loop {
+            if self.config.max_results.is_some_and(|max| self.given >= max) {
+                return None;
+            }
+            let Queued {
+                weight,
+                complete,
+                depth,
+                input,
+                node,
+                ..
+            } = self.pop()?;
+
+            if complete {
+                let alphabet = self.transducer.alphabet();
+                let input = alphabet.string_from_symbols(&input);
+                let output = alphabet.string_from_symbols(&node.string);
+                // Best-first, nothing still queued is cheaper, so this path's
+                // weight is the best its strings get.
+                if matches!(self.frontier, Frontier::Cheapest(_))
+                    && !self.seen.insert((input.clone(), output.clone()))
+                {
+                    continue;
+                }
+                self.given += 1;
+                return Some(AcceptingPath {
+                    input,
+                    output,
+                    weight,
+                });
+            }
+
+            if depth < self.config.max_depth {
+                self.expand(&node, &input, depth);
+            }
+
+            // Queue a path ending in a final state again, as complete, at its
+            // weight with the final weight added.
+            let state = node.lexicon_state;
+            if self.transducer.is_final(state) {
+                let final_weight = self.transducer.final_weight(state).unwrap_or(Weight::ZERO);
+                let mut node = node;
+                node.weight = node.weight + final_weight;
+                self.push(node, input, depth, true);
+            }
+            self.schedule();
+        }
+    }
+}
+
+impl<T: Transducer> Paths<'_, T> {
+    fn push(&mut self, node: TreeNode, input: Vec<SymbolNumber>, depth: usize, complete: bool) {
+        if self.config.max_weight.is_some_and(|max| node.weight > max) {
+            return;
+        }
+        self.sequence += 1;
+        self.children.push(Queued {
+            weight: node.weight,
+            complete,
+            sequence: self.sequence,
+            depth,
+            input,
+            node,
+        });
+    }
+
+    /// The next path to take up, if any is left.
+    fn pop(&mut self) -> Option<Queued> {
+        match &mut self.frontier {
+            Frontier::Cheapest(queue) => queue.pop(),
+            Frontier::DepthFirst(stack) => stack.pop(),
+            Frontier::Random {
+                start,
+                walk,
+                failures,
+                ..
+            } => match walk.take() {
+                Some(path) => {
+                    if path.complete {
+                        *failures = 0;
+                    }
+                    Some(path)
+                }
+                None if *failures < MAX_FAILED_WALKS => {
+                    *failures += 1;
+                    self.sequence += 1;
+                    Some(Queued {
+                        weight: start.weight,
+                        complete: false,
+                        sequence: self.sequence,
+                        depth: 0,
+                        input: vec![],
+                        node: start.clone(),
+                    })
+                }
+                None => None,
+            },
+        }
+    }
+
+    /// Move the paths [`push`](Self::push)ed since the last call into the
+    /// frontier.
+    fn schedule(&mut self) {
+        match &mut self.frontier {
+            Frontier::Cheapest(queue) => queue.extend(self.children.drain(..)),
+            // The complete path was pushed last, so it comes off first, and
+            // the rest in the order they were pushed.
+            Frontier::DepthFirst(stack) => {
+                let complete = self
+                    .children
+                    .last()
+                    .is_some_and(|path| path.complete)
+                    .then(|| self.children.pop())
+                    .flatten();
+                stack.extend(self.children.drain(..).rev());
+                stack.extend(complete);
+            }
+            // A walk with nowhere to go leaves `walk` empty, to start over.
+            Frontier::Random { walk, rng, .. } => {
+                if !self.children.is_empty() {
+                    let choice = rng.below(self.children.len());
+                    *walk = Some(self.children.swap_remove(choice));
+                }
+                self.children.clear();
+            }
+        }
+    }
+
+    /// Push every path one transition longer than `node`'s.
+    fn expand(&mut self, node: &TreeNode, input: &[SymbolNumber], depth: usize) {
+        let transducer = self.transducer;
+        let state = node.lexicon_state;
+
+        // Transitions reading no input: epsilons and flag diacritics.
+        if transducer.has_epsilons_or_flags(state.incr())
+            && let Some(mut pos) = transducer.next(state, SymbolNumber::ZERO)
+        {
+            let operations = transducer.alphabet().operations();
+            while let Some(trans) = transducer.take_epsilons_and_flags(pos) {
+                let input_sym = transducer
+                    .transition_input_symbol(pos)
+                    .unwrap_or(SymbolNumber::ZERO);
+                if input_sym == SymbolNumber::ZERO {
+                    self.advance(node, input, depth, input_sym, trans, None);
+                } else if let Some(op) = operations.get(&input_sym) {
+                    self.advance(node, input, depth, input_sym, trans, Some(op));
+                }
+                pos = pos.incr();
+            }
+        }
+
+        // Transitions reading a symbol, for every symbol of the alphabet.
+        //
+        // `has_transitions(state.incr(), Some(sym))` must be checked before
+        // `next(state, sym)`; see the same caveat in the speller's worker.
+        let alphabet = transducer.alphabet();
+        for sym in (1..alphabet.len()).map(|s| SymbolNumber(s as u16)) {
+            if alphabet.is_flag(sym) || !transducer.has_transitions(state.incr(), Some(sym)) {
+                continue;
+            }
+            let Some(mut pos) = transducer.next(state, sym) else {
+                continue;
+            };
+            while let Some(trans) = transducer.take_non_epsilons(pos, sym) {
+                self.advance(node, input, depth, sym, trans, None);
+                pos = pos.incr();
+            }
+        }
+    }
+
+    /// Push the path of `node` extended by `trans`, which reads `input_sym`.
+    fn advance(
+        &mut self,
+        node: &TreeNode,
+        input: &[SymbolNumber],
+        depth: usize,
+        input_sym: SymbolNumber,
+        trans: SymbolTransition,
+        op: Option<&FlagDiacriticOperation>,
+    ) {
+        if trans.target().is_none() {
+            return;
+        }
+        let next = match op {
+            Some(op) => match node.apply_operation(&self.pool, op, &trans) {
+                Some(next) => next,
+                None => return,
+            },
+            None => node.apply_transition(&self.pool, &trans),
+        };
+        let mut next = next.detach();
+
+        let alphabet = self.transducer.alphabet();
+        let mut input = input.to_vec();
+        if input_sym != SymbolNumber::ZERO && !alphabet.is_flag(input_sym) {
+            input.push(input_sym);
+        }
+        let output_sym = trans.symbol().unwrap_or(SymbolNumber::ZERO);
+        if output_sym != SymbolNumber::ZERO && !alphabet.is_flag(output_sym) {
+            next.string.push(output_sym);
+        }
+        self.push(next, input, depth + 1, false);
+    }
+}
@@ -0,0 +1,436 @@
+//! Combining transducers: union, concatenation, composition and
+//! intersection.
+//!
+//! [`union`] accepts what either transducer does, and [`concatenate`] what
+//! the first does followed by what the second does. [`compose`] feeds what
+//! the first writes to the second, such as a normaliser's output to an
+//! analyser, and [`intersect`] accepts the pairs both do. Weights add up
+//! along the paths taken. The result is held in memory; write it out as
+//! THFST through [`ConvertFile`].
+//!
+//! The two alphabets are merged by key. A wildcard, `@_IDENTITY_SYMBOL_@` or
+//! `@_UNKNOWN_SYMBOL_@`, reads what its transducer doesn't know, so each
+//! wildcard arc is also given arcs for the symbols only the other transducer
+//! knows, and goes on reading what neither does. Composition and
+//! intersection match wildcards with each other.
+//!
+//! Flag diacritics are kept on the paths of the result, where lookup and the
+//! speller check them as they did in the operand they came from. In
+//! composition and intersection a flag is a move of one transducer, which
+//! the other waits through, as it does through the first's arcs writing
+//! nothing. A flag feature named in both transducers is one feature in the
+//! result, and constrains both, as it would on one path.
+//!
+//! [`ConvertFile`]: super::convert::ConvertFile
+use hashbrown::HashMap;
+
+use super::graph::{Arc, Automaton, State};
+use super::thfst::MemoryThfstTransducer;
+use super::{Transducer, TransducerError};
+use crate::types::SymbolNumber;
+
+const IDENTITY: &str = "@_IDENTITY_SYMBOL_@";
+const UNKNOWN: &str = "@_UNKNOWN_SYMBOL_@";
+
+/// A transducer accepting what `first` or `second` does.
+pub fn union<A: Transducer, B: Transducer>(
+    first: &A,
+    second: &B,
+) -> Result<MemoryThfstTransducer, TransducerError> {
+    let (symbols, first, second) = harmonise(first, second);
+
+    // A new start state, with free arcs into both.
+    let start = State {
+        final_weight: None,
+        arcs: vec![free_arc(1, 0.0), free_arc(1 + first.states.len(), 0.0)],
+    };
+    let mut states = vec![start];
+    states.extend(shift(&first.states, 1));
+    states.extend(shift(&second.states, 1 + first.states.len()));
+    Automaton { symbols, states }.build()
+}
+
+/// A transducer accepting what `first` does followed by what `second` does.
+pub fn concatenate<A: Transducer, B: Transducer>(
+    first: &A,
+    second: &B,
+) -> Result<MemoryThfstTransducer, TransducerError> {
+    let (symbols, first, second) = harmonise(first, second);
+
+    // The first's final states go on into the second's start state, paying
+    // their final weight on the way.
+    let offset = first.states.len();
+    let mut states = first.states;
+    for state in &mut states {
+        if let Some(weight) = state.final_weight.take() {
+            state.arcs.push(free_arc(offset, weight));
+        }
+    }
+    states.extend(shift(&second.states, offset));
+    Automaton { symbols, states }.build()
+}
+
+/// A transducer writing what `second` writes for what `first` writes, for
+/// what `first` reads.
+pub fn compose<A: Transducer, B: Transducer>(
+    first: &A,
+    second: &B,
+) -> Result<MemoryThfstTransducer, TransducerError> {
+    let (symbols, first, second) = harmonise(first, second);
+    let flags = flags(&symbols);
+    let wildcards = wildcards(&symbols);
+
+    // The first moves alone when it writes nothing, the second when it
+    // reads nothing; a flag is both.
+    let product = Product {
+        first: &first,
+        second: &second,
+        first_alone: &|arc| arc.output == SymbolNumber::ZERO || flags[arc.output.0 as usize],
+        second_alone: &|arc| arc.input == SymbolNumber::ZERO || flags[arc.input.0 as usize],
+        together: &|a, b| matches(&wildcards, a.output, b.input).then_some((a.input, b.output)),
+    };
+    Automaton {
+        symbols: symbols.clone(),
+        states: product.states(),
+    }
+    .trim()
+    .build()
+}
+
+/// A transducer accepting the pairs both `first` and `second` do.
+pub fn intersect<A: Transducer, B: Transducer>(
+    first: &A,
+    second: &B,
+) -> Result<MemoryThfstTransducer, TransducerError> {
+    let (symbols, first, second) = harmonise(first, second);
+    let flags = flags(&symbols);
+    let wildcards = wildcards(&symbols);
+
+    let alone = |arc: &Arc| arc.is_free() || flags[arc.input.0 as usize];
+    let product = Product {
+        first: &first,
+        second: &second,
+        first_alone: &alone,
+        second_alone: &alone,
+        together: &|a, b| {
+            (matches(&wildcards, a.input, b.input) && matches(&wildcards, a.output, b.output))
+                .then_some((a.input, a.output))
+        },
+    };
+    Automaton {
+        symbols: symbols.clone(),
+        states: product.states(),
+    }
+    .trim()
+    .build()
+}
+
+fn free_arc(target: usize, weight: f32) -> Arc {
+    Arc {
+        input: SymbolNumber::ZERO,
+        output: SymbolNumber::ZERO,
+        target,
+        weight,
+    }
+}
+
+/// `states` numbered from `offset`.
+fn shift(states: &[State], offset: usize) -> impl Iterator<Item = State> + '_ {
+    states.iter().map(move |state| State {
+        final_weight: state.final_weight,
+        arcs: state
+            .arcs
+            .iter()
+            .map(|arc| Arc {
+                target: arc.target + offset,
+                ..*arc
+            })
+            .collect(),
+    })
+}
+
+fn is_flag(key: &str) -> bool {
+    key.len() >= 5 && key.starts_with('@') && key.ends_with('@') && key.as_bytes()[2] == b'.'
+}
+
+fn is_wildcard(key: &str) -> bool {
+    key == IDENTITY || key == UNKNOWN
+}
+
+fn flags(symbols: &[String]) -> Vec<bool> {
+    symbols.iter().map(|key| is_flag(key)).collect()
+}
+
+fn wildcards(symbols: &[String]) -> Vec<bool> {
+    symbols.iter().map(|key| is_wildcard(key)).collect()
+}
+
+/// Whether `a` and `b` read the same: they are the same symbol, or both
+/// wildcards.
+fn matches(wildcards: &[bool], a: SymbolNumber, b: SymbolNumber) -> bool {
+    a == b || (wildcards[a.0 as usize] && wildcards[b.0 as usize])
+}
+
+/// Read both transducers over one alphabet, their wildcard arcs given arcs
+/// for what the other knows, and return that alphabet's keys.
+fn harmonise<A: Transducer, B: Transducer>(
+    first: &A,
+    second: &B,
+) -> (Vec<String>, Automaton, Automaton) {
+    let mut first = Automaton::read(first);
+    let mut second = Automaton::read(second);
+
+    let mut symbols = first.symbols.clone();
+    let mut numbers: HashMap<String, SymbolNumber> = symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, key)| !key.is_empty())
+        .map(|(sym, key)| (key.clone(), SymbolNumber(sym as u16)))
+        .collect();
+    let renumbered: Vec<SymbolNumber> = second
+        .symbols
+        .iter()
+        .enumerate()
+        .map(|(sym, key)| {
+            if sym == 0 || key.is_empty() {
+                return SymbolNumber::ZERO;
+            }
+            *numbers.entry(key.clone()).or_insert_with(|| {
+                symbols.push(key.clone());
+                SymbolNumber((symbols.len() - 1) as u16)
+            })
+        })
+        .collect();
+    for arc in second.states.iter_mut().flat_map(|state| &mut state.arcs) {
+        arc.input = renumbered[arc.input.0 as usize];
+        arc.output = renumbered[arc.output.0 as usize];
+    }
+
+    // The symbols each wildcard can read: those of the other transducer it
+    // didn't know.
+    let readable = |known: &[String]| -> Vec<SymbolNumber> {
+        symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, key)| {
+                !key.is_empty() && !is_flag(key) && !is_wildcard(key) && !known.contains(key)
+            })
+            .map(|(sym, _)| SymbolNumber(sym as u16))
+            .collect()
+    };
+    let first_new = readable(&first.symbols);
+    let second_new = readable(&second.symbols);
+    let wildcards = wildcards(&symbols);
+    expand(&mut first, &first_new, &wildcards);
+    expand(&mut second, &second_new, &wildcards);
+    first.symbols = symbols.clone();
+    second.symbols = symbols.clone();
+    (symbols, first, second)
+}
+
+/// Give each arc reading a wildcard an arc reading each of `new` instead,
+/// writing it back if the wildcard would, and drop the arcs writing a
+/// wildcard they didn't read, which lookup never takes.
+fn expand(automaton: &mut Automaton, new: &[SymbolNumber], wildcards: &[bool]) {
+    for state in &mut automaton.states {
+        let mut arcs = Vec::with_capacity(state.arcs.len());
+        for arc in &state.arcs {
+            let reads_wildcard = wildcards[arc.input.0 as usize];
+            let writes_wildcard = wildcards[arc.output.0 as usize];
+            if writes_wildcard && !reads_wildcard {
+                continue;
+            }
+            arcs.push(*arc);
+            if reads_wildcard {
+                arcs.extend(new.iter().map(|&sym| Arc {
+                    input: sym,
+                    output: if writes_wildcard { sym } else { arc.output },
+                    ..*arc
+                }));
+            }
+        }
+        state.arcs = arcs;
+    }
+}
+
+/// What an arc reads and writes.
+type Label = (SymbolNumber, SymbolNumber);
+
+/// The states of two automata walked together: which arcs of each move on
+/// their own, and which pairs of arcs move together, and with what label.
+struct Product<'a> {
+    first: &'a Automaton,
+    second: &'a Automaton,
+    first_alone: &'a dyn Fn(&Arc) -> bool,
+    second_alone: &'a dyn Fn(&Arc) -> bool,
+    together: &'a dyn Fn(&Arc, &Arc) -> Option<Label>,
+}
+
+impl Product<'_> {
+    /// The reachable states of the product, 0 the start state.
+    ///
+    /// A path on which both move alone between two moves together could
+    /// interleave those moves many ways, and would be found once for each.
+    /// Only the first moving before the second is kept: once the second
+    /// has moved alone, the first waits for the next move together.
+    fn states(&self) -> Vec<State> {
+        let mut numbers: HashMap<(usize, usize, bool), usize> = HashMap::new();
+        let mut pending = vec![(0, 0, false)];
+        numbers.insert((0, 0, false), 0);
+        let mut states = vec![State::default()];
+
+        while let Some(key) = pending.pop() {
+            let (a, b, second_moved) = key;
+            let (first, second) = (&self.first.states[a], &self.second.states[b]);
+            let mut arcs = vec![];
+            let mut go = |target: (usize, usize, bool), input, output, weight| {
+                let next = numbers.len();
+                let target = *numbers.entry(target).or_insert_with(|| {
+                    pending.push(target);
+                    next
+                });
+                arcs.push(Arc {
+                    input,
+                    output,
+                    target,
+                    weight,
+                });
+            };
+
+            for arc in &first.arcs {
+                if (self.first_alone)(arc) {
+                    if !second_moved {
+                        go((arc.target, b, false), arc.input, arc.output, arc.weight);
+                    }
+                    continue;
+                }
+                for other in second.arcs.iter().filter(|arc| !(self.second_alone)(arc)) {
+                    if let Some((input, output)) = (self.together)(arc, other) {
+                        let target = (arc.target, other.target, false);
+                        go(target, input, output, arc.weight + other.weight);
+                    }
+                }
+            }
+            for arc in second.arcs.iter().filter(|arc| (self.second_alone)(arc)) {
+                go((a, arc.target, true), arc.input, arc.output, arc.weight);
+            }
+
+            let n = numbers[&key];
+            if states.len() <= n {
+                states.resize_with(n + 1, State::default);
+            }
+            states[n] = State {
+                final_weight: first
+                    .final_weight
+                    .zip(second.final_weight)
+                    .map(|(a, b)| a + b),
+                arcs,
+            };
+        }
+        states
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transducer::att;
+    use crate::transducer::lookup::lookup;
+    use crate::transducer::paths::{PathsConfig, paths};
+
+    fn all<T: Transducer>(transducer: &T) -> Vec<(String, String, f32)> {
+        let mut all: Vec<_> = paths(transducer, PathsConfig::default())
+            .map(|p| (p.input.to_string(), p.output.to_string(), p.weight.0))
+            .collect();
+        all.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        all
+    }
+
+    fn pairs(list: &[(&str, &str, f32)]) -> Vec<(String, String, f32)> {
+        list.iter()
+            .map(|(i, o, w)| (i.to_string(), o.to_string(), *w))
+            .collect()
+    }
+
+    /// ab:xy at 1, then optionally c at 0.5.
+    fn first() -> MemoryThfstTransducer {
+        att::parse("0\t1\ta\tx\t1\n1\t2\tb\ty\t0\n2\t0\n2\t3\tc\tc\t0.5\n3\t0\n").unwrap()
+    }
+
+    /// d, or x written as z.
+    fn second() -> MemoryThfstTransducer {
+        att::parse("0\t1\td\td\t0\n0\t1\tx\tz\t2\n1\t0\n").unwrap()
+    }
+
+    #[test]
+    fn test_union() {
+        let union = union(&first(), &second()).unwrap();
+        assert_eq!(
+            all(&union),
+            pairs(&[
+                ("ab", "xy", 1.0),
+                ("abc", "xyc", 1.5),
+                ("d", "d", 0.0),
+                ("x", "z", 2.0)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_concatenate() {
+        let both = concatenate(&first(), &second()).unwrap();
+        assert_eq!(
+            all(&both),
+            pairs(&[
+                ("abcd", "xycd", 1.5),
+                ("abcx", "xycz", 3.5),
+                ("abd", "xyd", 1.0),
+                ("abx", "xyz", 3.0)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_compose() {
+        // x written as z, then y passed through, or deleted at 3.
+        let lower = att::parse("0\t1\tx\tz\t2\n1\t2\ty\ty\t0\n1\t2\ty\t@0@\t3\n2\t0\n").unwrap();
+        let composed = compose(&first(), &lower).unwrap();
+        assert_eq!(
+            all(&composed),
+            pairs(&[("ab", "z", 6.0), ("ab", "zy", 3.0)])
+        );
+    }
+
+    #[test]
+    fn test_intersect() {
+        let other = att::parse("0\t1\ta\tx\t0\n1\t2\tb\ty\t0.25\n2\t0\n").unwrap();
+        let both = intersect(&first(), &other).unwrap();
+        assert_eq!(all(&both), pairs(&[("ab", "xy", 1.25)]));
+    }
+
+    #[test]
+    fn test_epsilons_are_not_interleaved() {
+        // Each side writes an epsilon before reading a.
+        let upper = att::parse("0\t1\t@0@\t@0@\t0\n1\t2\ta\ta\t0\n2\t0\n").unwrap();
+        let both = intersect(&upper, &upper).unwrap();
+        assert_eq!(paths(&both, PathsConfig::default()).count(), 1);
+        let both = compose(&upper, &upper).unwrap();
+        assert_eq!(paths(&both, PathsConfig::default()).count(), 1);
+    }
+
+    #[test]
+    fn test_wildcards_learn_the_other_alphabet() {
+        // Anything but a passes through; a is known and written as b.
+        let upper =
+            att::parse("0\t0\t@_IDENTITY_SYMBOL_@\t@_IDENTITY_SYMBOL_@\t0\n0\t0\ta\tb\t0\n0\t0\n")
+                .unwrap();
+        let lower = att::parse("0\t1\tq\tq\t0\n1\t2\tb\tb\t0\n2\t0\n").unwrap();
+        let composed = compose(&upper, &lower).unwrap();
+        // q was read by the identity wildcard, and still is.
+        assert_eq!(lookup(&composed, "qa")[0].0, "qb");
+        assert!(lookup(&composed, "za").is_empty());
+
+        let union = union(&upper, &lower).unwrap();
+        assert_eq!(lookup(&union, "qz")[0].0, "qz");
+    }
+}
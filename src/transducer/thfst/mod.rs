@@ -29,6 +29,10 @@ pub(crate) mod transition_table;
 pub type MmapThfstTransducer =
     ThfstTransducer<IndexTable<memmap2::Mmap>, TransitionTable<memmap2::Mmap>>;
 
+/// THFST transducer held in memory, as built at runtime rather than loaded
+/// from a file.
+pub type MemoryThfstTransducer = ThfstTransducer<IndexTable<Vec<u8>>, TransitionTable<Vec<u8>>>;
+
 /// THFST transducer using file-based access (Unix only, slower but lower memory footprint).
 #[cfg(unix)]
 pub type FileThfstTransducer<F> = ThfstTransducer<IndexTable<F>, TransitionTable<F>>;
@@ -70,14 +74,33 @@ where
     I: crate::transducer::IndexTableTrait,
     T: crate::transducer::TransitionTableTrait,
 {
-    index_table: I,
-    transition_table: T,
+    pub(crate) index_table: I,
+    pub(crate) transition_table: T,
     alphabet: TransducerAlphabet,
     /// Backward shortest distances, computed on first use by the suggestion
     /// search and kept for the transducer's lifetime.
     distances: OnceLock<BackwardDistance>,
 }
 
+impl<I, T> ThfstTransducer<I, T>
+where
+    I: crate::transducer::IndexTableTrait,
+    T: crate::transducer::TransitionTableTrait,
+{
+    pub(crate) fn from_parts(
+        index_table: I,
+        transition_table: T,
+        alphabet: TransducerAlphabet,
+    ) -> Self {
+        ThfstTransducer {
+            index_table,
+            transition_table,
+            alphabet,
+            distances: OnceLock::new(),
+        }
+    }
+}
+
 impl<I, T> crate::transducer::heuristic::BackwardTables for ThfstTransducer<I, T>
 where
     I: crate::transducer::IndexTableTrait,
@@ -0,0 +1,285 @@
+//! Compiling a word list into a lexicon.
+//!
+//! A word list has one word per line, optionally followed by a tab and how
+//! often the word was seen, here written `\t`:
+//!
+//! ```text
+//! talk\t12
+//! talked\t3
+//! walked
+//! ```
+//!
+//! A word listed without a count counts once, and a word listed twice counts
+//! as the sum of its counts. [`WordList::acceptor`] compiles the list into a
+//! deterministic acceptor with the fewest states, weighting each word by how
+//! rare it is, the negative log of its share of all counts, so that the
+//! speller suggests common words first. With [`EditDistance`] for the error
+//! model, that is a whole speller.
+//!
+//! [`EditDistance`]: super::errmodel::EditDistance
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use hashbrown::HashMap;
+use smol_str::SmolStr;
+use unic_segment::Graphemes;
+
+use super::TransducerError;
+use super::builder::TransducerBuilder;
+use super::thfst::MemoryThfstTransducer;
+use crate::types::Weight;
+
+/// Error reading a word list.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum WordListError {
+    /// A line is not a word with an optional count.
+    #[error("line {line}: {detail}")]
+    Syntax {
+        /// the line, counting from 1
+        line: usize,
+        /// human-readable explanation
+        detail: Cow<'static, str>,
+    },
+
+    /// The acceptor could not be built, such as for having more distinct
+    /// characters than an alphabet can hold.
+    #[error("failed to build acceptor")]
+    Build(#[source] TransducerError),
+}
+
+/// Words and how often each was seen.
+#[derive(Debug, Clone, Default)]
+pub struct WordList {
+    counts: BTreeMap<SmolStr, u64>,
+}
+
+impl WordList {
+    /// An empty word list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the word list written in `text`. Blank lines are skipped.
+    pub fn parse(text: &str) -> Result<WordList, WordListError> {
+        let mut list = WordList::new();
+        for (i, line) in text.lines().enumerate() {
+            let syntax = |detail: Cow<'static, str>| WordListError::Syntax {
+                line: i + 1,
+                detail,
+            };
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (word, count) = match line.split_once('\t') {
+                Some((word, count)) => {
+                    let count = count
+                        .trim()
+                        .parse()
+                        .ok()
+                        .filter(|count| *count > 0)
+                        .ok_or_else(|| {
+                            syntax(format!("'{count}' is not a positive count").into())
+                        })?;
+                    (word, count)
+                }
+                None => (line, 1),
+            };
+            if word.is_empty() {
+                return Err(syntax("empty word".into()));
+            }
+            list.insert(word, count);
+        }
+        Ok(list)
+    }
+
+    /// Count `word` `count` more times.
+    pub fn insert(&mut self, word: &str, count: u64) {
+        let total = self.counts.entry(word.into()).or_insert(0);
+        *total = total.saturating_add(count);
+    }
+
+    /// The number of distinct words.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Whether there are no words.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The characters the words are written with, in order. A character is
+    /// a grapheme cluster, as the speller reads its input, so a letter with
+    /// combining marks is one symbol.
+    pub fn alphabet(&self) -> Vec<SmolStr> {
+        let mut chars: Vec<&str> = self
+            .counts
+            .keys()
+            .flat_map(|word| Graphemes::new(word))
+            .collect();
+        chars.sort_unstable();
+        chars.dedup();
+        chars.into_iter().map(SmolStr::from).collect()
+    }
+
+    /// The weight the acceptor gives `word`, if it is listed.
+    pub fn weight(&self, word: &str) -> Option<Weight> {
+        let total: f64 = self.counts.values().map(|count| *count as f64).sum();
+        self.counts
+            .get(word)
+            .map(|count| Weight((total.ln() - (*count as f64).ln()) as f32))
+    }
+
+    /// Compile the words into a minimal deterministic acceptor.
+    ///
+    /// Each word's weight is spread along its path as early as it can be,
+    /// so that words sharing an ending at the same weights share its states.
+    pub fn acceptor(&self) -> Result<MemoryThfstTransducer, WordListError> {
+        let total: f64 = self.counts.values().map(|count| *count as f64).sum();
+        let total = total.ln();
+
+        // A trie of the words, whose nodes all come after their parents.
+        let mut nodes = vec![TrieNode::default()];
+        for (word, count) in &self.counts {
+            let mut node = 0;
+            for grapheme in Graphemes::new(word) {
+                let label = SmolStr::from(grapheme);
+                node = match nodes[node].children.iter().find(|(l, _)| *l == label) {
+                    Some((_, child)) => *child,
+                    None => {
+                        nodes.push(TrieNode::default());
+                        let child = nodes.len() - 1;
+                        nodes[node].children.push((label, child));
+                        child
+                    }
+                };
+            }
+            nodes[node].weight = Some(total - (*count as f64).ln());
+        }
+
+        // The cheapest word through each node, and so through its parent.
+        let mut cheapest = vec![f64::INFINITY; nodes.len()];
+        for n in (0..nodes.len()).rev() {
+            let below = nodes[n].children.iter().map(|(_, child)| cheapest[*child]);
+            cheapest[n] = below.fold(nodes[n].weight.unwrap_or(f64::INFINITY), f64::min);
+        }
+        // Everything up to a node's cheapest word is paid on the way in; the
+        // start state has no way in.
+        let paid = |n: usize| if n == 0 { 0.0 } else { cheapest[n] };
+
+        // Merge the nodes whose futures are the same, children first.
+        let mut classes: HashMap<Signature, usize> = HashMap::new();
+        let mut class_of = vec![0; nodes.len()];
+        let mut representatives = vec![];
+        for n in (0..nodes.len()).rev() {
+            let signature = Signature {
+                final_weight: nodes[n].weight.map(|w| ((w - paid(n)) as f32).to_bits()),
+                arcs: nodes[n]
+                    .children
+                    .iter()
+                    .map(|(label, child)| {
+                        let weight = (cheapest[*child] - paid(n)) as f32;
+                        (label.clone(), weight.to_bits(), class_of[*child])
+                    })
+                    .collect(),
+            };
+            let next = classes.len();
+            class_of[n] = *classes.entry(signature).or_insert_with(|| {
+                representatives.push(n);
+                next
+            });
+        }
+
+        let mut builder = TransducerBuilder::new();
+        let start = class_of[0];
+        let states: Vec<usize> = (0..representatives.len())
+            .map(|class| {
+                if class == start {
+                    0
+                } else {
+                    builder.add_state()
+                }
+            })
+            .collect();
+        for (class, &n) in representatives.iter().enumerate() {
+            let state = states[class];
+            if let Some(weight) = nodes[n].weight {
+                builder.set_final(state, Weight((weight - paid(n)) as f32));
+            }
+            for (label, child) in &nodes[n].children {
+                let sym = builder.symbol(label);
+                let weight = Weight((cheapest[*child] - paid(n)) as f32);
+                builder.add_arc(state, states[class_of[*child]], sym, sym, weight);
+            }
+        }
+        builder.build().map_err(WordListError::Build)
+    }
+}
+
+#[derive(Debug, Default)]
+struct TrieNode {
+    children: Vec<(SmolStr, usize)>,
+    weight: Option<f64>,
+}
+
+/// What a state accepts from here on: its final weight and its arcs, with
+/// their targets' classes.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct Signature {
+    final_weight: Option<u32>,
+    arcs: Vec<(SmolStr, u32, usize)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transducer::graph;
+    use crate::transducer::lookup::lookup;
+
+    #[test]
+    fn test_parse() {
+        let list = WordList::parse("talk\t12\nwalked\n\ntalk\t3\r\n").unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.counts["talk"], 15);
+        assert_eq!(list.counts["walked"], 1);
+
+        let err = WordList::parse("talk\n\ttwo\n").unwrap_err();
+        assert!(
+            matches!(err, WordListError::Syntax { line: 2, .. }),
+            "{err}"
+        );
+        let err = WordList::parse("talk\t0\n").unwrap_err();
+        assert!(
+            matches!(err, WordListError::Syntax { line: 1, .. }),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_acceptor_is_minimal() {
+        let list = WordList::parse("talk\ntalked\ntalking\nwalk\nwalked\nwalking\n").unwrap();
+        let acceptor = list.acceptor().unwrap();
+        // t|w, a, l, k, then e-d and i-n-g to one final state
+        assert_eq!(graph::states(&acceptor).len(), 9);
+        for word in ["talk", "walked", "talking"] {
+            let found = lookup(&acceptor, word);
+            assert_eq!(found.len(), 1, "{word}");
+            assert!(
+                (found[0].1.0 - list.weight(word).unwrap().0).abs() < 1e-5,
+                "{word}"
+            );
+        }
+        assert!(lookup(&acceptor, "tal").is_empty());
+    }
+
+    #[test]
+    fn test_weights_follow_counts() {
+        let list = WordList::parse("a\t1\nab\t3\n").unwrap();
+        let acceptor = list.acceptor().unwrap();
+        let weight = |word| lookup(&acceptor, word)[0].1.0;
+        assert!((weight("ab") - (4f32 / 3.0).ln()).abs() < 1e-6);
+        assert!((weight("a") - 4f32.ln()).abs() < 1e-6);
+    }
+}
@@ -62,6 +62,19 @@ impl std::str::FromStr for FlagDiacriticOperator {
     }
 }
 
+impl std::fmt::Display for FlagDiacriticOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FlagDiacriticOperator::PositiveSet => "P",
+            FlagDiacriticOperator::NegativeSet => "N",
+            FlagDiacriticOperator::Require => "R",
+            FlagDiacriticOperator::Disallow => "D",
+            FlagDiacriticOperator::Clear => "C",
+            FlagDiacriticOperator::Unification => "U",
+        })
+    }
+}
+
 /// Transducer header property flags.
 ///
 /// These flags describe properties of the finite-state transducer.
@@ -43,6 +43,30 @@ impl Memory for memmap2::Mmap {
     }
 }
 
+/// In-memory access implementation.
+///
+/// For tables built at runtime rather than read from a file, such as those
+/// of a transducer parsed from AT&T text. Values are stored little-endian, as
+/// in a file.
+impl Memory for Vec<u8> {
+    #[inline(always)]
+    fn read_u16_at(&self, offset: usize) -> u16 {
+        u16::from_le_bytes([self[offset], self[offset + 1]])
+    }
+
+    #[inline(always)]
+    fn read_u32_at(&self, offset: usize) -> u32 {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(&self[offset..offset + 4]);
+        u32::from_le_bytes(buf)
+    }
+
+    #[inline(always)]
+    fn read_f32_at(&self, offset: usize) -> f32 {
+        f32::from_bits(self.read_u32_at(offset))
+    }
+}
+
 /// File-based access implementation (Unix only).
 ///
 /// Uses `read_exact_at` syscalls for each access. This is slower than mmap
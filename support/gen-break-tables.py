@@ -0,0 +1,143 @@
+#!/usr/bin/env python3
+"""Regenerate the UAX #29 word and sentence break tables used by the tokenizer.
+
+Reads WordBreakProperty.txt, SentenceBreakProperty.txt, emoji-data.txt and
+WordBreakTest.txt from a local copy of the Unicode Character Database, or
+downloads them for the given Unicode version, and writes:
+
+  src/tokenizer/tables/word_break.rsv
+  src/tokenizer/tables/sentence_break.rsv
+  src/tokenizer/tables/extended_pictographic.rsv
+  tests/fixtures/WordBreakTest.txt
+
+WordBreakTest.txt is copied unmodified. A local copy without it leaves the
+fixture as it is.
+
+Usage:
+  support/gen-break-tables.py --version 16.0.0
+  support/gen-break-tables.py --ucd path/to/ucd
+"""
+
+import argparse
+import os
+import re
+import shutil
+import sys
+import urllib.request
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+TABLES = os.path.join(ROOT, "src", "tokenizer", "tables")
+FIXTURES = os.path.join(ROOT, "tests", "fixtures")
+
+# Where each file lives, relative to the root of the UCD.
+FILES = {
+    "WordBreakProperty.txt": "auxiliary/WordBreakProperty.txt",
+    "SentenceBreakProperty.txt": "auxiliary/SentenceBreakProperty.txt",
+    "WordBreakTest.txt": "auxiliary/WordBreakTest.txt",
+    "emoji-data.txt": "emoji/emoji-data.txt",
+}
+
+
+def fetch(version, dest):
+    os.makedirs(dest, exist_ok=True)
+    for name, path in FILES.items():
+        url = "https://www.unicode.org/Public/%s/ucd/%s" % (version, path)
+        print("fetching", url, file=sys.stderr)
+        with urllib.request.urlopen(url) as response:
+            with open(os.path.join(dest, name), "wb") as f:
+                f.write(response.read())
+
+
+def find(ucd, name, required=True):
+    for candidate in (os.path.join(ucd, FILES[name]), os.path.join(ucd, name)):
+        if os.path.exists(candidate):
+            return candidate
+    if required:
+        sys.exit("%s not found in %s" % (name, ucd))
+    return None
+
+
+def parse(path, wanted=None):
+    """The (first, last, value) ranges of a UCD property file, and the
+    Unicode version named in its header."""
+    version = None
+    ranges = []
+    with open(path, encoding="utf-8") as f:
+        for line in f:
+            if version is None:
+                m = re.match(r"#\s*\S+-(\d+\.\d+\.\d+)\.txt", line)
+                if m:
+                    version = m.group(1)
+            line = line.split("#", 1)[0].strip()
+            if not line:
+                continue
+            chars, value = (field.strip() for field in line.split(";"))
+            if wanted is not None and value != wanted:
+                continue
+            first, _, last = chars.partition("..")
+            ranges.append((int(first, 16), int(last or first, 16), value))
+    ranges.sort()
+    merged = []
+    for first, last, value in ranges:
+        if merged and merged[-1][2] == value and merged[-1][1] + 1 == first:
+            merged[-1] = (merged[-1][0], last, value)
+        else:
+            merged.append((first, last, value))
+    return version, merged
+
+
+def write_table(path, version, ranges, value):
+    with open(path, "w", encoding="utf-8") as f:
+        f.write("// WARNING: Auto-generated by `support/gen-break-tables.py`\n")
+        f.write("// from Unicode %s.\n" % version)
+        f.write("// WARNING: DO NOT EDIT MANUALLY!\n")
+        f.write("CharDataTable::Range(&[\n")
+        for first, last, v in ranges:
+            f.write(
+                "    (chars!('\\u{%x}'..='\\u{%x}'), %s),\n" % (first, last, value(v))
+            )
+        f.write("])\n")
+    print("wrote", os.path.relpath(path, ROOT), file=sys.stderr)
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.split("\n")[0])
+    source = parser.add_mutually_exclusive_group(required=True)
+    source.add_argument("--version", help="Unicode version to download, e.g. 16.0.0")
+    source.add_argument("--ucd", help="directory holding the UCD files")
+    args = parser.parse_args()
+
+    ucd = args.ucd
+    if args.version:
+        ucd = os.path.join(ROOT, "target", "ucd-%s" % args.version)
+        fetch(args.version, ucd)
+
+    version, word_break = parse(find(ucd, "WordBreakProperty.txt"))
+    write_table(
+        os.path.join(TABLES, "word_break.rsv"), version, word_break, lambda v: "WB::" + v
+    )
+    sentence_version, sentence_break = parse(find(ucd, "SentenceBreakProperty.txt"))
+    write_table(
+        os.path.join(TABLES, "sentence_break.rsv"),
+        sentence_version,
+        sentence_break,
+        lambda v: "SB::" + v,
+    )
+    _, ext_pict = parse(find(ucd, "emoji-data.txt"), "Extended_Pictographic")
+    write_table(
+        os.path.join(TABLES, "extended_pictographic.rsv"),
+        version,
+        ext_pict,
+        lambda _: "()",
+    )
+    test = find(ucd, "WordBreakTest.txt", required=not args.ucd)
+    if test is None:
+        print("no WordBreakTest.txt in %s; fixture left as it is" % ucd, file=sys.stderr)
+        return
+    os.makedirs(FIXTURES, exist_ok=True)
+    shutil.copyfile(test, os.path.join(FIXTURES, "WordBreakTest.txt"))
+    print("wrote tests/fixtures/WordBreakTest.txt", file=sys.stderr)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,113 @@
+#!/usr/bin/env node
+// Write tests/fixtures/word-break-icu.txt: word boundaries found by ICU, as
+// Node's Intl.Segmenter exposes it, in the format of the UCD's
+// WordBreakTest.txt.
+//
+// This is not the UCD's conformance file, which support/gen-break-tables.py
+// copies into tests/fixtures/WordBreakTest.txt unmodified. It is a second,
+// independent implementation of UAX #29 to check the tokenizer against, over
+// many more strings: every pair of the samples below, with and without a
+// combining mark between them, the longer strings after them, and random
+// strings of the samples, seeded so the file is the same on every run.
+//
+// ICU's Unicode version need not be the tables'. The samples are characters
+// whose Word_Break and Extended_Pictographic values have not changed since
+// Unicode 14; check that still holds before adding any.
+//
+// Usage:
+//   node support/gen-word-break-icu.mjs
+
+import { writeFileSync } from "node:fs";
+import { dirname, join } from "node:path";
+import { fileURLToPath } from "node:url";
+
+const ROOT = dirname(dirname(fileURLToPath(import.meta.url)));
+const FIXTURE = join(ROOT, "tests", "fixtures", "word-break-icu.txt");
+
+// A character of each Word_Break value, of Extended_Pictographic, and of
+// both, U+24C2, which is a letter.
+const SAMPLES = [
+  0x0001, 0x000d, 0x000a, 0x000b, 0x3031, 0x0041, 0x003a, 0x002c, 0x002e,
+  0x0030, 0x005f, 0x1f1e6, 0x05d0, 0x0022, 0x0027, 0x231a, 0x0020, 0x00ad,
+  0x0300, 0x200d, 0x0061, 0x24c2, 0x2060,
+];
+
+// More characters for the random strings: a second regional indicator, an
+// emoji with a skin tone, and other spaces and apostrophes.
+const RANDOM_SAMPLES = [
+  ...SAMPLES, 0x1f1e7, 0x0308, 0x1f6d1, 0x1f3ff, 0x3000, 0x2019,
+];
+const RANDOM_STRINGS = 2000;
+
+// Longer strings for the rules the pairs alone don't reach.
+const EXTRA_SAMPLES = [
+  "can't", "can’t", "ab­by", "a$-34,567.14%b", "3a", "c.d", "C.d",
+  "c.D", "C.D", "c.⁠d", "c⁠.⁠d", "1⁠,⁠ 2",
+  "a:b", "a.b", "a'b", "a,b", "1,2", "1.2", "1'2", "1:2", "1;2", "a'1",
+  "1a'b", "א'", "א\"א", "א'א", "א\"a",
+  "a_a", "a_1", "1_a", "〱_〱", "__a", "〱〱a",
+  "\u{1f1e6}\u{1f1e7}\u{1f1e8}b",
+  "a\u{1f1e6}\u{1f1e7}\u{1f1e8}\u{1f1e9}b",
+  "a\u{1f1e6}\u{1f1e7}‍\u{1f1e8}b",
+  "a\u{1f1e6}‍\u{1f1e7}\u{1f1e8}b",
+  "a\u{1f1e6}\u{1f1e7}\u{1f1e8}̈\u{1f1e9}b",
+  "\u{1f476}\u{1f3ff}\u{1f476}",
+  "\u{1f6d1}‍\u{1f6d1}", "a‍\u{1f6d1}",
+  "✂‍✂", "a‍✂",
+  "\u{1f476}\u{1f3ff}̈‍\u{1f476}\u{1f3ff}",
+  "\u{1f6d1}\u{1f3ff}", "‍\u{1f6d1}\u{1f3ff}",
+  "‍\u{1f6d1}", "‍‍\u{1f6d1}",
+  "a  b", "a ‍\u{1f6d1}", "a   ̈b", "  ̈ ",
+  "a ⁠ b", "　　a", "   a",
+  "a\r\nb", "\r\r\n\n", "ä\r", "\n̈̈",
+  "ⓂⓂa", "aⓂb",
+];
+
+// The linear congruential generator of C's rand(), good enough to pick
+// samples with.
+let seed = 29;
+function random(n) {
+  seed = (seed * 1103515245 + 12345) & 0x7fffffff;
+  return seed % n;
+}
+
+const strings = [];
+for (const a of SAMPLES) {
+  for (const b of SAMPLES) {
+    strings.push([a, b]);
+    strings.push([a, 0x0308, b]);
+  }
+}
+for (const s of EXTRA_SAMPLES) {
+  strings.push([...s].map((c) => c.codePointAt(0)));
+}
+for (let i = 0; i < RANDOM_STRINGS; i++) {
+  const length = 3 + random(5);
+  strings.push(
+    Array.from({ length }, () => RANDOM_SAMPLES[random(RANDOM_SAMPLES.length)]),
+  );
+}
+
+const segmenter = new Intl.Segmenter("und", { granularity: "word" });
+const hex = (cp) => cp.toString(16).toUpperCase().padStart(4, "0");
+const { icu, unicode } = process.versions;
+let out = `# word-break-icu.txt: ICU ${icu}, Unicode ${unicode}
+#
+# Generated by \`node support/gen-word-break-icu.mjs\`: the word boundaries
+# ICU finds in sample strings, in the format of the UCD's WordBreakTest.txt.
+# This is not that file; see support/gen-break-tables.py for it.
+#
+`;
+for (const cps of strings) {
+  const s = String.fromCodePoint(...cps);
+  // Boundaries as code point offsets; Intl.Segmenter gives UTF-16 ones.
+  const at = new Set([cps.length]);
+  for (const { index } of segmenter.segment(s)) {
+    at.add([...s.slice(0, index)].length);
+  }
+  const fields = ["÷"];
+  cps.forEach((cp, i) => fields.push(hex(cp), at.has(i + 1) ? "÷" : "×"));
+  out += fields.join(" ") + "\n";
+}
+writeFileSync(FIXTURE, out);
+console.error(`wrote tests/fixtures/word-break-icu.txt (${strings.length} cases)`);
@@ -0,0 +1,95 @@
+//! AT&T text tests: `transducer::att` reading the hand-written fixtures in
+//! tests/fixtures/*.att, and writing the checked-in THFST fixtures.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use divvun_fst::speller::{HfstSpeller, Speller};
+use divvun_fst::transducer::att;
+use divvun_fst::transducer::lookup::lookup;
+use divvun_fst::transducer::paths::{PathsConfig, paths};
+use divvun_fst::transducer::thfst::{MemoryThfstTransducer, MmapThfstTransducer};
+use divvun_fst::transducer::{Transducer, TransducerLoader};
+use divvun_fst::vfs::Fs;
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures")).join(name)
+}
+
+fn parse(name: &str) -> MemoryThfstTransducer {
+    att::parse(&std::fs::read_to_string(fixture(name)).unwrap()).unwrap()
+}
+
+fn words(transducer: &MemoryThfstTransducer) -> Vec<(String, f32)> {
+    paths(transducer, PathsConfig::default())
+        .map(|p| (p.output.to_string(), p.weight.0))
+        .collect()
+}
+
+#[test]
+fn hand_written_lexicon_checks_flags() {
+    let lexicon = parse("flag-lexicon.att");
+    assert_eq!(
+        words(&lexicon),
+        [
+            ("cat".to_string(), 0.0),
+            ("cart".to_string(), 0.0),
+            ("car".to_string(), 0.5)
+        ]
+    );
+    // @R.f.1@ without @P.f.1@ before it
+    assert!(lookup(&lexicon, "rat").is_empty());
+}
+
+#[test]
+fn hand_written_transducers_make_a_speller() {
+    let speller = HfstSpeller::new(
+        parse("substitution-errmodel.att"),
+        parse("flag-lexicon.att"),
+    );
+    assert!(Arc::clone(&speller).is_correct("cart"));
+    assert!(!Arc::clone(&speller).is_correct("rat"));
+    let suggestions: Vec<_> = speller
+        .suggest("crt")
+        .into_iter()
+        .map(|s| (s.value.to_string(), s.weight.0))
+        .collect();
+    assert_eq!(suggestions[0], ("cat".to_string(), 1.0));
+}
+
+#[test]
+fn fixtures_are_written_as_the_search_reads_them() {
+    let eps = MmapThfstTransducer::from_path(&Fs, fixture("eps-lexicon.thfst")).unwrap();
+    assert_eq!(
+        att::to_string(&eps),
+        "0\t1\tc\tc\t0\n\
+         1\t2\ta\ta\t0\n\
+         2\t3\tt\tt\t0\n\
+         3\t4\t@0@\t+N\t0\n\
+         3\t5\t@0@\t+V\t0\n\
+         4\t0\n\
+         5\t2\n"
+    );
+}
+
+#[test]
+fn written_text_reads_back() {
+    // The order of equally weighted paths depends on the symbols' numbers,
+    // which reading the text back may change.
+    fn all<T: Transducer>(transducer: &T) -> Vec<(String, String, f32)> {
+        let mut all: Vec<_> = paths(transducer, PathsConfig::default())
+            .map(|p| (p.input.to_string(), p.output.to_string(), p.weight.0))
+            .collect();
+        all.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        all
+    }
+    for name in ["eps-lexicon.thfst", "flag-lexicon.thfst"] {
+        let transducer = MmapThfstTransducer::from_path(&Fs, fixture(name)).unwrap();
+        let reread = att::parse(&att::to_string(&transducer)).unwrap();
+        assert_eq!(all(&reread), all(&transducer), "{name}");
+    }
+
+    let lexicon = parse("flag-lexicon.att");
+    let reread = att::parse(&att::to_string(&lexicon)).unwrap();
+    assert_eq!(all(&reread), all(&lexicon));
+}
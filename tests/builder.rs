@@ -0,0 +1,113 @@
+//! Builder tests: transducers built in memory with
+//! `transducer::builder::TransducerBuilder`, written out as THFST and HFST
+//! through `transducer::convert` and read back.
+
+use std::path::Path;
+
+use divvun_fst::transducer::att;
+use divvun_fst::transducer::builder::TransducerBuilder;
+use divvun_fst::transducer::convert::ConvertFile;
+use divvun_fst::transducer::hfst::HfstTransducer;
+use divvun_fst::transducer::lookup::lookup;
+use divvun_fst::transducer::paths::{PathsConfig, paths};
+use divvun_fst::transducer::thfst::{MemoryThfstTransducer, MmapThfstTransducer};
+use divvun_fst::transducer::{Transducer, TransducerLoader};
+use divvun_fst::types::{FlagDiacriticOperator, Weight};
+use divvun_fst::vfs::Fs;
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures")).join(name)
+}
+
+fn all<T: Transducer>(transducer: &T) -> Vec<(String, String, f32)> {
+    paths(transducer, PathsConfig::default())
+        .map(|p| (p.input.to_string(), p.output.to_string(), p.weight.0))
+        .collect()
+}
+
+/// cat+N, and car+N at 0.5 with a flag setting the plural, which cars+N
+/// requires.
+fn lexicon() -> MemoryThfstTransducer {
+    let mut builder = TransducerBuilder::new();
+    let arc = |builder: &mut TransducerBuilder, from, to, input: &str, output: &str, w| {
+        let input = builder.symbol(input);
+        let output = builder.symbol(output);
+        builder.add_arc(from, to, input, output, Weight(w));
+    };
+    let c = builder.add_state();
+    let a = builder.add_state();
+    let end = builder.add_state();
+    arc(&mut builder, 0, c, "c", "c", 0.0);
+    arc(&mut builder, c, a, "a", "a", 0.0);
+    let t = builder.add_state();
+    arc(&mut builder, a, t, "t", "t", 0.0);
+    arc(&mut builder, t, end, "@_EPSILON_SYMBOL_@", "+N", 0.0);
+
+    let r = builder.add_state();
+    let set = builder.flag(FlagDiacriticOperator::PositiveSet, "NUM", "PL");
+    let require = builder.flag(FlagDiacriticOperator::Require, "NUM", "PL");
+    let flagged = builder.add_state();
+    let s = builder.add_state();
+    arc(&mut builder, a, r, "r", "r", 0.5);
+    arc(&mut builder, r, end, "@_EPSILON_SYMBOL_@", "+N", 0.0);
+    builder.add_arc(r, flagged, set, set, Weight::ZERO);
+    builder.add_arc(flagged, s, require, require, Weight::ZERO);
+    arc(&mut builder, s, end, "s", "+N", 0.0);
+    builder.set_final(end, Weight::ZERO);
+    builder.build().unwrap()
+}
+
+#[test]
+fn built_transducer_checks_flags() {
+    let lexicon = lexicon();
+    assert_eq!(
+        all(&lexicon),
+        [
+            ("cat".to_string(), "cat+N".to_string(), 0.0),
+            ("car".to_string(), "car+N".to_string(), 0.5),
+            ("cars".to_string(), "car+N".to_string(), 0.5),
+        ]
+    );
+    assert_eq!(lookup(&lexicon, "cars").len(), 1);
+}
+
+#[test]
+fn built_transducer_writes_thfst() {
+    let lexicon = lexicon();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lexicon");
+    MmapThfstTransducer::convert_file(&lexicon, &path).unwrap();
+
+    let read = MmapThfstTransducer::from_path(&Fs, path.with_extension("thfst")).unwrap();
+    assert_eq!(all(&read), all(&lexicon));
+    assert_eq!(att::to_string(&read), att::to_string(&lexicon));
+}
+
+#[test]
+fn built_transducer_writes_hfst() {
+    let lexicon = lexicon();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lexicon");
+    HfstTransducer::convert_file(&lexicon, &path).unwrap();
+
+    let read = HfstTransducer::from_path(&Fs, path.with_extension("hfst")).unwrap();
+    assert_eq!(all(&read), all(&lexicon));
+    assert_eq!(att::to_string(&read), att::to_string(&lexicon));
+}
+
+#[test]
+fn fixtures_convert_to_hfst_and_back() {
+    for name in ["eps-lexicon.thfst", "flag-lexicon.thfst", "mutator.thfst"] {
+        let thfst = MmapThfstTransducer::from_path(&Fs, fixture(name)).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(name);
+        HfstTransducer::convert_file(&thfst, &path).unwrap();
+        let hfst = HfstTransducer::from_path(&Fs, path.with_extension("hfst")).unwrap();
+        assert_eq!(att::to_string(&hfst), att::to_string(&thfst), "{name}");
+
+        let path = dir.path().join("back");
+        MmapThfstTransducer::convert_file(&hfst, &path).unwrap();
+        let back = MmapThfstTransducer::from_path(&Fs, path.with_extension("thfst")).unwrap();
+        assert_eq!(att::to_string(&back), att::to_string(&thfst), "{name}");
+    }
+}
@@ -0,0 +1,172 @@
+//! Text-level checks: `check_text` over the fixture speller.
+//!
+//! The fixture lexicon accepts "cat", "car", "cart", "care" and "cär"; see
+//! `tests/README.md`.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use divvun_fst::check::{CheckConfig, Diagnostic, DiagnosticKind, check_text};
+use divvun_fst::speller::{HfstSpeller, Speller};
+use divvun_fst::tokenizer::markup::Markup;
+use divvun_fst::transducer::TransducerLoader;
+use divvun_fst::transducer::thfst::MmapThfstTransducer;
+use divvun_fst::vfs::Fs;
+
+fn test_speller() -> Arc<dyn Speller + Send + Sync> {
+    let base = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"));
+    let mutator = MmapThfstTransducer::from_path(&Fs, base.join("mutator.thfst")).unwrap();
+    let lexicon = MmapThfstTransducer::from_path(&Fs, base.join("lexicon.thfst")).unwrap();
+    HfstSpeller::new(mutator, lexicon)
+}
+
+fn check(text: &str) -> Vec<Diagnostic> {
+    check_text(test_speller(), text, &CheckConfig::default())
+}
+
+fn kinds(diagnostics: &[Diagnostic]) -> Vec<(DiagnosticKind, &str)> {
+    diagnostics
+        .iter()
+        .map(|d| (d.kind, d.text.as_str()))
+        .collect()
+}
+
+#[test]
+fn clean_text_has_no_diagnostics() {
+    assert_eq!(check("Cat car, care. Cart!"), vec![]);
+}
+
+#[test]
+fn repeated_word_spans_the_second_occurrence() {
+    let text = "Cat car car.";
+    let diagnostics = check(text);
+    assert_eq!(
+        kinds(&diagnostics),
+        [(DiagnosticKind::RepeatedWord, " car")]
+    );
+
+    // Applying the fix leaves the sentence intact.
+    let d = &diagnostics[0];
+    let mut fixed = text.to_string();
+    fixed.replace_range(d.span.clone(), &d.suggestions[0]);
+    assert_eq!(fixed, "Cat car.");
+}
+
+// Only adjacent words count, and case does not hide a repetition; punctuation
+// between two equal words does.
+#[test]
+fn repeated_word_ignores_case_but_not_punctuation() {
+    assert_eq!(
+        kinds(&check("CAR car cat")),
+        [(DiagnosticKind::RepeatedWord, " car")]
+    );
+    assert_eq!(check("Car, car. Car"), vec![]);
+}
+
+#[test]
+fn lowercase_sentence_start() {
+    let diagnostics = check("cat car. care! «cart»");
+    assert_eq!(
+        kinds(&diagnostics),
+        [
+            (DiagnosticKind::LowercaseSentenceStart, "cat"),
+            (DiagnosticKind::LowercaseSentenceStart, "care"),
+            (DiagnosticKind::LowercaseSentenceStart, "cart"),
+        ]
+    );
+    assert_eq!(diagnostics[0].suggestions, ["Cat"]);
+    assert_eq!(diagnostics[2].span, 17..21);
+}
+
+// A full stop after an abbreviation of the text's language, or a line break
+// alone, does not end the sentence.
+#[test]
+fn abbreviations_do_not_end_sentences() {
+    let config = CheckConfig {
+        spelling: false,
+        language: "en".into(),
+        ..CheckConfig::default()
+    };
+    let diagnostics = check_text(test_speller(), "Dr. smith, e.g. care. Car\ncart", &config);
+    assert_eq!(diagnostics, vec![]);
+    let diagnostics = check_text(test_speller(), "Dr. smith, e.g. care. car", &config);
+    assert_eq!(
+        kinds(&diagnostics),
+        [(DiagnosticKind::LowercaseSentenceStart, "car")]
+    );
+
+    // Without the language, "Dr." and "e.g." end sentences.
+    let config = CheckConfig {
+        language: "".into(),
+        ..config
+    };
+    let diagnostics = check_text(test_speller(), "Dr. smith, e.g. care", &config);
+    assert_eq!(
+        kinds(&diagnostics),
+        [
+            (DiagnosticKind::LowercaseSentenceStart, "smith"),
+            (DiagnosticKind::LowercaseSentenceStart, "care"),
+        ]
+    );
+}
+
+#[test]
+fn all_caps_mid_sentence() {
+    let diagnostics = check("Cat CARE car. CART car");
+    assert_eq!(
+        kinds(&diagnostics),
+        [(DiagnosticKind::AllCapsMidSentence, "CARE")]
+    );
+    assert_eq!(diagnostics[0].suggestions, ["care"]);
+    assert_eq!(diagnostics[0].span, 4..8);
+}
+
+#[test]
+fn spelling_errors_alongside_rules() {
+    let diagnostics = check("kat cat cat");
+    assert_eq!(
+        kinds(&diagnostics),
+        [
+            (DiagnosticKind::LowercaseSentenceStart, "kat"),
+            (DiagnosticKind::Spelling, "kat"),
+            (DiagnosticKind::RepeatedWord, " cat"),
+        ]
+    );
+    assert_eq!(diagnostics[1].suggestions[0], "cat");
+}
+
+#[test]
+fn checks_can_be_switched_off() {
+    let config = CheckConfig {
+        repeated_words: false,
+        sentence_case: false,
+        mid_sentence_caps: false,
+        ..CheckConfig::default()
+    };
+    let diagnostics = check_text(test_speller(), "kat cat cat CARE", &config);
+    assert_eq!(kinds(&diagnostics), [(DiagnosticKind::Spelling, "kat")]);
+}
+
+// Code and URLs are not checked and keep the words around them apart; a tag
+// is invisible, so the words around it still repeat. Each list item starts a
+// sentence.
+#[test]
+fn markdown_checks_prose_only() {
+    let text = "# Car\n\ncat `kat kat` car <https://kat.example> car <b>car</b> cart.\n\n- care\n";
+    let config = CheckConfig {
+        markup: Markup::Markdown,
+        ..CheckConfig::default()
+    };
+    let diagnostics = check_text(test_speller(), text, &config);
+    assert_eq!(
+        kinds(&diagnostics),
+        [
+            (DiagnosticKind::LowercaseSentenceStart, "cat"),
+            (DiagnosticKind::RepeatedWord, "car"),
+            (DiagnosticKind::LowercaseSentenceStart, "care"),
+        ]
+    );
+    for d in &diagnostics {
+        assert_eq!(&text[d.span.clone()], d.text);
+    }
+}
@@ -12,7 +12,7 @@
 use std::path::Path;
 use std::sync::Arc;
 
-use divvun_fst::generator::GeneratorConfig;
+use divvun_fst::generator::{GeneratorConfig, Paradigm, ParadigmFormat};
 use divvun_fst::speller::{HfstSpeller, Speller};
 use divvun_fst::transducer::TransducerLoader;
 use divvun_fst::transducer::thfst::MmapThfstTransducer;
@@ -58,6 +58,16 @@ fn write_trans_boundary(buf: &mut Vec<u8>) {
 }
 
 fn build_alphabet_json(symbols: &[&str]) -> String {
+    build_wildcard_alphabet_json(symbols, None, None)
+}
+
+/// Alphabet builder with the identity and unknown symbols at the given
+/// indices.
+fn build_wildcard_alphabet_json(
+    symbols: &[&str],
+    identity: Option<usize>,
+    unknown: Option<usize>,
+) -> String {
     let mut key_table_entries = Vec::new();
     let mut s2s_entries = Vec::new();
     for (i, sym) in symbols.iter().enumerate() {
@@ -67,12 +77,15 @@ fn build_alphabet_json(symbols: &[&str]) -> String {
             s2s_entries.push(format!("\"{}\":{}", escaped, i));
         }
     }
+    let index = |i: Option<usize>| i.map_or("null".to_string(), |i| i.to_string());
     format!(
-        r#"{{"key_table":[{}],"initial_symbol_count":{},"flag_state_size":0,"length":{},"string_to_symbol":{{{}}},"operations":{{}},"identity_symbol":null,"unknown_symbol":null}}"#,
+        r#"{{"key_table":[{}],"initial_symbol_count":{},"flag_state_size":0,"length":{},"string_to_symbol":{{{}}},"operations":{{}},"identity_symbol":{},"unknown_symbol":{}}}"#,
         key_table_entries.join(","),
         symbols.len(),
         symbols.len(),
         s2s_entries.join(","),
+        index(identity),
+        index(unknown),
     )
 }
 
@@ -107,6 +120,11 @@ fn write_empties(buf: &mut Vec<u8>, n: usize) {
 ///                                      (S5) FINAL
 /// ```
 fn build_cat_lexicon(dir: &Path) {
+    build_weighted_cat_lexicon(dir, 0.0, 0.0);
+}
+
+/// [`build_cat_lexicon`] with weights on the `+Sg` and `+Pl` arcs.
+fn build_weighted_cat_lexicon(dir: &Path, sg_weight: f32, pl_weight: f32) {
     let symbols = &["@_EPSILON_SYMBOL_@", "c", "a", "t", "s", "+N", "+Sg", "+Pl"];
     let n = symbols.len(); // 8
     let slots = n + 1; // 9
@@ -177,13 +195,104 @@ fn build_cat_lexicon(dir: &Path) {
     write_trans_entry(&mut tr, 3, 3, 27, 0.0); // [2]
     write_trans_entry(&mut tr, 5, 0, 36, 0.0); // [3]
     write_trans_boundary(&mut tr); // [4]
-    write_trans_entry(&mut tr, 6, 0, 45, 0.0); // [5]
+    write_trans_entry(&mut tr, 6, 0, 45, sg_weight); // [5]
     write_trans_boundary(&mut tr); // [6]
-    write_trans_entry(&mut tr, 7, 4, 54, 0.0); // [7]
+    write_trans_entry(&mut tr, 7, 4, 54, pl_weight); // [7]
 
     write_thfst(dir, &build_alphabet_json(symbols), &idx, &tr);
 }
 
+/// Generator for names, which reads any characters outside its alphabet
+/// with its wildcards (input = name+tags, output = surface):
+///   input "X+N+Sg"  → output "X"
+///   input "X+N+Gen" → output "Xs"
+///
+/// ```text
+/// Alphabet: [eps, @_IDENTITY_SYMBOL_@, @_UNKNOWN_SYMBOL_@, a, s, +N, +Sg, +Gen]
+///           (n = 8 symbols, 9 slots per state)
+///
+///            UNKNOWN:UNKNOWN/1       IDENTITY:IDENTITY/0, a:a/0
+/// (S0)-------------------------->(S1)<-----.  (self-loops)
+///    `---------a:a/0------------->  |  `----'
+///                                  +N/eps
+///                                   v
+///                                  (S2)--+Sg/eps-->(S3) FINAL
+///                                   `----+Gen/s--->(S3)
+/// ```
+///
+/// The first character of a name is read by `@_UNKNOWN_@` only, so a name
+/// starting outside the alphabet costs 1, and one starting with `a` costs
+/// nothing. `s` is in the alphabet but has no arcs on the input tape: the
+/// wildcards do not read it, and a name with an `s` generates nothing.
+fn build_identity_generator(dir: &Path) {
+    let symbols = &[
+        "@_EPSILON_SYMBOL_@",
+        "@_IDENTITY_SYMBOL_@",
+        "@_UNKNOWN_SYMBOL_@",
+        "a",
+        "s",
+        "+N",
+        "+Sg",
+        "+Gen",
+    ];
+    let slots = symbols.len() + 1; // 9
+
+    let mut idx = Vec::new();
+
+    // S0 at idx 0
+    write_index_empty(&mut idx); // [0] not final
+    write_index_empty(&mut idx); // [1] eps
+    write_index_empty(&mut idx); // [2] identity
+    write_index_entry(&mut idx, 2, TARGET_TABLE + 0); // [3] unknown -> trans[0]
+    write_index_entry(&mut idx, 3, TARGET_TABLE + 2); // [4] a -> trans[2]
+    write_empties(&mut idx, slots - 5);
+
+    // S1 at idx 9 (inside the name)
+    write_index_empty(&mut idx); // [9]
+    write_index_empty(&mut idx); // [10] eps
+    write_index_entry(&mut idx, 1, TARGET_TABLE + 4); // [11] identity -> trans[4]
+    write_index_empty(&mut idx); // [12] unknown
+    write_index_entry(&mut idx, 3, TARGET_TABLE + 6); // [13] a -> trans[6]
+    write_index_empty(&mut idx); // [14] s
+    write_index_entry(&mut idx, 5, TARGET_TABLE + 8); // [15] +N -> trans[8]
+    write_empties(&mut idx, slots - 7);
+
+    // S2 at idx 18 (after "+N")
+    write_empties(&mut idx, slots - 2); // [18]..[24]
+    write_index_entry(&mut idx, 6, TARGET_TABLE + 10); // [25] +Sg -> trans[10]
+    write_index_entry(&mut idx, 7, TARGET_TABLE + 12); // [26] +Gen -> trans[12]
+
+    // S3 at idx 27 (FINAL)
+    write_index_final(&mut idx, 0.0); // [27]
+    write_empties(&mut idx, slots - 1);
+
+    let mut tr = Vec::new();
+    write_trans_entry(&mut tr, 2, 2, 9, 1.0); // [0] UNKNOWN:UNKNOWN -> S1
+    write_trans_boundary(&mut tr); // [1]
+    write_trans_entry(&mut tr, 3, 3, 9, 0.0); // [2] a:a -> S1
+    write_trans_boundary(&mut tr); // [3]
+    write_trans_entry(&mut tr, 1, 1, 9, 0.0); // [4] IDENTITY:IDENTITY -> S1
+    write_trans_boundary(&mut tr); // [5]
+    write_trans_entry(&mut tr, 3, 3, 9, 0.0); // [6] a:a -> S1
+    write_trans_boundary(&mut tr); // [7]
+    write_trans_entry(&mut tr, 5, 0, 18, 0.0); // [8] +N:eps -> S2
+    write_trans_boundary(&mut tr); // [9]
+    write_trans_entry(&mut tr, 6, 0, 27, 0.0); // [10] +Sg:eps -> S3
+    write_trans_boundary(&mut tr); // [11]
+    write_trans_entry(&mut tr, 7, 4, 27, 0.0); // [12] +Gen:s -> S3
+
+    write_thfst(
+        dir,
+        &build_wildcard_alphabet_json(symbols, Some(1), Some(2)),
+        &idx,
+        &tr,
+    );
+}
+
+fn fixtures_dir() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"))
+}
+
 /// Trivial single-state mutator (identity-only) so we can construct a
 /// `HfstSpeller` for the generation API. The mutator is unused by the
 /// `generate` walk — it only touches the lexicon — but the speller
@@ -239,6 +348,8 @@ fn generates_cat_singular_and_plural() {
     );
     for r in &results {
         assert_eq!(r.weight, Weight(0.0));
+        assert_eq!(r.tags.concat(), r.analysis, "tags of {r:?}");
+        assert_eq!(r.tags.len(), 2, "tags of {r:?}");
     }
 }
 
@@ -275,6 +386,184 @@ fn config_max_results_caps_output() {
     assert_eq!(speller.generate_with_config("cat", &cfg).len(), 1);
 }
 
+#[test]
+fn results_come_out_cheapest_first() {
+    let lex_dir = tempfile::tempdir().unwrap();
+    let mut_dir = tempfile::tempdir().unwrap();
+    // The plural is queued after the singular, but is the cheaper form.
+    build_weighted_cat_lexicon(lex_dir.path(), 2.0, 1.0);
+    build_noop_mutator(mut_dir.path());
+    let speller = build_test_speller(lex_dir.path(), mut_dir.path());
+
+    let forms = |cfg: &GeneratorConfig| -> Vec<(String, f32)> {
+        speller
+            .clone()
+            .generate_with_config("cat", cfg)
+            .iter()
+            .map(|r| (r.surface.to_string(), r.weight.0))
+            .collect()
+    };
+
+    let all = GeneratorConfig::default();
+    assert_eq!(
+        forms(&all),
+        [("cats".to_string(), 1.0), ("cat".to_string(), 2.0)]
+    );
+
+    // Stopping early keeps the cheapest form, not the first one found.
+    let one = GeneratorConfig {
+        max_results: Some(1),
+        ..GeneratorConfig::default()
+    };
+    assert_eq!(forms(&one), [("cats".to_string(), 1.0)]);
+
+    let light = GeneratorConfig {
+        max_weight: Weight(1.5),
+        ..GeneratorConfig::default()
+    };
+    assert_eq!(forms(&light), [("cats".to_string(), 1.0)]);
+}
+
+#[test]
+fn tag_patterns_select_forms() {
+    let lex_dir = tempfile::tempdir().unwrap();
+    let mut_dir = tempfile::tempdir().unwrap();
+    build_cat_lexicon(lex_dir.path());
+    build_noop_mutator(mut_dir.path());
+    let speller = build_test_speller(lex_dir.path(), mut_dir.path());
+
+    let generate = |pattern: &str| {
+        let cfg = GeneratorConfig {
+            tags: Some(pattern.into()),
+            ..GeneratorConfig::default()
+        };
+        sorted_pairs(&speller.clone().generate_with_config("cat", &cfg))
+    };
+
+    assert_eq!(
+        generate("+N+Pl"),
+        vec![("cats".to_string(), "+N+Pl".to_string())]
+    );
+    assert_eq!(generate("+N*").len(), 2);
+    assert_eq!(
+        generate("*+Sg"),
+        vec![("cat".to_string(), "+N+Sg".to_string())]
+    );
+    assert!(generate("+N").is_empty());
+    assert!(generate("+V*").is_empty());
+}
+
+#[test]
+fn paradigm_groups_forms_by_tags() {
+    let lex_dir = tempfile::tempdir().unwrap();
+    let mut_dir = tempfile::tempdir().unwrap();
+    build_weighted_cat_lexicon(lex_dir.path(), 2.0, 1.0);
+    build_noop_mutator(mut_dir.path());
+    let speller = build_test_speller(lex_dir.path(), mut_dir.path());
+
+    let paradigm = Paradigm::generate(speller.lexicon(), "cat", &GeneratorConfig::default());
+    assert_eq!(paradigm.lemma, "cat");
+    assert_eq!(paradigm.groups.len(), 1);
+    assert_eq!(paradigm.groups[0].pos, "+N");
+    let rows: Vec<(String, String)> = paradigm.groups[0]
+        .rows
+        .iter()
+        .map(|r| (r.tags.concat(), r.forms[0].surface.to_string()))
+        .collect();
+    // Cheapest first: the plural is the cheaper form here.
+    assert_eq!(
+        rows,
+        [
+            ("+Pl".to_string(), "cats".to_string()),
+            ("+Sg".to_string(), "cat".to_string())
+        ]
+    );
+    assert_eq!(
+        paradigm.render(ParadigmFormat::Tsv),
+        "cat\t+N\t+Pl\tcats\t1\ncat\t+N\t+Sg\tcat\t2\n"
+    );
+
+    assert!(Paradigm::generate(speller.lexicon(), "dog", &GeneratorConfig::default()).is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Identity / unknown fixture
+// ---------------------------------------------------------------------------
+
+fn identity_generator_speller() -> Arc<HfstSpeller<MmapThfstTransducer, MmapThfstTransducer>> {
+    let mut_dir = tempfile::tempdir().unwrap();
+    build_noop_mutator(mut_dir.path());
+    build_test_speller(
+        &fixtures_dir().join("identity-generator.thfst"),
+        mut_dir.path(),
+    )
+}
+
+fn forms(results: &[divvun_fst::generator::GenerationResult]) -> Vec<(String, String, f32)> {
+    results
+        .iter()
+        .map(|r| (r.surface.to_string(), r.analysis.to_string(), r.weight.0))
+        .collect()
+}
+
+#[test]
+fn identity_and_unknown_pass_foreign_characters_through() {
+    let speller = identity_generator_speller();
+
+    // "Z" is read by the unknown arc, "o" and "ë" by the identity loop.
+    assert_eq!(
+        forms(&speller.clone().generate("Zoë")),
+        [
+            ("Zoë".to_string(), "+N+Sg".to_string(), 1.0),
+            ("Zoës".to_string(), "+N+Gen".to_string(), 1.0),
+        ]
+    );
+    // Digits are as foreign as letters.
+    assert_eq!(
+        forms(&speller.clone().generate("a42")),
+        [
+            ("a42".to_string(), "+N+Sg".to_string(), 0.0),
+            ("a42s".to_string(), "+N+Gen".to_string(), 0.0),
+        ]
+    );
+}
+
+#[test]
+fn wildcards_do_not_read_characters_in_the_alphabet() {
+    let speller = identity_generator_speller();
+
+    // "s" has a symbol of its own, and no arc reads it on the input tape.
+    assert!(speller.clone().generate("as").is_empty());
+    // Past the first character, the identity loop reads "Z" for nothing.
+    assert_eq!(
+        forms(&speller.generate("aZ")),
+        [
+            ("aZ".to_string(), "+N+Sg".to_string(), 0.0),
+            ("aZs".to_string(), "+N+Gen".to_string(), 0.0),
+        ]
+    );
+}
+
+#[test]
+fn lexicons_without_wildcards_reject_foreign_characters() {
+    let lex_dir = tempfile::tempdir().unwrap();
+    let mut_dir = tempfile::tempdir().unwrap();
+    build_cat_lexicon(lex_dir.path());
+    build_noop_mutator(mut_dir.path());
+    let speller = build_test_speller(lex_dir.path(), mut_dir.path());
+
+    assert!(speller.generate("cät").is_empty());
+}
+
+#[test]
+#[ignore]
+fn rebuild_fixtures() {
+    let dir = fixtures_dir().join("identity-generator.thfst");
+    std::fs::create_dir_all(&dir).unwrap();
+    build_identity_generator(&dir);
+    eprintln!("wrote {}", dir.display());
+}
+
 // ---------------------------------------------------------------------------
 // Real Sámi: `se.bhfst`
 // ---------------------------------------------------------------------------
@@ -332,6 +621,7 @@ fn smoke_sme_generator_hfstol() {
         max_weight: Weight(120.0),
         max_results: Some(64),
         max_iterations: Some(500_000),
+        tags: None,
     };
 
     for lemma in ["biila", "guolli", "dieđihit"] {
@@ -0,0 +1,77 @@
+//! Hunspell import tests: the dictionary in tests/fixtures/hunspell, read
+//! with `transducer::hunspell` and compiled into a speller.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use divvun_fst::archive::meta::SpellerMetadata;
+use divvun_fst::speller::{HfstSpeller, Speller};
+use divvun_fst::transducer::hunspell::{Dictionary, ErrorModelConfig};
+
+fn dictionary() -> Dictionary {
+    let dir = Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/hunspell"
+    ));
+    Dictionary::from_bytes(
+        &std::fs::read(dir.join("en.dic")).unwrap(),
+        &std::fs::read(dir.join("en.aff")).unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn affixes_are_expanded() {
+    let words = dictionary().words();
+    let expected = [
+        "fly", "flies", "play", "plays", "played", "lock", "locked", "unlock", "unlocked", "teach",
+        "teacher", "teachers", "unkind", "phone", "phones", "station", "stations", "café", "cafés",
+    ];
+    for word in expected {
+        assert!(words.weight(word).is_some(), "{word}");
+    }
+    assert_eq!(words.len(), expected.len());
+}
+
+#[test]
+fn dictionary_makes_a_speller() {
+    let dictionary = dictionary();
+    let words = dictionary.words();
+    let errmodel = dictionary
+        .error_model(&words, &ErrorModelConfig::default())
+        .unwrap();
+    let speller = HfstSpeller::new(errmodel, words.acceptor().unwrap());
+
+    assert!(Arc::clone(&speller).is_correct("unlocked"));
+    // NEEDAFFIX and FORBIDDENWORD
+    assert!(!Arc::clone(&speller).is_correct("kind"));
+    assert!(!Arc::clone(&speller).is_correct("damn"));
+
+    let best = |word: &str| {
+        Arc::clone(&speller)
+            .suggest(word)
+            .first()
+            .map(|s| s.value.to_string())
+    };
+    // REP
+    assert_eq!(best("fone").as_deref(), Some("phone"));
+    assert_eq!(best("stashun").as_deref(), Some("station"));
+    // MAP
+    assert_eq!(best("cafe").as_deref(), Some("café"));
+    // KEY, d being next to s
+    assert_eq!(best("playd").as_deref(), Some("plays"));
+    // TRY
+    assert_eq!(best("stattion").as_deref(), Some("station"));
+}
+
+#[test]
+fn metadata_names_the_transducers() {
+    let dictionary = dictionary();
+    let locale = dictionary.language().unwrap();
+    assert_eq!(locale, "en_GB");
+    let json = serde_json::to_string(&dictionary.metadata(locale)).unwrap();
+    let metadata: SpellerMetadata = serde_json::from_str(&json).unwrap();
+    assert_eq!(metadata.info().locale(), "en_GB");
+    assert_eq!(metadata.acceptor().id(), "acceptor.default.thfst");
+    assert_eq!(metadata.errmodel().id(), "errmodel.default.thfst");
+}
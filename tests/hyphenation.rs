@@ -0,0 +1,211 @@
+//! Hyphenation tests: `Hyphenator::hyphenate` over a tiny synthetic
+//! hyphenator FST.
+
+use std::path::Path;
+
+use divvun_fst::hyphenator::{Hyphenate, Hyphenation, HyphenationPoint, Hyphenator};
+use divvun_fst::transducer::TransducerLoader;
+use divvun_fst::transducer::thfst::MmapThfstTransducer;
+use divvun_fst::types::Weight;
+use divvun_fst::vfs::Fs;
+
+const TARGET_TABLE: u32 = 2_147_483_648; // 0x80000000
+
+// ---------------------------------------------------------------------------
+// THFST builder helpers (mirror tests/speller_integration.rs)
+// ---------------------------------------------------------------------------
+
+fn write_index_entry(buf: &mut Vec<u8>, input_symbol: u16, target: u32) {
+    buf.extend_from_slice(&input_symbol.to_le_bytes());
+    buf.extend_from_slice(&0u16.to_le_bytes());
+    buf.extend_from_slice(&target.to_le_bytes());
+}
+
+fn write_index_final(buf: &mut Vec<u8>, weight: f32) {
+    buf.extend_from_slice(&0xFFFFu16.to_le_bytes());
+    buf.extend_from_slice(&0u16.to_le_bytes());
+    buf.extend_from_slice(&weight.to_bits().to_le_bytes());
+}
+
+fn write_index_empty(buf: &mut Vec<u8>) {
+    write_index_entry(buf, 0xFFFF, 0xFFFFFFFF);
+}
+
+fn write_trans_entry(buf: &mut Vec<u8>, input: u16, output: u16, target: u32, weight: f32) {
+    buf.extend_from_slice(&input.to_le_bytes());
+    buf.extend_from_slice(&output.to_le_bytes());
+    buf.extend_from_slice(&target.to_le_bytes());
+    buf.extend_from_slice(&weight.to_bits().to_le_bytes());
+}
+
+/// Sentinel transition record that terminates an eps/symbol walk. Both
+/// `input_symbol` and `target` decode to None.
+fn write_trans_boundary(buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&0xFFFFu16.to_le_bytes());
+    buf.extend_from_slice(&0xFFFFu16.to_le_bytes());
+    buf.extend_from_slice(&0xFFFFFFFFu32.to_le_bytes());
+    buf.extend_from_slice(&0u32.to_le_bytes());
+}
+
+fn build_alphabet_json(symbols: &[&str]) -> String {
+    let mut key_table_entries = Vec::new();
+    let mut s2s_entries = Vec::new();
+    for (i, sym) in symbols.iter().enumerate() {
+        let escaped = sym.replace('\\', "\\\\").replace('"', "\\\"");
+        key_table_entries.push(format!("\"{}\"", escaped));
+        if !sym.starts_with('@') {
+            s2s_entries.push(format!("\"{}\":{}", escaped, i));
+        }
+    }
+    format!(
+        r#"{{"key_table":[{}],"initial_symbol_count":{},"flag_state_size":0,"length":{},"string_to_symbol":{{{}}},"operations":{{}},"identity_symbol":null,"unknown_symbol":null}}"#,
+        key_table_entries.join(","),
+        symbols.len(),
+        symbols.len(),
+        s2s_entries.join(","),
+    )
+}
+
+fn write_thfst(dir: &Path, alphabet_json: &str, index_data: &[u8], trans_data: &[u8]) {
+    std::fs::write(dir.join("alphabet"), alphabet_json).unwrap();
+    std::fs::write(dir.join("index"), index_data).unwrap();
+    std::fs::write(dir.join("transition"), trans_data).unwrap();
+}
+
+fn write_empties(buf: &mut Vec<u8>, n: usize) {
+    for _ in 0..n {
+        write_index_empty(buf);
+    }
+}
+
+/// Tiny hyphenator FST for the words (ab)+, writing `^` between the
+/// syllables for free, or after an `a` at weight 1. Going on to the next
+/// syllable without a mark costs 0.5.
+///
+/// ```text
+/// Alphabet: [eps, a, b, ^]    (n = 4 symbols)
+/// Slots per state: n + 1 = 5
+///
+/// (S0)--a/a-->(S1)--b/b-->(S2) FINAL--eps/^-->(S3)--a/a-->(S1)
+///              |     ^     |
+///        eps/^ |     |     `--a/a::0.5-->(S1)
+///          ::1 v     |
+///             (S4)---' b/b
+/// ```
+fn build_hyphenator(dir: &Path) {
+    let symbols = &["@_EPSILON_SYMBOL_@", "a", "b", "^"];
+
+    let mut idx = Vec::new();
+
+    // S0 at idx 0
+    write_index_empty(&mut idx); // [0] not final
+    write_index_empty(&mut idx); // [1] eps slot
+    write_index_entry(&mut idx, 1, TARGET_TABLE); // [2] a -> trans[0]
+    write_empties(&mut idx, 2);
+
+    // S1 at idx 5
+    write_index_empty(&mut idx); // [5] not final
+    write_index_entry(&mut idx, 0, TARGET_TABLE + 2); // [6] eps -> trans[2]
+    write_index_empty(&mut idx); // [7] a
+    write_index_entry(&mut idx, 2, TARGET_TABLE + 4); // [8] b -> trans[4]
+    write_index_empty(&mut idx); // [9] ^
+
+    // S2 at idx 10
+    write_index_final(&mut idx, 0.0); // [10] final
+    write_index_entry(&mut idx, 0, TARGET_TABLE + 6); // [11] eps -> trans[6]
+    write_index_entry(&mut idx, 1, TARGET_TABLE + 8); // [12] a -> trans[8]
+    write_empties(&mut idx, 2);
+
+    // S3 at idx 15
+    write_index_empty(&mut idx); // [15] not final
+    write_index_empty(&mut idx); // [16] eps slot
+    write_index_entry(&mut idx, 1, TARGET_TABLE + 10); // [17] a -> trans[10]
+    write_empties(&mut idx, 2);
+
+    // S4 at idx 20
+    write_index_empty(&mut idx); // [20] not final
+    write_index_empty(&mut idx); // [21] eps slot
+    write_index_empty(&mut idx); // [22] a
+    write_index_entry(&mut idx, 2, TARGET_TABLE + 12); // [23] b -> trans[12]
+    write_index_empty(&mut idx); // [24] ^
+
+    let mut trans = Vec::new();
+    write_trans_entry(&mut trans, 1, 1, 5, 0.0); // [0] S0 a:a -> S1
+    write_trans_boundary(&mut trans); // [1]
+    write_trans_entry(&mut trans, 0, 3, 20, 1.0); // [2] S1 eps:^ -> S4
+    write_trans_boundary(&mut trans); // [3]
+    write_trans_entry(&mut trans, 2, 2, 10, 0.0); // [4] S1 b:b -> S2
+    write_trans_boundary(&mut trans); // [5]
+    write_trans_entry(&mut trans, 0, 3, 15, 0.0); // [6] S2 eps:^ -> S3
+    write_trans_boundary(&mut trans); // [7]
+    write_trans_entry(&mut trans, 1, 1, 5, 0.5); // [8] S2 a:a -> S1
+    write_trans_boundary(&mut trans); // [9]
+    write_trans_entry(&mut trans, 1, 1, 5, 0.0); // [10] S3 a:a -> S1
+    write_trans_boundary(&mut trans); // [11]
+    write_trans_entry(&mut trans, 2, 2, 10, 0.0); // [12] S4 b:b -> S2
+    write_trans_boundary(&mut trans); // [13]
+
+    write_thfst(dir, &build_alphabet_json(symbols), &idx, &trans);
+}
+
+fn hyphenator() -> (tempfile::TempDir, Hyphenator<MmapThfstTransducer>) {
+    let dir = tempfile::tempdir().unwrap();
+    build_hyphenator(dir.path());
+    let transducer = MmapThfstTransducer::from_path(&Fs, dir.path()).unwrap();
+    (dir, Hyphenator::new(transducer, "^"))
+}
+
+fn hyphenation(points: &[usize], weight: f32) -> Hyphenation {
+    Hyphenation {
+        points: points.to_vec(),
+        weight: Weight(weight),
+    }
+}
+
+#[test]
+fn hyphenations_are_ranked() {
+    let (_dir, hyphenator) = hyphenator();
+    let hyphenations = hyphenator.hyphenations("abab");
+    assert_eq!(
+        &hyphenations[..5],
+        [
+            hyphenation(&[2], 0.0),
+            hyphenation(&[], 0.5),
+            hyphenation(&[1, 2], 1.0),
+            hyphenation(&[2, 3], 1.0),
+            hyphenation(&[1], 1.5),
+        ]
+    );
+    // Every path writing the word is a hyphenation of it: each of the three
+    // points may be there or not.
+    assert_eq!(hyphenations.len(), 8);
+}
+
+#[test]
+fn points_take_their_best_weight() {
+    let (_dir, hyphenator) = hyphenator();
+    let point = |index, weight| HyphenationPoint {
+        index,
+        weight: Weight(weight),
+    };
+    assert_eq!(
+        hyphenator.hyphenate("abab"),
+        [point(2, 0.0), point(1, 1.0), point(3, 1.0)]
+    );
+    assert_eq!(hyphenator.hyphenate("ab"), [point(1, 1.0)]);
+}
+
+#[test]
+fn unknown_words_have_no_points() {
+    let (_dir, hyphenator) = hyphenator();
+    assert!(hyphenator.hyphenate("aba").is_empty());
+    assert!(hyphenator.hyphenate("abc").is_empty());
+    assert!(hyphenator.hyphenate("").is_empty());
+}
+
+#[test]
+fn case_variants_are_hyphenated() {
+    let (_dir, hyphenator) = hyphenator();
+    assert_eq!(hyphenator.hyphenate("Abab"), hyphenator.hyphenate("abab"));
+    assert_eq!(hyphenator.hyphenate("ABAB"), hyphenator.hyphenate("abab"));
+}
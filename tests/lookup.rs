@@ -0,0 +1,73 @@
+//! Lookup tests: `transducer::lookup` over the checked-in THFST fixtures
+//! (see tests/speller_integration.rs for how they are built).
+
+use std::path::Path;
+
+use divvun_fst::transducer::TransducerLoader;
+use divvun_fst::transducer::lookup::{LookupConfig, lookup, lookup_with_config};
+use divvun_fst::transducer::thfst::MmapThfstTransducer;
+use divvun_fst::types::Weight;
+use divvun_fst::vfs::Fs;
+
+fn load(name: &str) -> MmapThfstTransducer {
+    let path = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures")).join(name);
+    MmapThfstTransducer::from_path(&Fs, path).unwrap()
+}
+
+fn outputs(results: &[(smol_str::SmolStr, Weight)]) -> Vec<(&str, f32)> {
+    results.iter().map(|(o, w)| (o.as_str(), w.0)).collect()
+}
+
+#[test]
+fn epsilon_arcs_write_tags_cheapest_first() {
+    let eps = load("eps-lexicon.thfst");
+    assert_eq!(
+        outputs(&lookup(&eps, "cat")),
+        [("cat+N", 0.0), ("cat+V", 2.0)]
+    );
+    assert!(lookup(&eps, "ca").is_empty());
+    assert!(lookup(&eps, "cats").is_empty());
+}
+
+#[test]
+fn flag_diacritics_are_checked_and_not_written() {
+    let flag = load("flag-lexicon.thfst");
+    assert_eq!(outputs(&lookup(&flag, "cat")), [("cat", 0.0)]);
+    // only reachable through @P.f.1@ and @R.f.1@
+    assert_eq!(outputs(&lookup(&flag, "cart")), [("cart", 0.0)]);
+    // @R.f.1@ without @P.f.1@ before it
+    assert!(lookup(&flag, "rat").is_empty());
+}
+
+#[test]
+fn identity_arcs_read_foreign_characters() {
+    let identity = load("identity-lexicon.thfst");
+    assert_eq!(outputs(&lookup(&identity, "cát")), [("cát", 0.0)]);
+    assert_eq!(outputs(&lookup(&identity, "c😀t")), [("c😀t", 0.0)]);
+    assert!(lookup(&identity, "cát!").is_empty());
+
+    // Without wildcards a foreign character can't be read at all.
+    let eps = load("eps-lexicon.thfst");
+    assert!(lookup(&eps, "cát").is_empty());
+}
+
+#[test]
+fn results_are_limited() {
+    let eps = load("eps-lexicon.thfst");
+    let config = LookupConfig {
+        max_results: Some(1),
+        ..LookupConfig::default()
+    };
+    assert_eq!(
+        outputs(&lookup_with_config(&eps, "cat", &config)),
+        [("cat+N", 0.0)]
+    );
+    let config = LookupConfig {
+        max_weight: Some(Weight(1.0)),
+        ..LookupConfig::default()
+    };
+    assert_eq!(
+        outputs(&lookup_with_config(&eps, "cat", &config)),
+        [("cat+N", 0.0)]
+    );
+}
@@ -0,0 +1,143 @@
+//! Optimiser tests: fixtures and built error models run through
+//! `transducer::optimize`, which must keep what they accept and the best
+//! weight they accept it at.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use divvun_fst::speller::{HfstSpeller, Speller, SpellerConfig};
+use divvun_fst::transducer::convert::ConvertFile;
+use divvun_fst::transducer::errmodel::EditDistance;
+use divvun_fst::transducer::lookup::lookup;
+use divvun_fst::transducer::optimize::{OptimizeConfig, optimize};
+use divvun_fst::transducer::paths::{PathsConfig, paths};
+use divvun_fst::transducer::thfst::MmapThfstTransducer;
+use divvun_fst::transducer::{Transducer, TransducerLoader};
+use divvun_fst::vfs::Fs;
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures")).join(name)
+}
+
+/// The best weight of each output for `input`, to four places.
+fn best<T: Transducer>(transducer: &T, input: &str) -> BTreeMap<String, i64> {
+    let mut best = BTreeMap::new();
+    for (output, weight) in lookup(transducer, input) {
+        let weight = (weight.0 * 1e4).round() as i64;
+        best.entry(output.to_string())
+            .and_modify(|w: &mut i64| *w = (*w).min(weight))
+            .or_insert(weight);
+    }
+    best
+}
+
+#[test]
+fn lexicons_keep_their_paths() {
+    for name in ["lexicon.thfst", "eps-lexicon.thfst", "flag-lexicon.thfst"] {
+        let lexicon = MmapThfstTransducer::from_path(&Fs, fixture(name)).unwrap();
+        let (optimised, report) = optimize(&lexicon, &OptimizeConfig::default()).unwrap();
+        assert!(report.determinised, "{name}");
+        assert!(report.states_after <= report.states_before, "{name}");
+
+        for path in paths(&lexicon, PathsConfig::default()) {
+            let input = &path.input;
+            assert_eq!(
+                best(&optimised, input),
+                best(&lexicon, input),
+                "{name} {input}"
+            );
+        }
+        assert_eq!(
+            paths(&optimised, PathsConfig::default()).count(),
+            paths(&lexicon, PathsConfig::default()).count(),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn error_models_correct_the_same() {
+    let model = EditDistance::default()
+        .error_model(&["a", "b", "c"])
+        .unwrap();
+    for determinise in [true, false] {
+        let config = OptimizeConfig {
+            determinise,
+            ..OptimizeConfig::default()
+        };
+        let (optimised, report) = optimize(&model, &config).unwrap();
+        assert_eq!(report.determinised, determinise);
+        assert!(report.states_after <= report.states_before, "{report:?}");
+        if !determinise {
+            assert!(report.arcs_after <= report.arcs_before, "{report:?}");
+        }
+
+        // Written out and read back, as the tool does.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("errmodel");
+        MmapThfstTransducer::convert_file(&optimised, &path).unwrap();
+        let read = MmapThfstTransducer::from_path(&Fs, path.with_extension("thfst")).unwrap();
+        for input in ["", "a", "ab", "cab", "abca"] {
+            assert_eq!(best(&read, input), best(&model, input), "{input}");
+        }
+    }
+}
+
+#[test]
+fn compact_wildcards_are_merged() {
+    for name in [
+        "wildcard-compact-mutator.thfst",
+        "unknown-out-compact-mutator.thfst",
+    ] {
+        let mutator = MmapThfstTransducer::from_path(&Fs, fixture(name)).unwrap();
+        let (optimised, report) = optimize(&mutator, &OptimizeConfig::default()).unwrap();
+        assert_eq!(
+            (report.states_before, report.states_after),
+            (3, 1),
+            "{name}"
+        );
+        for input in ["a", "ab", "x", "zz"] {
+            assert_eq!(
+                best(&optimised, input),
+                best(&mutator, input),
+                "{name} {input}"
+            );
+        }
+    }
+}
+
+#[test]
+fn optimised_speller_suggests_the_same() {
+    let load = |name| MmapThfstTransducer::from_path(&Fs, fixture(name)).unwrap();
+    let config = OptimizeConfig::default();
+    let (mutator, _) = optimize(&load("mutator.thfst"), &config).unwrap();
+    let (lexicon, _) = optimize(&load("lexicon.thfst"), &config).unwrap();
+    let optimised = HfstSpeller::new(mutator, lexicon);
+    let original = HfstSpeller::new(load("mutator.thfst"), load("lexicon.thfst"));
+
+    let config = SpellerConfig {
+        reweight: None,
+        recase: false,
+        ..SpellerConfig::default()
+    };
+    for word in ["kat", "cta", "car", "ca", "dog"] {
+        assert_eq!(
+            suggestions(Arc::clone(&optimised), word, &config),
+            suggestions(Arc::clone(&original), word, &config),
+            "{word}"
+        );
+    }
+}
+
+fn suggestions<T: Transducer + Send, U: Transducer + Send>(
+    speller: Arc<HfstSpeller<T, U>>,
+    word: &str,
+    config: &SpellerConfig,
+) -> Vec<(String, i64)> {
+    speller
+        .suggest_with_config(word, config)
+        .iter()
+        .map(|s| (s.value.to_string(), (s.weight().0 * 1e4).round() as i64))
+        .collect()
+}
@@ -0,0 +1,150 @@
+//! Accepting-path tests: `transducer::paths` over the checked-in THFST
+//! fixtures (see tests/speller_integration.rs for how they are built).
+
+use std::path::Path;
+
+use divvun_fst::transducer::TransducerLoader;
+use divvun_fst::transducer::paths::{AcceptingPath, PathOrder, PathsConfig, paths};
+use divvun_fst::transducer::thfst::MmapThfstTransducer;
+use divvun_fst::types::Weight;
+use divvun_fst::vfs::Fs;
+
+fn load(name: &str) -> MmapThfstTransducer {
+    let path = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures")).join(name);
+    MmapThfstTransducer::from_path(&Fs, path).unwrap()
+}
+
+fn triples(paths: impl Iterator<Item = AcceptingPath>) -> Vec<(String, String, f32)> {
+    paths
+        .map(|p| (p.input.to_string(), p.output.to_string(), p.weight.0))
+        .collect()
+}
+
+fn triple(input: &str, output: &str, weight: f32) -> (String, String, f32) {
+    (input.to_string(), output.to_string(), weight)
+}
+
+#[test]
+fn epsilon_arcs_write_to_the_output_side_only() {
+    let eps = load("eps-lexicon.thfst");
+    assert_eq!(
+        triples(paths(&eps, PathsConfig::default())),
+        [triple("cat", "cat+N", 0.0), triple("cat", "cat+V", 2.0)]
+    );
+}
+
+#[test]
+fn flag_diacritics_are_checked_and_not_written() {
+    let flag = load("flag-lexicon.thfst");
+    let words = triples(paths(&flag, PathsConfig::default()));
+    assert_eq!(
+        words,
+        [
+            triple("cat", "cat", 0.0),
+            triple("car", "car", 0.0),
+            // only reachable through @P.f.1@ and @R.f.1@
+            triple("cart", "cart", 0.0),
+        ]
+    );
+    // @R.f.1@ without @P.f.1@ before it
+    assert!(!words.iter().any(|(input, _, _)| input == "rat"));
+}
+
+#[test]
+fn wildcards_are_written_as_their_symbols() {
+    let identity = load("identity-lexicon.thfst");
+    let words = triples(paths(&identity, PathsConfig::default()));
+    assert!(words.contains(&triple(
+        "c@_IDENTITY_SYMBOL_@t",
+        "c@_IDENTITY_SYMBOL_@t",
+        0.0
+    )));
+}
+
+#[test]
+fn paths_are_limited() {
+    let eps = load("eps-lexicon.thfst");
+    let config = PathsConfig {
+        max_results: Some(1),
+        ..PathsConfig::default()
+    };
+    assert_eq!(triples(paths(&eps, config)), [triple("cat", "cat+N", 0.0)]);
+    let config = PathsConfig {
+        max_weight: Some(Weight(1.0)),
+        ..PathsConfig::default()
+    };
+    assert_eq!(triples(paths(&eps, config)), [triple("cat", "cat+N", 0.0)]);
+    // "cat" is three transitions, and its tags a fourth.
+    let config = PathsConfig {
+        max_depth: 3,
+        ..PathsConfig::default()
+    };
+    assert!(paths(&eps, config).next().is_none());
+}
+
+#[test]
+fn depth_first_gives_every_path() {
+    let flag = load("flag-lexicon.thfst");
+    let config = PathsConfig {
+        order: PathOrder::DepthFirst,
+        ..PathsConfig::default()
+    };
+    // The same words as best-first, some once for each of their paths
+    // through the flags.
+    let mut words = triples(paths(&flag, config));
+    words.sort_by(|a, b| a.0.cmp(&b.0));
+    words.dedup();
+    assert_eq!(
+        words,
+        [
+            triple("car", "car", 0.0),
+            triple("cart", "cart", 0.0),
+            triple("cat", "cat", 0.0),
+        ]
+    );
+    // "car" ends where "cart" goes on, and comes first.
+    let config = PathsConfig {
+        order: PathOrder::DepthFirst,
+        ..PathsConfig::default()
+    };
+    let inputs: Vec<_> = triples(paths(&flag, config))
+        .into_iter()
+        .map(|(input, _, _)| input)
+        .collect();
+    let car = inputs.iter().position(|w| w == "car").unwrap();
+    let cart = inputs.iter().position(|w| w == "cart").unwrap();
+    assert!(car < cart, "{inputs:?}");
+}
+
+#[test]
+fn random_samples_follow_the_seed() {
+    let eps = load("eps-lexicon.thfst");
+    let sample = |seed| {
+        let config = PathsConfig {
+            max_results: Some(50),
+            order: PathOrder::Random { seed },
+            ..PathsConfig::default()
+        };
+        triples(paths(&eps, config))
+    };
+    let first = sample(7);
+    assert_eq!(first.len(), 50);
+    assert_eq!(first, sample(7));
+    // Only accepting paths, and with 50 walks, both of them.
+    let noun = triple("cat", "cat+N", 0.0);
+    let verb = triple("cat", "cat+V", 2.0);
+    assert!(first.iter().all(|path| *path == noun || *path == verb));
+    assert!(first.contains(&noun) && first.contains(&verb));
+}
+
+#[test]
+fn random_samples_end_when_nothing_is_accepted() {
+    let eps = load("eps-lexicon.thfst");
+    let config = PathsConfig {
+        max_depth: 3,
+        max_results: Some(10),
+        order: PathOrder::Random { seed: 1 },
+        ..PathsConfig::default()
+    };
+    assert!(paths(&eps, config).next().is_none());
+}
@@ -0,0 +1,98 @@
+//! Rational operation tests: fixtures combined with `transducer::rational`,
+//! written out as THFST and read back.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use divvun_fst::speller::{HfstSpeller, Speller, SpellerConfig};
+use divvun_fst::transducer::convert::ConvertFile;
+use divvun_fst::transducer::lookup::lookup;
+use divvun_fst::transducer::paths::{PathsConfig, paths};
+use divvun_fst::transducer::rational::{compose, concatenate, intersect, union};
+use divvun_fst::transducer::thfst::{MemoryThfstTransducer, MmapThfstTransducer};
+use divvun_fst::transducer::{Transducer, TransducerLoader};
+use divvun_fst::vfs::Fs;
+
+fn load(name: &str) -> MmapThfstTransducer {
+    let path = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures")).join(name);
+    MmapThfstTransducer::from_path(&Fs, path).unwrap()
+}
+
+fn all<T: Transducer>(transducer: &T) -> Vec<(String, String, f32)> {
+    let mut all: Vec<_> = paths(transducer, PathsConfig::default())
+        .map(|p| (p.input.to_string(), p.output.to_string(), p.weight.0))
+        .collect();
+    all.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    all
+}
+
+fn written(transducer: &MemoryThfstTransducer) -> MmapThfstTransducer {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("combined");
+    MmapThfstTransducer::convert_file(transducer, &path).unwrap();
+    MmapThfstTransducer::from_path(&Fs, path.with_extension("thfst")).unwrap()
+}
+
+#[test]
+fn union_keeps_flagged_paths() {
+    let lexicon = load("lexicon.thfst");
+    let flagged = load("flag-lexicon.thfst");
+    let union = written(&union(&lexicon, &flagged).unwrap());
+
+    let mut expected = all(&lexicon);
+    expected.extend(all(&flagged));
+    // Each pair once, at its cheapest.
+    expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    expected.dedup_by(|a, b| (&a.0, &a.1) == (&b.0, &b.1));
+    assert_eq!(all(&union), expected);
+}
+
+#[test]
+fn flagged_lexicon_intersects_with_itself() {
+    let flagged = load("flag-lexicon.thfst");
+    let both = written(&intersect(&flagged, &flagged).unwrap());
+    assert_eq!(all(&both), all(&flagged));
+}
+
+#[test]
+fn concatenation_follows_flags_across() {
+    let flagged = load("flag-lexicon.thfst");
+    let lexicon = load("lexicon.thfst");
+    let both = written(&concatenate(&flagged, &lexicon).unwrap());
+    let first = all(&flagged);
+    let second = all(&lexicon);
+    assert_eq!(all(&both).len(), first.len() * second.len());
+    let (word, _, _) = &first[0];
+    let (next, _, _) = &second[0];
+    assert_eq!(lookup(&both, &format!("{word}{next}")).len(), 1);
+}
+
+#[test]
+fn composed_error_model_corrects_like_the_speller() {
+    let mutator = load("mutator.thfst");
+    let lexicon = load("lexicon.thfst");
+    let composed = written(&compose(&mutator, &lexicon).unwrap());
+    let speller = HfstSpeller::new(mutator, lexicon);
+    let config = SpellerConfig {
+        reweight: None,
+        recase: false,
+        ..SpellerConfig::default()
+    };
+
+    for word in ["kat", "cta", "car"] {
+        let mut best = BTreeMap::new();
+        for (output, weight) in lookup(&composed, word) {
+            let weight = (weight.0 * 1e4).round() as i64;
+            best.entry(output.to_string())
+                .and_modify(|w: &mut i64| *w = (*w).min(weight))
+                .or_insert(weight);
+        }
+        let suggested: BTreeMap<String, i64> = Arc::clone(&speller)
+            .suggest_with_config(word, &config)
+            .iter()
+            .map(|s| (s.value.to_string(), (s.weight().0 * 1e4).round() as i64))
+            .collect();
+        assert_eq!(best, suggested, "{word}");
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 
+use divvun_fst::speller::multi::MultiSpeller;
 use divvun_fst::speller::{HfstSpeller, Speller, SpellerConfig};
 use divvun_fst::transducer::Transducer;
 use divvun_fst::transducer::TransducerLoader;
@@ -2392,6 +2393,71 @@ fn eps_speller() -> Arc<HfstSpeller<MmapThfstTransducer, MmapThfstTransducer>> {
     )
 }
 
+/// Lexicon with two paths analysing "cat" as "cat+N": ε→+N (w=0), and
+/// ε→@P.f.1@ ε→+N (w=1), whose symbols differ only in the flag.
+fn build_dup_lexicon(dir: &Path) {
+    // eps=0, c=1, a=2, t=3, +N=4, @P.f.1@=5
+    let symbols = &["@_EPSILON_SYMBOL_@", "c", "a", "t", "+N", "@P.f.1@"];
+    let flags = &[(5, "PositiveSet", 0u16, 1i16)];
+    let alphabet = build_alphabet_json_full(symbols, None, None, flags, 1);
+    let n = symbols.len(); // 6 → 7 entries per state
+
+    let mut idx = Vec::new();
+
+    // State 0 (idx 0): start. c→trans[0]
+    write_index_empty(&mut idx);
+    write_index_empty(&mut idx);
+    write_index_entry(&mut idx, 1, TARGET_TABLE + 0); // c
+    write_empties(&mut idx, n - 2);
+
+    // State 1 (idx 7): after "c". a→trans[2]
+    write_index_empty(&mut idx);
+    write_index_empty(&mut idx);
+    write_index_empty(&mut idx);
+    write_index_entry(&mut idx, 2, TARGET_TABLE + 2); // a
+    write_empties(&mut idx, n - 3);
+
+    // State 2 (idx 14): after "ca". t→trans[3]
+    write_index_empty(&mut idx);
+    write_index_empty(&mut idx);
+    write_index_empty(&mut idx);
+    write_index_empty(&mut idx);
+    write_index_entry(&mut idx, 3, TARGET_TABLE + 3); // t
+    write_empties(&mut idx, n - 4);
+
+    // State 3 (idx 21): after "cat". eps slot → trans[4]
+    write_index_empty(&mut idx);
+    write_index_entry(&mut idx, 0, TARGET_TABLE + 4);
+    write_empties(&mut idx, n - 1);
+
+    // State 4 (idx 28): "cat+N" FINAL w=0
+    write_index_final(&mut idx, 0.0);
+    write_empties(&mut idx, n);
+
+    // State 5 (idx 35): after the flag. eps slot → trans[1]
+    write_index_empty(&mut idx);
+    write_index_entry(&mut idx, 0, TARGET_TABLE + 1);
+    write_empties(&mut idx, n - 1);
+
+    let mut tr = Vec::new();
+    write_trans_entry(&mut tr, 1, 1, 7, 0.0); // [0] c→c → state 1
+    write_trans_entry(&mut tr, 0, 4, 28, 1.0); // [1] ε→+N → state 4 (after the flag)
+    write_trans_entry(&mut tr, 2, 2, 14, 0.0); // [2] a→a → state 2
+    write_trans_entry(&mut tr, 3, 3, 21, 0.0); // [3] t→t → state 3
+    write_trans_entry(&mut tr, 0, 4, 28, 0.0); // [4] ε→+N → state 4
+    write_trans_entry(&mut tr, 0, 5, 35, 0.0); // [5] ε→@P.f.1@ → state 5
+
+    write_thfst(dir, &alphabet, &idx, &tr);
+}
+
+fn dup_speller() -> Arc<HfstSpeller<MmapThfstTransducer, MmapThfstTransducer>> {
+    let base = fixtures_dir();
+    load_speller(
+        &base.join("dup-lexicon.thfst"),
+        &base.join("eps-mutator.thfst"),
+    )
+}
+
 // ===========================================================================
 // Flag diacritic tests
 // ===========================================================================
@@ -2730,6 +2796,78 @@ fn test_eps_analyze_noun_before_verb() {
     );
 }
 
+#[test]
+fn test_eps_analyze_structured() {
+    let s = eps_speller();
+    let analyses = s.clone().analyze("cat");
+    let flat: Vec<String> = s
+        .clone()
+        .analyze_input("cat")
+        .iter()
+        .map(|a| a.value.to_string())
+        .collect();
+    let structured: Vec<String> = analyses.iter().map(|a| a.to_string()).collect();
+    assert_eq!(
+        structured, flat,
+        "structured analyses should match the flat ones"
+    );
+    for analysis in &analyses {
+        assert_eq!(analysis.lemma, "cat", "lemma of {analysis:?}");
+        assert!(!analysis.is_compound());
+    }
+    assert_eq!(analyses[0].tags, ["+N"]);
+    assert!(analyses.iter().any(|a| a.has_tag("+V")));
+    assert!(s.clone().analyze("kat").is_empty());
+    assert!(s.clone().analyze("").is_empty());
+}
+
+#[test]
+fn test_analyze_same_analysis_once() {
+    let s = dup_speller();
+    // Both paths give "cat+N": it is given once, at the better weight
+    let analyses = s.clone().analyze("cat");
+    assert_eq!(analyses.len(), 1, "{analyses:?}");
+    assert_eq!(analyses[0].to_string(), "cat+N");
+    assert_eq!(analyses[0].weight, Weight(0.0));
+}
+
+#[test]
+fn test_eps_lemmatize() {
+    let s = eps_speller();
+    // +N (w=0) and +V (w=2) share a lemma: it is given once, at the best weight
+    assert_eq!(s.clone().lemmatize("cat"), [("cat".into(), Weight(0.0))]);
+    // case variants are lemmatized too, unless recasing is off
+    assert_eq!(s.clone().lemmatize("Cat"), [("cat".into(), Weight(0.0))]);
+    assert_eq!(s.clone().lemmatize("CAT"), [("cat".into(), Weight(0.0))]);
+    assert!(
+        s.clone()
+            .lemmatize_with_config("Cat", &raw_config())
+            .is_empty()
+    );
+    assert!(s.clone().lemmatize("kat").is_empty());
+}
+
+#[test]
+fn test_eps_suggest_preserving_morphology() {
+    let s = eps_speller();
+    let cfg = SpellerConfig {
+        morphology: Some(Default::default()),
+        ..SpellerConfig::default()
+    };
+    let plain = s.clone().suggest("cat");
+    let ranked = s.clone().suggest_with_config("cat", &cfg);
+    let values = |suggs: &[divvun_fst::speller::suggestion::Suggestion]| {
+        suggs
+            .iter()
+            .map(|x| (x.value.to_string(), x.weight))
+            .collect::<Vec<_>>()
+    };
+    // "cat" is the only candidate, so its tags are the ones inferred and it
+    // keeps its weight
+    assert_eq!(values(&ranked), values(&plain));
+    assert_eq!(values(&ranked), [("cat".to_string(), Weight(0.0))]);
+}
+
 // ===========================================================================
 // Case handling and weight limits around reweighting
 // ===========================================================================
@@ -2834,6 +2972,98 @@ fn test_n_best_uses_post_reweight_order() {
     );
 }
 
+// ===========================================================================
+// Several languages at once
+// ===========================================================================
+
+// Two languages built from the fixtures: "a" is the main lexicon (cat, car,
+// cart, care, cär), "b" the unknown-output one (cat, cät, cöt). "cüt" is wrong
+// in both, and both can correct it — "a" to "cat" for 8, "b" to "cät"/"cöt"
+// for 2.
+fn multi_speller() -> Arc<MultiSpeller> {
+    MultiSpeller::from_spellers(vec![
+        ("a".into(), test_speller() as Arc<dyn Speller + Send + Sync>),
+        (
+            "b".into(),
+            unknown_out_speller("unknown-out-compact-mutator.thfst"),
+        ),
+    ])
+}
+
+fn sources(suggs: &[divvun_fst::speller::suggestion::Suggestion]) -> Vec<(String, Option<String>)> {
+    suggs
+        .iter()
+        .map(|s| (s.value.to_string(), s.source().map(str::to_string)))
+        .collect()
+}
+
+#[test]
+fn test_multi_accepts_words_of_either_language() {
+    let multi = multi_speller();
+    for word in ["cat", "care", "cär", "cät", "cöt"] {
+        assert!(multi.clone().is_correct(word), "{word} should be accepted");
+    }
+    assert!(!multi.clone().is_correct("cüt"));
+    assert_eq!(
+        multi.accepted_by("cat", &raw_config()),
+        ["a", "b"],
+        "a word both languages share is accepted by both"
+    );
+}
+
+#[test]
+fn test_multi_suggests_from_the_language_of_the_context() {
+    let multi = multi_speller();
+
+    let suggs = multi
+        .clone()
+        .suggest_in_context("cüt", "care car cart", &raw_config());
+    assert_eq!(
+        sources(&suggs)[0],
+        ("cat".to_string(), Some("a".to_string()))
+    );
+    assert!(
+        suggs.iter().all(|s| s.source() == Some("a")),
+        "suggestions must all come from one language: {:?}",
+        sources(&suggs)
+    );
+
+    let suggs = multi.suggest_in_context("cüt", "cöt cät", &raw_config());
+    assert!(
+        suggs.iter().all(|s| s.source() == Some("b")),
+        "{:?}",
+        sources(&suggs)
+    );
+    assert_eq!(suggs[0].weight(), Weight(2.0));
+}
+
+// Without context, and with context both languages accept equally ("cat" is in
+// both), the cheapest correction decides.
+#[test]
+fn test_multi_without_context_takes_the_cheapest_correction() {
+    let multi = multi_speller();
+    for context in ["", "cat"] {
+        let suggs = multi
+            .clone()
+            .suggest_in_context("cüt", context, &raw_config());
+        assert_eq!(suggs[0].source(), Some("b"), "context {context:?}");
+        assert_eq!(suggs[0].weight(), Weight(2.0));
+    }
+    let suggs = multi.suggest_with_config("cüt", &raw_config());
+    assert_eq!(suggs[0].source(), Some("b"));
+}
+
+// "cäd" is only correctable by "a"; a context pointing at "b" must not leave
+// the writer with nothing.
+#[test]
+fn test_multi_falls_back_when_the_likeliest_language_has_nothing() {
+    let suggs = multi_speller().suggest_in_context("cäd", "cöt cät", &raw_config());
+    assert_eq!(
+        sources(&suggs)[0],
+        ("cat".to_string(), Some("a".to_string()))
+    );
+}
+
 // ===========================================================================
 // Fixture regeneration
 // ===========================================================================
@@ -2868,6 +3098,13 @@ fn rebuild_fixtures() {
             "eps-lexicon.thfst",
             "eps-mutator.thfst",
         ),
+        // The eps mutator is rewritten byte-identically, for the second time.
+        (
+            build_dup_lexicon,
+            build_eps_mutator,
+            "dup-lexicon.thfst",
+            "eps-mutator.thfst",
+        ),
         (
             build_reorder_lexicon,
             build_reorder_mutator,
@@ -0,0 +1,133 @@
+//! UAX #29 word boundaries against the Unicode conformance test file, and
+//! against ICU.
+//!
+//! `tests/fixtures/WordBreakTest.txt` is the file of that name from the
+//! `auxiliary` directory of the Unicode Character Database, copied unmodified
+//! by `support/gen-break-tables.py --version` together with the tables in
+//! `src/tokenizer/tables`, so the two always come from the same Unicode
+//! version. `tests/fixtures/word-break-icu.txt`, in the same format, has the
+//! boundaries ICU finds in many more strings; `support/gen-word-break-icu.mjs`
+//! writes it.
+
+use std::path::Path;
+
+use divvun_fst::tokenizer::word::WordBounds;
+
+/// One test case: the test string and its expected word boundaries.
+struct Case {
+    line: usize,
+    text: String,
+    words: Vec<String>,
+}
+
+/// Parse lines like `÷ 0061 × 0308 ÷ 0020 ÷ # ÷ [0.2] ...`, where `÷`
+/// marks a boundary and `×` marks none.
+fn parse(data: &str) -> Vec<Case> {
+    let mut cases = vec![];
+    for (index, line) in data.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut text = String::new();
+        let mut words = vec![];
+        let mut word = String::new();
+        for field in line.split_whitespace() {
+            match field {
+                "÷" => {
+                    if !word.is_empty() {
+                        words.push(std::mem::take(&mut word));
+                    }
+                }
+                "×" => {}
+                hex => {
+                    let ch = u32::from_str_radix(hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .unwrap_or_else(|| panic!("line {}: bad code point {hex}", index + 1));
+                    text.push(ch);
+                    word.push(ch);
+                }
+            }
+        }
+        cases.push(Case {
+            line: index + 1,
+            text,
+            words,
+        });
+    }
+    cases
+}
+
+/// Check the tokenizer, forwards and backwards, against every case in `data`.
+fn check(data: &str) {
+    let cases = parse(data);
+    assert!(!cases.is_empty());
+
+    let mut failures = vec![];
+    for case in &cases {
+        let forward: Vec<&str> = WordBounds::new(&case.text).collect();
+        let mut backward: Vec<&str> = WordBounds::new(&case.text).rev().collect();
+        backward.reverse();
+        if forward != case.words {
+            failures.push(format!(
+                "line {}: forward {forward:?}, expected {:?}",
+                case.line, case.words
+            ));
+        }
+        if backward != case.words {
+            failures.push(format!(
+                "line {}: backward {backward:?}, expected {:?}",
+                case.line, case.words
+            ));
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} of {} cases failed:\n{}",
+        failures.len(),
+        cases.len(),
+        failures.join("\n")
+    );
+}
+
+fn fixture(name: &str) -> Option<String> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    std::fs::read_to_string(path).ok()
+}
+
+#[test]
+fn word_break_test() {
+    // The UCD's file is only there once fetched with `--version`; the tables
+    // may have been generated offline, from a local copy without it.
+    let Some(data) = fixture("WordBreakTest.txt") else {
+        eprintln!(
+            "tests/fixtures/WordBreakTest.txt is missing; \
+             run support/gen-break-tables.py --version"
+        );
+        return;
+    };
+    check(&data);
+}
+
+#[test]
+fn icu_word_break_test() {
+    check(&fixture("word-break-icu.txt").expect("tests/fixtures/word-break-icu.txt"));
+}
+
+#[test]
+fn parses_the_test_file_format() {
+    let cases = parse(
+        "# WordBreakTest-16.0.0.txt\n\
+         ÷ 0061 × 0308 ÷ 0020 ÷\t#  ÷ [0.2] LATIN SMALL LETTER A (ALetter) ...\n\
+         \n\
+         ÷ 0020 × 0020 ÷\t#  ÷ [0.2] SPACE (WSegSpace) × [3.4] ...\n",
+    );
+    assert_eq!(cases.len(), 2);
+    assert_eq!(cases[0].line, 2);
+    assert_eq!(cases[0].text, "a\u{308} ");
+    assert_eq!(cases[0].words, ["a\u{308}", " "]);
+    assert_eq!(cases[1].words, ["  "]);
+}
@@ -0,0 +1,88 @@
+//! Word list tests: a lexicon compiled from tests/fixtures/words.tsv with
+//! `transducer::wordlist` and an `transducer::errmodel::EditDistance` error
+//! model make a speller.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use divvun_fst::speller::{HfstSpeller, Speller};
+use divvun_fst::transducer::TransducerLoader;
+use divvun_fst::transducer::convert::ConvertFile;
+use divvun_fst::transducer::errmodel::EditDistance;
+use divvun_fst::transducer::lookup::lookup;
+use divvun_fst::transducer::paths::{PathsConfig, paths};
+use divvun_fst::transducer::thfst::MmapThfstTransducer;
+use divvun_fst::transducer::wordlist::WordList;
+use divvun_fst::vfs::Fs;
+
+fn words() -> WordList {
+    let path = Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/words.tsv"
+    ));
+    WordList::parse(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn acceptor_accepts_the_words_commonest_first() {
+    let list = words();
+    let acceptor = list.acceptor().unwrap();
+    let accepted: Vec<_> = paths(&acceptor, PathsConfig::default())
+        .map(|p| p.output.to_string())
+        .collect();
+    assert_eq!(
+        accepted,
+        ["the", "cat", "car", "card", "cart", "cast", "bat"]
+    );
+}
+
+#[test]
+fn word_list_makes_a_speller() {
+    let list = words();
+    let errmodel = EditDistance::default()
+        .error_model(&list.alphabet())
+        .unwrap();
+    let speller = HfstSpeller::new(errmodel, list.acceptor().unwrap());
+
+    assert!(Arc::clone(&speller).is_correct("card"));
+    assert!(!Arc::clone(&speller).is_correct("cas"));
+    let suggestions: Vec<_> = speller
+        .suggest("cas")
+        .into_iter()
+        .map(|s| s.value.to_string())
+        .collect();
+    // One edit from each, the commonest first
+    assert_eq!(suggestions[..3], ["cat", "car", "cast"]);
+}
+
+// Decomposed, "cát" is a letter and a combining mark, which the speller
+// reads as one character.
+#[test]
+fn combining_marks_stay_on_their_letters() {
+    let list = WordList::parse("ca\u{301}t\t2\ncat\n").unwrap();
+    assert_eq!(list.alphabet(), ["a", "a\u{301}", "c", "t"]);
+    let acceptor = list.acceptor().unwrap();
+    assert_eq!(
+        lookup(&acceptor, "ca\u{301}t"),
+        [("ca\u{301}t".into(), list.weight("ca\u{301}t").unwrap())]
+    );
+    assert!(lookup(&acceptor, "ca").is_empty());
+}
+
+#[test]
+fn acceptor_writes_thfst() {
+    let list = words();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("acceptor.default");
+    MmapThfstTransducer::convert_file(&list.acceptor().unwrap(), &path).unwrap();
+    let acceptor = MmapThfstTransducer::from_path(&Fs, path.with_extension("thfst")).unwrap();
+    let weights: Vec<_> = paths(&acceptor, PathsConfig::default())
+        .map(|p| (p.output.to_string(), p.weight))
+        .collect();
+    for (word, weight) in weights {
+        assert!(
+            (weight.0 - list.weight(&word).unwrap().0).abs() < 1e-5,
+            "{word}"
+        );
+    }
+}